@@ -0,0 +1,68 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use libdeflater::{CompressionLvl, Compressor};
+use rsm::lib::img::png::image::png_image::PNGImage;
+use std::hint::black_box;
+
+/// Encode a chunk with its length, type and CRC
+fn chunk(r#type: &[u8; 4], data: &[u8]) -> Vec<u8> {
+  let mut bytes: Vec<u8> = (data.len() as u32).to_be_bytes().to_vec();
+  bytes.extend_from_slice(r#type);
+  bytes.extend_from_slice(data);
+
+  let mut hasher = crc32fast::Hasher::new();
+  hasher.update(r#type);
+  hasher.update(data);
+  bytes.extend_from_slice(&hasher.finalize().to_be_bytes());
+  bytes
+}
+
+/// Build a 1024x1024 RGBA PNG of gradients and noise, whose image data is
+/// split into chunks of the given size
+fn sample_png(size: usize) -> Vec<u8> {
+  let (width, height): (u32, u32) = (1024, 1024);
+  let mut state: u32 = 0x2545_f491;
+  let mut raw: Vec<u8> = Vec::with_capacity((width * 4 + 1) as usize * height as usize);
+  for y in 0..height {
+    raw.push(1);
+    for x in 0..width {
+      state ^= state << 13;
+      state ^= state >> 17;
+      state ^= state << 5;
+      let noise: u8 = (state % 8) as u8;
+      raw.extend_from_slice(&[(x / 4) as u8 ^ noise, (y / 4) as u8, noise, 255]);
+    }
+  }
+
+  let mut compressor: Compressor = Compressor::new(CompressionLvl::default());
+  let mut data: Vec<u8> = vec![0u8; compressor.zlib_compress_bound(raw.len())];
+  let length: usize = compressor.zlib_compress(&raw, &mut data).unwrap();
+  data.truncate(length);
+
+  let mut ihdr: Vec<u8> = width.to_be_bytes().to_vec();
+  ihdr.extend_from_slice(&height.to_be_bytes());
+  ihdr.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+  let mut png: Vec<u8> = vec![0x89, 0x50, 0x4e, 0x47, 0xd, 0xa, 0x1a, 0xa];
+  png.extend(chunk(b"IHDR", &ihdr));
+  for part in data.chunks(size) {
+    png.extend(chunk(b"IDAT", part));
+  }
+  png.extend(chunk(b"IEND", &[]));
+  png
+}
+
+/// Compare decoding image data held in a single IDAT chunk with data split
+/// into 8 KiB chunks, as libpng writes it
+fn decode(criterion: &mut Criterion) {
+  let mut group = criterion.benchmark_group("decode");
+  for (name, size) in [("single_idat", usize::MAX), ("split_idat_8k", 8192)] {
+    let png: Vec<u8> = sample_png(size);
+    group.bench_function(name, |bencher| {
+      bencher.iter(|| PNGImage::read_bytes(black_box(&png)).unwrap())
+    });
+  }
+  group.finish();
+}
+
+criterion_group!(benches, decode);
+criterion_main!(benches);
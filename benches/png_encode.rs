@@ -0,0 +1,71 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use libdeflater::{CompressionLvl, Compressor};
+use rsm::lib::img::png::{encode::png_encode_options::EncodeOptions, image::png_image::PNGImage};
+use std::hint::black_box;
+
+/// Encode a chunk with its length, type and CRC
+fn chunk(r#type: &[u8; 4], data: &[u8]) -> Vec<u8> {
+  let mut bytes: Vec<u8> = (data.len() as u32).to_be_bytes().to_vec();
+  bytes.extend_from_slice(r#type);
+  bytes.extend_from_slice(data);
+
+  let mut hasher = crc32fast::Hasher::new();
+  hasher.update(r#type);
+  hasher.update(data);
+  bytes.extend_from_slice(&hasher.finalize().to_be_bytes());
+  bytes
+}
+
+/// Decode a 2048x2048 RGBA image of gradients and noise
+fn sample_image() -> PNGImage {
+  let (width, height): (u32, u32) = (2048, 2048);
+  let mut state: u32 = 0x2545_f491;
+  let mut raw: Vec<u8> = Vec::with_capacity((width * 4 + 1) as usize * height as usize);
+  for y in 0..height {
+    raw.push(0);
+    for x in 0..width {
+      state ^= state << 13;
+      state ^= state >> 17;
+      state ^= state << 5;
+      let noise: u8 = (state % 8) as u8;
+      raw.extend_from_slice(&[(x / 8) as u8 ^ noise, (y / 8) as u8, noise, 255]);
+    }
+  }
+
+  let mut compressor: Compressor = Compressor::new(CompressionLvl::fastest());
+  let mut data: Vec<u8> = vec![0u8; compressor.zlib_compress_bound(raw.len())];
+  let length: usize = compressor.zlib_compress(&raw, &mut data).unwrap();
+  data.truncate(length);
+
+  let mut ihdr: Vec<u8> = width.to_be_bytes().to_vec();
+  ihdr.extend_from_slice(&height.to_be_bytes());
+  ihdr.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+  let mut png: Vec<u8> = vec![0x89, 0x50, 0x4e, 0x47, 0xd, 0xa, 0x1a, 0xa];
+  png.extend(chunk(b"IHDR", &ihdr));
+  png.extend(chunk(b"IDAT", &data));
+  png.extend(chunk(b"IEND", &[]));
+  PNGImage::read_bytes(&png).unwrap()
+}
+
+/// Compare compressing the image data on the calling thread with compressing
+/// it on several threads
+fn encode(criterion: &mut Criterion) {
+  let image: PNGImage = sample_image();
+  let mut group = criterion.benchmark_group("encode");
+  group.sample_size(10);
+  for threads in [1, 2, 4, 0] {
+    let options: EncodeOptions = EncodeOptions::new().threads(threads);
+    group.bench_with_input(
+      BenchmarkId::new("threads", threads),
+      &options,
+      |bencher, options| {
+        bencher.iter(|| black_box(&image).write_bytes_with(options.clone()).unwrap())
+      },
+    );
+  }
+  group.finish();
+}
+
+criterion_group!(benches, encode);
+criterion_main!(benches);
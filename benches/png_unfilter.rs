@@ -0,0 +1,45 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use rsm::lib::img::png::parse::chunks::idat::{png_filters::FilterType, png_unfilter::Unfilter};
+use std::hint::black_box;
+
+/// Pseudo-random bytes of a 4096 pixel scanline
+fn scanline(bpp: usize, seed: u32) -> Vec<u8> {
+  let mut state: u32 = seed;
+  (0..4096 * bpp)
+    .map(|_| {
+      state ^= state << 13;
+      state ^= state >> 17;
+      state ^= state << 5;
+      state as u8
+    })
+    .collect()
+}
+
+/// Compare the kernels selected for the processor with the portable ones, for
+/// each filter and common pixel sizes
+fn unfilter(criterion: &mut Criterion) {
+  for (name, method) in [
+    ("sub", FilterType::Sub),
+    ("up", FilterType::Up),
+    ("average", FilterType::Average),
+    ("paeth", FilterType::Paeth),
+  ] {
+    let mut group = criterion.benchmark_group(format!("unfilter_{name}"));
+    for bpp in [3, 4, 8] {
+      let (current, previous): (Vec<u8>, Vec<u8>) = (scanline(bpp, 0x2545_f491), scanline(bpp, 7));
+      for (kernels, unfilter) in [
+        ("simd", Unfilter::new(bpp)),
+        ("portable", Unfilter::portable(bpp)),
+      ] {
+        group.bench_with_input(BenchmarkId::new(kernels, bpp), &bpp, |bencher, _| {
+          let mut line: Vec<u8> = current.clone();
+          bencher.iter(|| unfilter.apply(method, black_box(&mut line), black_box(&previous)))
+        });
+      }
+    }
+    group.finish();
+  }
+}
+
+criterion_group!(benches, unfilter);
+criterion_main!(benches);
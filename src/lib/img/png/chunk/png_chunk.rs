@@ -1,7 +1,11 @@
 use crate::lib::{img::png::chunk::png_chunk_type::ChunkType, util::err::rsm_error::RSMError};
+use crc32fast::Hasher;
 
 /// Representation of a chunk in the PNG datastream
 pub struct Chunk<'c> {
+  /// Position of the chunk (starting at its length field) in the datastream.
+  pub offset: usize,
+
   /// Indicates in the datastream the amount of bytes that the parser should
   /// read in order to obtain the information for a chunk.
   pub length: u32,
@@ -35,4 +39,12 @@ impl<'c> Chunk<'c> {
     let data: &'c [u8; N] = self.data.try_into().map_err(|_| RSMError::InvalidLength)?;
     parse(data)
   }
+
+  /// Determine if the CRC of the chunk matches its type and data
+  pub(crate) fn is_crc_valid(&self) -> bool {
+    let mut hasher: Hasher = Hasher::new();
+    hasher.update(&self.r#type.as_bytes());
+    hasher.update(self.data);
+    hasher.finalize() == u32::from_be_bytes(self.crc)
+  }
 }
@@ -34,13 +34,26 @@ macro_rules! define_chunk_types {
       pub fn as_bytes(&self) -> [u8; 4] {
         self.as_u32().to_be_bytes()
       }
+
+      /// Determine if the chunk is critical, which is given by the ancillary
+      /// bit (bit 5 of the first byte) being unset.
+      pub fn is_critical(&self) -> bool {
+        self.as_bytes()[0] & 0x20 == 0
+      }
+
+      /// Determine if the chunk can be copied to a modified image without
+      /// being understood, which is given by the safe-to-copy bit (bit 5 of
+      /// the last byte) being set.
+      pub fn is_safe_to_copy(&self) -> bool {
+        self.as_bytes()[3] & 0x20 != 0
+      }
     }
   }
 }
 
 define_chunk_types! {
   IHDR, PLTE, IDAT, IEND, acTL, bKGD, caBX, cHRM, cICP, cLLI, eXIf, fcTL, fdAT,
-  gAMA, hIST, iCCP, iTXt, mDCV, pHYs, sBIT, sRGB, sPLT, tEXt, tIME, tRNS, zTXt,
+  gAMA, hIST, iCCP, iDOT, iTXt, mDCV, pHYs, sBIT, sRGB, sPLT, tEXt, tIME, tRNS, zTXt,
 }
 
 #[cfg(test)]
@@ -66,6 +79,37 @@ pub mod tests {
     }
   }
 
+  /// Test the criticality of chunks
+  #[test]
+  fn test_critical_chunks() {
+    for r#type in [
+      ChunkType::IHDR,
+      ChunkType::PLTE,
+      ChunkType::IDAT,
+      ChunkType::IEND,
+    ] {
+      assert!(r#type.is_critical());
+    }
+    for r#type in [
+      ChunkType::tEXt,
+      ChunkType::pHYs,
+      ChunkType::cHRM,
+      ChunkType::tIME,
+    ] {
+      assert!(!r#type.is_critical());
+    }
+  }
+
+  /// Test the safe-to-copy bit of chunks
+  #[test]
+  fn test_safe_to_copy_chunks() {
+    assert!(ChunkType::tEXt.is_safe_to_copy());
+    assert!(ChunkType::iTXt.is_safe_to_copy());
+    assert!(!ChunkType::IDAT.is_safe_to_copy());
+    assert!(!ChunkType::gAMA.is_safe_to_copy());
+    assert!(ChunkType::from(str_to_u32("prVt")).is_safe_to_copy());
+  }
+
   proptest! {
     /// Test chunk type conversions (&str to u32, u32 to &str)
     #[test]
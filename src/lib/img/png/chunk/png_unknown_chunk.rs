@@ -0,0 +1,11 @@
+use crate::lib::img::png::chunk::png_chunk_type::ChunkType;
+
+/// Chunk kept as is, because its data is not parsed into the metadata
+#[derive(Debug, PartialEq, Clone)]
+pub struct UnknownChunk {
+  /// The [type](ChunkType) of the chunk
+  pub r#type: ChunkType,
+
+  /// The data of the chunk
+  pub data: Vec<u8>,
+}
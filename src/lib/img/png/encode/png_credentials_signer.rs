@@ -0,0 +1,75 @@
+use crate::lib::util::err::rsm_error::RSMError;
+use c2pa::{Builder, Signer, SigningAlg, create_signer};
+use std::{
+  fmt::{Debug, Formatter, Result},
+  io::Cursor,
+  sync::Arc,
+};
+
+/// Signs content credentials (C2PA manifests) embedded in encoded images
+#[derive(Clone)]
+pub struct CredentialsSigner {
+  /// Manifest definition, as JSON
+  manifest: String,
+  signer: Arc<dyn Signer + Send + Sync>,
+}
+
+impl CredentialsSigner {
+  /// Create a signer from a manifest definition (as JSON) and a PEM encoded
+  /// certificate chain and private key. The certificate chain starts with the
+  /// signing certificate, followed by the certificates that issued it.
+  pub fn from_keys(
+    manifest: &str,
+    certificates: &[u8],
+    private_key: &[u8],
+    algorithm: SigningAlg,
+  ) -> std::result::Result<Self, RSMError> {
+    let signer = create_signer::from_keys(certificates, private_key, algorithm, None)
+      .map_err(|error| RSMError::Other(format!("Invalid signing credentials: {error}")))?;
+    Self::with_signer(manifest, signer)
+  }
+
+  /// Create a signer from a manifest definition (as JSON) and any C2PA signer,
+  /// such as one backed by a remote key.
+  pub fn with_signer(
+    manifest: &str,
+    signer: Box<dyn Signer + Send + Sync>,
+  ) -> std::result::Result<Self, RSMError> {
+    Self::builder(manifest)?;
+    Ok(Self {
+      manifest: manifest.to_string(),
+      signer: Arc::from(signer),
+    })
+  }
+
+  fn builder(manifest: &str) -> std::result::Result<Builder, RSMError> {
+    Builder::from_json(manifest)
+      .map_err(|error| RSMError::Other(format!("Invalid manifest definition: {error}")))
+  }
+
+  /// Sign the bytes of an encoded image, inserting a `caBX` chunk whose hard
+  /// binding (data hash) covers every byte of the image but the chunk itself.
+  pub(crate) fn sign(&self, png: &[u8]) -> std::result::Result<Vec<u8>, RSMError> {
+    let mut builder: Builder = Self::builder(&self.manifest)?;
+    let mut signed: Cursor<Vec<u8>> = Cursor::new(Vec::with_capacity(png.len()));
+
+    builder
+      .sign(
+        self.signer.as_ref(),
+        "image/png",
+        &mut Cursor::new(png),
+        &mut signed,
+      )
+      .map_err(|error| RSMError::Other(format!("Failed to sign content credentials: {error}")))?;
+    Ok(signed.into_inner())
+  }
+}
+
+impl Debug for CredentialsSigner {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    f.debug_struct("CredentialsSigner")
+      .field("manifest", &self.manifest)
+      .field("algorithm", &self.signer.alg())
+      .finish_non_exhaustive()
+  }
+}
@@ -0,0 +1,251 @@
+use crate::lib::util::err::rsm_error::RSMError;
+use adler2::Adler32;
+use miniz_oxide::{
+  DataFormat, MZError, MZFlush, MZStatus,
+  deflate::{core::CompressorOxide, stream::deflate},
+};
+use std::{
+  ops::Range,
+  sync::atomic::{AtomicUsize, Ordering},
+  thread,
+};
+
+/// Amount of filtered data compressed by a thread at once. Groups do not
+/// depend on the number of threads, so the output does not either.
+const GROUP_BYTES: usize = 1 << 20;
+
+/// Size of the Deflate window, which is the farthest data can refer back to
+const WINDOW_BYTES: usize = 32 * 1024;
+
+/// Largest prime below 2<sup>16</sup>, the modulus of Adler-32 sums
+const ADLER_BASE: u64 = 65_521;
+
+/// Compressed group of data with its Adler-32
+type Group = Result<(Vec<u8>, u32), RSMError>;
+
+/// Compress filtered scanlines as a single zlib stream on several threads,
+/// at a level from 0 (none) to 12 (smallest).
+///
+/// Scanlines are split into groups that are compressed separately, each one
+/// primed with the last 32 KiB of the group before it so matches can still
+/// refer to them. libdeflater only compresses whole streams, ending with a
+/// final block and without a preset dictionary, so groups are compressed as
+/// raw Deflate streams with miniz_oxide instead. A group is primed by
+/// compressing the window first and discarding the output, which a sync flush
+/// keeps apart from the output of the group. Groups end with a sync flush,
+/// except the last one, so they can be joined between the zlib header and the
+/// Adler-32 combined from the checksum of each group.
+pub(crate) fn deflate_parallel(
+  filtered: &[u8],
+  row_size: usize,
+  level: u8,
+  threads: usize,
+) -> Result<Vec<u8>, RSMError> {
+  let group_size: usize = (GROUP_BYTES / row_size).max(1) * row_size;
+  deflate_groups(filtered, group_size, level, threads)
+}
+
+/// Compress data as a single zlib stream, in groups of the given size
+fn deflate_groups(
+  filtered: &[u8],
+  group_size: usize,
+  level: u8,
+  threads: usize,
+) -> Result<Vec<u8>, RSMError> {
+  if level > 12 {
+    return Err(RSMError::OutOfBounds);
+  }
+  // Levels above 9 are mapped to the slowest setting of miniz_oxide
+  let level: u8 = level.min(10);
+
+  let groups: usize = filtered.len().div_ceil(group_size).max(1);
+  let next: AtomicUsize = AtomicUsize::new(0);
+
+  // Threads take the next group to compress until none remain
+  let mut compressed: Vec<(usize, Group)> = thread::scope(|scope| {
+    let workers: Vec<_> = (0..threads.min(groups))
+      .map(|_| {
+        scope.spawn(|| {
+          let mut done: Vec<(usize, Group)> = Vec::new();
+          loop {
+            let index: usize = next.fetch_add(1, Ordering::Relaxed);
+            if index >= groups {
+              return done;
+            }
+            let start: usize = index * group_size;
+            let end: usize = (start + group_size).min(filtered.len());
+            done.push((index, compress_group(filtered, start..end, level)));
+          }
+        })
+      })
+      .collect();
+
+    workers
+      .into_iter()
+      .flat_map(|worker| worker.join().unwrap_or_default())
+      .collect()
+  });
+  if compressed.len() != groups {
+    return Err(RSMError::Other("Compressing thread panicked".into()));
+  }
+  compressed.sort_by_key(|(index, _)| *index);
+
+  let mut output: Vec<u8> = zlib_header(level).to_vec();
+  let mut adler: u32 = 1;
+  for (index, group) in compressed {
+    let (bytes, checksum) = group?;
+    let length: usize = (filtered.len() - index * group_size).min(group_size);
+    adler = adler32_combine(adler, checksum, length);
+    output.extend_from_slice(&bytes);
+  }
+  output.extend_from_slice(&adler.to_be_bytes());
+  Ok(output)
+}
+
+/// Compress a group of the data as raw Deflate data, returning it with the
+/// Adler-32 of the group
+fn compress_group(data: &[u8], group: Range<usize>, level: u8) -> Group {
+  let mut compressor: Box<CompressorOxide> = Box::default();
+  compressor.set_format_and_level(DataFormat::Raw, level);
+  let mut output: Vec<u8> = Vec::with_capacity(group.len() / 2);
+
+  let window: &[u8] = &data[group.start.saturating_sub(WINDOW_BYTES)..group.start];
+  if !window.is_empty() {
+    compress_into(&mut compressor, window, MZFlush::Sync, &mut output)?;
+    output.clear();
+  }
+
+  let flush: MZFlush = match group.end == data.len() {
+    true => MZFlush::Finish,
+    false => MZFlush::Sync,
+  };
+  let bytes: &[u8] = &data[group];
+  compress_into(&mut compressor, bytes, flush, &mut output)?;
+
+  let mut adler: Adler32 = Adler32::new();
+  adler.write_slice(bytes);
+  Ok((output, adler.checksum()))
+}
+
+/// Compress all of the input, then flush the compressor
+fn compress_into(
+  compressor: &mut CompressorOxide,
+  mut input: &[u8],
+  flush: MZFlush,
+  output: &mut Vec<u8>,
+) -> Result<(), RSMError> {
+  loop {
+    let start: usize = output.len();
+    let available: usize = input.len() / 2 + 1024;
+    output.resize(start + available, 0);
+
+    let result = deflate(compressor, input, &mut output[start..], flush);
+    output.truncate(start + result.bytes_written);
+    input = &input[result.bytes_consumed..];
+
+    // A full output may hold back more data, even once the input is read
+    let flushed: bool = input.is_empty() && result.bytes_written < available;
+    match result.status {
+      Ok(MZStatus::StreamEnd) => return Ok(()),
+      Ok(_) | Err(MZError::Buf) if flushed => return Ok(()),
+      Ok(_) => {}
+      Err(_) => return Err(RSMError::Other("Compression failed".into())),
+    }
+  }
+}
+
+/// Header of a zlib stream using a 32 KiB window, without preset dictionary
+fn zlib_header(level: u8) -> [u8; 2] {
+  let cmf: u8 = 0x78;
+  let flevel: u8 = match level {
+    0..2 => 0,
+    2..6 => 1,
+    6 => 2,
+    _ => 3,
+  };
+  let flg: u8 = flevel << 6;
+  let check: u16 = 31 - (u16::from_be_bytes([cmf, flg]) % 31);
+  [cmf, flg + check as u8]
+}
+
+/// Compute the Adler-32 of two pieces of data joined together from the
+/// Adler-32 of each one and the length of the second one
+fn adler32_combine(first: u32, second: u32, second_length: usize) -> u32 {
+  let remainder: u64 = second_length as u64 % ADLER_BASE;
+  let (first_a, first_b) = ((first & 0xffff) as u64, (first >> 16) as u64);
+  let (second_a, second_b) = ((second & 0xffff) as u64, (second >> 16) as u64);
+
+  // The second sums are offset by every byte of the first piece, once for
+  // `a` and once per byte of the second piece for `b`
+  let a: u64 = (first_a + second_a + ADLER_BASE - 1) % ADLER_BASE;
+  let b: u64 = (first_b + second_b + remainder * first_a + ADLER_BASE - remainder) % ADLER_BASE;
+  ((b << 16) | a) as u32
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::img::png::parse::chunks::utils::inflate;
+  use proptest::{collection::vec, prelude::any, prop_assert_eq, proptest};
+
+  fn adler32(data: &[u8]) -> u32 {
+    let mut adler: Adler32 = Adler32::new();
+    adler.write_slice(data);
+    adler.checksum()
+  }
+
+  #[test]
+  fn test_deflate_groups() {
+    let row_size: usize = 301;
+    let group_size: usize = 10 * row_size;
+    let data: Vec<u8> = (0..row_size * 105)
+      .map(|n| ((n * n) >> 9) as u8 ^ (n / 7000) as u8)
+      .collect();
+
+    let single: Vec<u8> = deflate_groups(&data, group_size, 6, 1).unwrap();
+    for threads in [2, 3, 8] {
+      let compressed: Vec<u8> = deflate_groups(&data, group_size, 6, threads).unwrap();
+      assert_eq!(compressed, single);
+      assert_eq!(inflate(&compressed, usize::MAX).unwrap(), data);
+    }
+
+    for level in [0, 1, 12] {
+      let compressed: Vec<u8> = deflate_groups(&data, group_size, level, 4).unwrap();
+      assert_eq!(inflate(&compressed, usize::MAX).unwrap(), data);
+    }
+    assert!(deflate_groups(&data, group_size, 13, 4).is_err());
+
+    // Groups smaller than the window are primed with every group before them
+    let compressed: Vec<u8> = deflate_groups(&data[..100], 7, 6, 3).unwrap();
+    assert_eq!(inflate(&compressed, usize::MAX).unwrap(), data[..100]);
+    assert_eq!(
+      inflate(&deflate_parallel(&[], 1, 6, 4).unwrap(), usize::MAX).unwrap(),
+      Vec::<u8>::new()
+    );
+  }
+
+  #[test]
+  fn test_zlib_header() {
+    for level in 0..=10 {
+      let [cmf, flg] = zlib_header(level);
+      assert!(u16::from_be_bytes([cmf, flg]).is_multiple_of(31));
+    }
+  }
+
+  proptest! {
+    #[test]
+    fn test_groups_inflate(data in vec(0..4u8, 0..8000), group_size in 256..4000usize, level in 0..=12u8) {
+      let compressed: Vec<u8> = deflate_groups(&data, group_size, level, 2).unwrap();
+      prop_assert_eq!(inflate(&compressed, usize::MAX).unwrap(), data);
+    }
+
+    #[test]
+    fn test_adler32_combine(first in vec(any::<u8>(), 0..6000), second in vec(any::<u8>(), 0..6000)) {
+      let joined: Vec<u8> = [first.as_slice(), second.as_slice()].concat();
+      prop_assert_eq!(
+        adler32_combine(adler32(&first), adler32(&second), second.len()),
+        adler32(&joined)
+      );
+    }
+  }
+}
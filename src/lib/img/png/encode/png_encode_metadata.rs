@@ -0,0 +1,313 @@
+use crate::lib::{
+  img::png::{
+    chunk::png_chunk_type::ChunkType,
+    encode::{
+      png_encode_options::{EncodeOptions, TimeUpdate},
+      png_encoded_pixels::EncodedPixels,
+    },
+    parse::{
+      chunks::{
+        chrm::png_chromaticities::Chromaticities,
+        ihdr::{png_color_type::ColorType, png_header::PNGHeader},
+        plte::png_palette::Palette,
+        time::png_time::ModificationTime,
+        utils::{deflate, write_text},
+      },
+      states::data::png_metadata::PNGMetadata,
+    },
+    writer::png_writer::PNGWriter,
+  },
+  util::err::rsm_error::RSMError,
+};
+
+impl PNGMetadata {
+  /// Write the metadata that does not depend on the color type of the image.
+  /// Chunks describing colors as samples are written with the pixels, while
+  /// animations are left out, as are unknown chunks that are unsafe to copy
+  /// once the image data has changed.
+  pub(crate) fn encode(
+    &self,
+    writer: &mut PNGWriter,
+    options: &EncodeOptions,
+  ) -> Result<(), RSMError> {
+    self.encode_colors(writer, options)?;
+
+    if let Some(dimensions) = &self.physical_dimensions {
+      writer.write_chunk(ChunkType::pHYs, &dimensions.to_bytes());
+    }
+
+    let time: Option<ModificationTime> = match options.modification_time {
+      TimeUpdate::Keep => self.modification_time,
+      TimeUpdate::Now => Some(ModificationTime::now()),
+      TimeUpdate::Fixed(time) => Some(time),
+    };
+    if let Some(time) = time {
+      writer.write_chunk(ChunkType::tIME, &time.to_bytes());
+    }
+
+    if let Some(exif) = &self.exif {
+      writer.write_chunk(ChunkType::eXIf, &exif.to_bytes()?);
+    }
+
+    if let Some(xmp) = &self.xmp {
+      writer.write_chunk(ChunkType::iTXt, &xmp.to_itxt());
+    }
+
+    for text in self.text_entries.iter().flatten() {
+      let (r#type, data) = text.to_chunk(options.compression)?;
+      writer.write_chunk(r#type, &data);
+    }
+
+    for chunk in self.unknown_chunks.iter().flatten() {
+      if chunk.r#type.is_safe_to_copy() && !chunk.r#type.is_critical() {
+        writer.write_chunk(chunk.r#type, &chunk.data);
+      }
+    }
+    Ok(())
+  }
+
+  /// Write the metadata describing how samples map to colors, which is needed
+  /// to display the image as intended
+  pub(crate) fn encode_colors(
+    &self,
+    writer: &mut PNGWriter,
+    options: &EncodeOptions,
+  ) -> Result<(), RSMError> {
+    let fixed = |value: f32| ((value as f64) * 100_000.0).round() as u32;
+
+    if let Some(profile) = &self.icc_profile {
+      let mut data: Vec<u8> = write_text(&profile.name)?;
+      data.extend_from_slice(&[0, 0]);
+      data.extend(deflate(&profile.code, options.compression)?);
+      writer.write_chunk(ChunkType::iCCP, &data);
+    }
+
+    if let Some(intent) = self.rendering_intent {
+      writer.write_chunk(ChunkType::sRGB, &[intent as u8]);
+    }
+
+    if let Some(gamma) = self.gamma {
+      writer.write_chunk(ChunkType::gAMA, &fixed(gamma).to_be_bytes());
+    }
+
+    if let Some(chromaticities) = &self.chromaticities {
+      let Chromaticities {
+        white_point,
+        red,
+        green,
+        blue,
+      } = chromaticities;
+
+      let data: Vec<u8> = [white_point, red, green, blue]
+        .iter()
+        .flat_map(|(x, y)| [fixed(*x), fixed(*y)])
+        .flat_map(u32::to_be_bytes)
+        .collect();
+      writer.write_chunk(ChunkType::cHRM, &data);
+    }
+
+    if let Some(code_points) = &self.code_points {
+      let data: [u8; 4] = [
+        code_points.color_primaries,
+        code_points.transfer_function,
+        code_points.matrix_coefficient,
+        code_points.full_video_range,
+      ];
+      writer.write_chunk(ChunkType::cICP, &data);
+    }
+
+    if let Some(light_level) = &self.light_level {
+      let level = |value: f32| ((value as f64) * 10_000.0).round() as u32;
+      let mut data: Vec<u8> = level(light_level.max_cll).to_be_bytes().to_vec();
+      data.extend_from_slice(&level(light_level.max_fall).to_be_bytes());
+      writer.write_chunk(ChunkType::cLLI, &data);
+    }
+    Ok(())
+  }
+
+  /// Write the `sBIT` chunk, which precedes the palette, remapped from the
+  /// color type of the image to the one of the encoded pixels. It is left out
+  /// when it cannot be represented with them.
+  pub(crate) fn encode_significant_bits(
+    &self,
+    writer: &mut PNGWriter,
+    header: &PNGHeader,
+    pixels: &EncodedPixels,
+  ) {
+    let bits: Option<Vec<u8>> = self
+      .significant_bits
+      .as_deref()
+      .and_then(|bits| remap_significant_bits(bits, header.color_type, pixels));
+    if let Some(bits) = bits {
+      writer.write_chunk(ChunkType::sBIT, &bits);
+    }
+  }
+
+  /// Write the `bKGD` and `hIST` chunks, which follow the palette, remapped
+  /// from the color type of the image to the one of the encoded pixels. Each
+  /// one is left out when it cannot be represented with them.
+  pub(crate) fn encode_palette_samples(
+    &self,
+    writer: &mut PNGWriter,
+    header: &PNGHeader,
+    pixels: &EncodedPixels,
+  ) {
+    let background: Option<Vec<u8>> = self
+      .background_bytes
+      .as_deref()
+      .and_then(|bytes| background_color(bytes, header, self.palette.as_ref()))
+      .and_then(|(color, bit_depth)| remap_background(color, bit_depth, pixels));
+    if let Some(background) = background {
+      writer.write_chunk(ChunkType::bKGD, &background);
+    }
+
+    let histogram: Option<Vec<u8>> = self.histogram.as_deref().and_then(|histogram| {
+      let palette: &Palette = self.palette.as_ref()?;
+      remap_histogram(
+        histogram,
+        &palette.to_rgba(self.transparency.as_ref()),
+        pixels,
+      )
+    });
+    if let Some(histogram) = histogram {
+      writer.write_chunk(ChunkType::hIST, &histogram);
+    }
+  }
+}
+
+/// Bit depth of the samples of the pixels, which is the one of the palette
+/// for indices
+fn sample_depth(pixels: &EncodedPixels) -> u8 {
+  match pixels.color_type {
+    ColorType::IndexedColor => 8,
+    _ => pixels.bit_depth as u8,
+  }
+}
+
+/// Remap the significant bits of each channel to the channels of the pixels.
+/// Greyscale keeps the largest amount of the color channels, alpha added to
+/// the pixels is fully significant, and no channel keeps more bits than its
+/// samples hold.
+fn remap_significant_bits(
+  bits: &[u8],
+  color_type: ColorType,
+  pixels: &EncodedPixels,
+) -> Option<Vec<u8>> {
+  let (colors, alpha): (&[u8], Option<u8>) = match (color_type, bits) {
+    (ColorType::Greyscale, [grey, ..]) => (&[*grey], None),
+    (ColorType::GreyscaleAlpha, [grey, alpha, ..]) => (&[*grey], Some(*alpha)),
+    (ColorType::Truecolor | ColorType::IndexedColor, [r, g, b, ..]) => (&[*r, *g, *b], None),
+    (ColorType::TruecolorAlpha, [r, g, b, alpha, ..]) => (&[*r, *g, *b], Some(*alpha)),
+    _ => return None,
+  };
+
+  let depth: u8 = sample_depth(pixels);
+  let mut remapped: Vec<u8> = match pixels.color_type {
+    ColorType::Greyscale | ColorType::GreyscaleAlpha => vec![*colors.iter().max()?],
+    _ => (0..3)
+      .map(|channel| colors[channel.min(colors.len() - 1)])
+      .collect(),
+  };
+  if matches!(
+    pixels.color_type,
+    ColorType::GreyscaleAlpha | ColorType::TruecolorAlpha
+  ) {
+    remapped.push(alpha.unwrap_or(depth));
+  }
+
+  remapped
+    .iter_mut()
+    .for_each(|bits| *bits = (*bits).min(depth));
+  remapped.iter().all(|&bits| bits > 0).then_some(remapped)
+}
+
+/// Read the background color as RGB samples, along with their bit depth
+fn background_color(
+  bytes: &[u8],
+  header: &PNGHeader,
+  palette: Option<&Palette>,
+) -> Option<([u16; 3], u8)> {
+  let sample = |pair: &[u8]| u16::from_be_bytes([pair[0], pair[1]]);
+  match (header.color_type, bytes) {
+    (ColorType::Greyscale | ColorType::GreyscaleAlpha, [_, _]) => {
+      Some(([sample(bytes); 3], header.bit_depth as u8))
+    }
+    (ColorType::Truecolor | ColorType::TruecolorAlpha, [_, _, _, _, _, _]) => {
+      let [r, g, b] = [0, 2, 4].map(|start| sample(&bytes[start..]));
+      Some(([r, g, b], header.bit_depth as u8))
+    }
+    (ColorType::IndexedColor, [index]) => {
+      let [r, g, b] = *palette?.colors().get(*index as usize)?;
+      Some(([r as u16, g as u16, b as u16], 8))
+    }
+    _ => None,
+  }
+}
+
+/// Encode the background color for the pixels: as samples of their bit depth,
+/// which must be equal for greyscale, or as the index of a palette entry
+fn remap_background(color: [u16; 3], bit_depth: u8, pixels: &EncodedPixels) -> Option<Vec<u8>> {
+  let depth: u8 = sample_depth(pixels);
+  let [r, g, b] = color.map(|sample| rescale(sample, bit_depth, depth));
+  let [r, g, b] = [r?, g?, b?];
+
+  match pixels.color_type {
+    ColorType::Greyscale | ColorType::GreyscaleAlpha => {
+      (r == g && g == b).then(|| r.to_be_bytes().to_vec())
+    }
+    ColorType::Truecolor | ColorType::TruecolorAlpha => Some(
+      [r, g, b]
+        .iter()
+        .flat_map(|sample| sample.to_be_bytes())
+        .collect(),
+    ),
+    ColorType::IndexedColor => {
+      let entry: [u8; 3] = [r as u8, g as u8, b as u8];
+      let index: usize = pixels
+        .palette
+        .as_ref()?
+        .colors()
+        .iter()
+        .position(|&color| color == entry)?;
+      Some(vec![index as u8])
+    }
+  }
+}
+
+/// Scale a sample to another bit depth, if it can be done exactly
+fn rescale(sample: u16, from: u8, to: u8) -> Option<u16> {
+  let (from_max, to_max) = ((1u32 << from) - 1, (1u32 << to) - 1);
+  let scaled: u32 = sample as u32 * to_max;
+  (sample as u32 <= from_max && scaled.is_multiple_of(from_max))
+    .then_some((scaled / from_max) as u16)
+}
+
+/// Remap the frequencies of the palette entries of the image to the palette
+/// of the pixels, adding up the frequencies of entries holding the same
+/// color. Every entry of the pixels must have a matching one in the image.
+fn remap_histogram(
+  histogram: &[u16],
+  entries: &[[u8; 4]],
+  pixels: &EncodedPixels,
+) -> Option<Vec<u8>> {
+  let palette: &Palette = pixels.palette.as_ref()?;
+  if histogram.len() != entries.len() {
+    return None;
+  }
+
+  let mut remapped: Vec<u8> = Vec::with_capacity(palette.len() * 2);
+  for color in palette.to_rgba(pixels.transparency.as_ref()) {
+    let frequencies: Vec<u32> = entries
+      .iter()
+      .zip(histogram)
+      .filter(|(entry, _)| **entry == color)
+      .map(|(_, &frequency)| frequency as u32)
+      .collect();
+    if frequencies.is_empty() {
+      return None;
+    }
+    let frequency: u16 = frequencies.iter().sum::<u32>().min(u16::MAX as u32) as u16;
+    remapped.extend_from_slice(&frequency.to_be_bytes());
+  }
+  Some(remapped)
+}
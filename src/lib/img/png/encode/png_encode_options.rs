@@ -0,0 +1,137 @@
+use crate::lib::img::png::{
+  encode::{png_credentials_signer::CredentialsSigner, png_filter::FilterStrategy},
+  parse::chunks::time::png_time::ModificationTime,
+};
+
+/// Defines the last-modification time written in the `tIME` chunk
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum TimeUpdate {
+  /// Write the time of the image, if any
+  Keep,
+
+  /// Write the time at which the image is encoded
+  Now,
+
+  /// Write a given time
+  Fixed(ModificationTime),
+}
+
+/// Options used when encoding PNG images
+#[derive(Debug, Clone)]
+pub struct EncodeOptions {
+  /// Compression level of the image data, from 0 (none) to 12 (smallest)
+  pub(crate) compression: u8,
+  pub(crate) filter: FilterStrategy,
+
+  /// Write the metadata of the image along with its pixels
+  pub(crate) metadata: bool,
+
+  /// Write pixels whose colors are all in the palette of the image as indices
+  pub(crate) indexed: bool,
+
+  /// Signer of the content credentials to embed in the image
+  pub(crate) credentials: Option<CredentialsSigner>,
+
+  /// Time written in the `tIME` chunk, which is the one of the image unless
+  /// [touched](Self::touch) or [fixed](Self::modification_time)
+  pub(crate) modification_time: TimeUpdate,
+
+  /// Number of threads compressing the image data, where 0 uses every
+  /// available core
+  #[cfg(feature = "parallel")]
+  pub(crate) threads: usize,
+}
+
+impl Default for EncodeOptions {
+  fn default() -> Self {
+    Self {
+      compression: 6,
+      filter: FilterStrategy::default(),
+      metadata: true,
+      indexed: false,
+      credentials: None,
+      modification_time: TimeUpdate::Keep,
+      #[cfg(feature = "parallel")]
+      threads: 1,
+    }
+  }
+}
+
+impl EncodeOptions {
+  /// Create the default encoding options
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Set the compression level of the image data, from 0 (none) to 12
+  /// (smallest)
+  pub fn compression(mut self, level: u8) -> Self {
+    self.compression = level;
+    self
+  }
+
+  /// Set how filters are chosen for each scanline
+  pub fn filter(mut self, filter: FilterStrategy) -> Self {
+    self.filter = filter;
+    self
+  }
+
+  /// Set whether the metadata of the image should be written
+  pub fn metadata(mut self, metadata: bool) -> Self {
+    self.metadata = metadata;
+    self
+  }
+
+  /// Set whether pixels whose colors are all in the palette of the image, such
+  /// as [quantized](crate::lib::img::png::image::png_image::PNGImage::quantize)
+  /// ones, should be written as indices into it. Other pixels are written with
+  /// their own colors.
+  pub fn indexed(mut self, indexed: bool) -> Self {
+    self.indexed = indexed;
+    self
+  }
+
+  /// Embed content credentials, signed once the image is encoded. Credentials
+  /// read from the image are never written again, as re-encoding invalidates
+  /// their hard binding.
+  pub fn credentials(mut self, signer: CredentialsSigner) -> Self {
+    self.credentials = Some(signer);
+    self
+  }
+
+  /// Set the last-modification time to the current UTC time when encoding,
+  /// replacing the time of the image
+  pub fn touch(mut self) -> Self {
+    self.modification_time = TimeUpdate::Now;
+    self
+  }
+
+  /// Set the last-modification time to write, replacing the time of the image.
+  /// A fixed time keeps the output reproducible.
+  pub fn modification_time(mut self, time: ModificationTime) -> Self {
+    self.modification_time = TimeUpdate::Fixed(time);
+    self
+  }
+
+  /// Set the number of threads compressing the image data, where 1 (the
+  /// default) compresses on the calling thread and 0 uses every available
+  /// core, like
+  /// [decoding](crate::lib::img::png::parse::png_decode_options::DecodeOptions::threads).
+  /// Several threads compress groups of scanlines separately, which gives a
+  /// slightly larger output that is the same for any number of threads above
+  /// 1.
+  #[cfg(feature = "parallel")]
+  pub fn threads(mut self, threads: usize) -> Self {
+    self.threads = threads;
+    self
+  }
+
+  /// Number of threads compressing the image data
+  #[cfg(feature = "parallel")]
+  pub(crate) fn thread_count(&self) -> usize {
+    match self.threads {
+      0 => std::thread::available_parallelism().map_or(1, |threads| threads.get()),
+      threads => threads,
+    }
+  }
+}
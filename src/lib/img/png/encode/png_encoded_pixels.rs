@@ -0,0 +1,176 @@
+use crate::lib::{
+  img::png::parse::chunks::{
+    idat::{png_pixel_data::PixelData, png_pixel_format::ColorTarget},
+    ihdr::{png_bit_depth::BitDepth, png_color_type::ColorType},
+    plte::png_palette::Palette,
+    trns::png_transparency::Transparency,
+  },
+  util::err::rsm_error::RSMError,
+};
+use std::{borrow::Cow, collections::HashMap};
+
+/// Pixels as stored in the image data of a PNG file: scanlines of samples of
+/// a color type and bit depth, along with the palette and transparency they
+/// refer to.
+#[derive(Debug, Clone)]
+pub(crate) struct EncodedPixels<'p> {
+  pub width: u32,
+  pub height: u32,
+  pub color_type: ColorType,
+  pub bit_depth: BitDepth,
+
+  /// Unfiltered scanlines, each starting on a byte boundary
+  pub data: Cow<'p, [u8]>,
+  pub palette: Option<Palette>,
+  pub transparency: Option<Transparency>,
+}
+
+impl<'p> EncodedPixels<'p> {
+  /// Describe decoded pixels, which hold 8-bit or 16-bit samples
+  pub(crate) fn new(pixels: &'p PixelData) -> Result<Self, RSMError> {
+    let bit_depth: BitDepth = match pixels.bit_depth {
+      8 => BitDepth::D8,
+      16 => BitDepth::D16,
+      _ => return Err(RSMError::InvalidContent),
+    };
+    let color_type: ColorType = match pixels.color {
+      ColorTarget::Grey => ColorType::Greyscale,
+      ColorTarget::GreyAlpha => ColorType::GreyscaleAlpha,
+      ColorTarget::Rgb => ColorType::Truecolor,
+      ColorTarget::Rgba => ColorType::TruecolorAlpha,
+    };
+
+    let encoded: Self = Self {
+      width: pixels.width,
+      height: pixels.height,
+      color_type,
+      bit_depth,
+      data: pixels.interleaved(),
+      palette: None,
+      transparency: None,
+    };
+    encoded.validate()?;
+    Ok(encoded)
+  }
+
+  /// Describe decoded pixels as indices into a palette, if it holds the color
+  /// of every pixel. The first matching entry is used for each color.
+  pub(crate) fn indexed(
+    pixels: &PixelData,
+    palette: &Palette,
+    transparency: Option<&Transparency>,
+  ) -> Option<EncodedPixels<'static>> {
+    if pixels.bit_depth != 8 {
+      return None;
+    }
+
+    let mut indices: HashMap<[u8; 4], u16> = HashMap::new();
+    for (index, color) in palette.to_rgba(transparency).into_iter().enumerate() {
+      indices.entry(color).or_insert(index as u16);
+    }
+    let values: Vec<u16> = pixels
+      .to_rgba8()
+      .iter()
+      .map(|color| indices.get(color).copied())
+      .collect::<Option<_>>()?;
+
+    let bit_depth: BitDepth = index_bit_depth(palette.len());
+    let transparency: Option<Transparency> = match transparency {
+      Some(Transparency::PaletteAlpha(alpha)) => Some(Transparency::PaletteAlpha(alpha.clone())),
+      _ => None,
+    };
+    Some(EncodedPixels {
+      width: pixels.width,
+      height: pixels.height,
+      color_type: ColorType::IndexedColor,
+      bit_depth,
+      data: Cow::Owned(pack(&values, pixels.width as usize, bit_depth)),
+      palette: Some(palette.clone()),
+      transparency,
+    })
+  }
+
+  /// Check the pixels have a size and hold whole scanlines
+  pub(crate) fn validate(&self) -> Result<(), RSMError> {
+    if self.width == 0 || self.height == 0 {
+      return Err(RSMError::InvalidContent);
+    }
+    match self.data.len() == self.stride() * self.height as usize {
+      true => Ok(()),
+      false => Err(RSMError::InvalidLength),
+    }
+  }
+
+  /// Amount of samples per pixel
+  pub(crate) fn channels(&self) -> usize {
+    match self.color_type {
+      ColorType::Greyscale | ColorType::IndexedColor => 1,
+      ColorType::GreyscaleAlpha => 2,
+      ColorType::Truecolor => 3,
+      ColorType::TruecolorAlpha => 4,
+    }
+  }
+
+  /// Size of a scanline in bytes, without its filter type
+  pub(crate) fn stride(&self) -> usize {
+    (self.width as usize * self.channels() * self.bit_depth as usize).div_ceil(8)
+  }
+
+  /// Distance between a byte and the matching byte of the previous pixel, as
+  /// used by filters. Pixels smaller than a byte use the previous byte.
+  pub(crate) fn filter_distance(&self) -> usize {
+    (self.channels() * self.bit_depth as usize).div_ceil(8)
+  }
+}
+
+/// Smallest bit depth of indices into a palette of the given size
+pub(crate) fn index_bit_depth(entries: usize) -> BitDepth {
+  match entries {
+    0..=2 => BitDepth::D1,
+    3..=4 => BitDepth::D2,
+    5..=16 => BitDepth::D4,
+    _ => BitDepth::D8,
+  }
+}
+
+/// Pack samples into scanlines of the given amount of samples, with the most
+/// significant bits first
+pub(crate) fn pack(values: &[u16], row_samples: usize, bit_depth: BitDepth) -> Vec<u8> {
+  let bits: usize = bit_depth as usize;
+  match bit_depth {
+    BitDepth::D16 => values
+      .iter()
+      .flat_map(|value| value.to_be_bytes())
+      .collect(),
+    BitDepth::D8 => values.iter().map(|&value| value as u8).collect(),
+    _ => values
+      .chunks_exact(row_samples)
+      .flat_map(|row| {
+        let mut bytes: Vec<u8> = vec![0u8; (row.len() * bits).div_ceil(8)];
+        for (index, &value) in row.iter().enumerate() {
+          let bit: usize = index * bits;
+          bytes[bit / 8] |= (value as u8) << (8 - bits - bit % 8);
+        }
+        bytes
+      })
+      .collect(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_pack() {
+    assert_eq!(
+      pack(&[1, 0, 1, 1, 1, 0], 3, BitDepth::D1),
+      [0b1010_0000, 0b1100_0000]
+    );
+    assert_eq!(
+      pack(&[3, 2, 1, 0, 3], 5, BitDepth::D2),
+      [0b1110_0100, 0b1100_0000]
+    );
+    assert_eq!(pack(&[0x0102], 1, BitDepth::D16), [1, 2]);
+  }
+}
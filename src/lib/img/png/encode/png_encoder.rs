@@ -0,0 +1,141 @@
+use crate::lib::{
+  img::png::{
+    chunk::png_chunk_type::ChunkType,
+    encode::{png_encode_options::EncodeOptions, png_encoded_pixels::EncodedPixels},
+    image::png_image::PNGImage,
+    parse::{chunks::utils::deflate, states::data::png_metadata::PNGMetadata},
+    writer::png_writer::PNGWriter,
+  },
+  util::err::rsm_error::RSMError,
+};
+
+#[cfg(feature = "parallel")]
+use crate::lib::img::png::encode::png_deflate::deflate_parallel;
+
+/// Encoder of PNG images
+pub(crate) struct PNGEncoder<'e> {
+  image: &'e PNGImage,
+  options: &'e EncodeOptions,
+  writer: PNGWriter,
+}
+
+impl<'e> PNGEncoder<'e> {
+  /// Maximum size of the data of an `IDAT` chunk
+  const IDAT_SIZE: usize = 1 << 20;
+
+  pub(crate) fn new(image: &'e PNGImage, options: &'e EncodeOptions) -> Self {
+    Self {
+      image,
+      options,
+      writer: PNGWriter::new(),
+    }
+  }
+
+  /// Encode the image, then sign its content credentials if requested. Pixels
+  /// whose colors are all in the palette of the image are written as indices
+  /// when the options allow it.
+  pub(crate) fn encode(self) -> Result<Vec<u8>, RSMError> {
+    let meta: &PNGMetadata = &self.image.meta;
+    let indexed: Option<EncodedPixels> = match (self.options.indexed, &meta.palette) {
+      (true, Some(palette)) => {
+        EncodedPixels::indexed(&self.image.data, palette, meta.transparency.as_ref())
+      }
+      _ => None,
+    };
+
+    let pixels: EncodedPixels = match indexed {
+      Some(pixels) => pixels,
+      None => EncodedPixels::new(&self.image.data)?,
+    };
+    self.encode_pixels(&pixels, false)
+  }
+
+  /// Encode the image with the given pixels in place of its own, writing
+  /// only the metadata describing colors when stripping it, then sign its
+  /// content credentials if requested. Chunks describing colors as samples
+  /// are remapped to the pixels.
+  pub(crate) fn encode_pixels(
+    mut self,
+    pixels: &EncodedPixels,
+    strip: bool,
+  ) -> Result<Vec<u8>, RSMError> {
+    self.write_header(pixels);
+    let meta: &PNGMetadata = &self.image.meta;
+    let samples: bool = self.options.metadata && !strip;
+    match (self.options.metadata, strip) {
+      (true, true) => meta.encode_colors(&mut self.writer, self.options)?,
+      (true, false) => meta.encode(&mut self.writer, self.options)?,
+      (false, _) => {}
+    }
+    if samples {
+      meta.encode_significant_bits(&mut self.writer, &self.image.header, pixels);
+    }
+
+    if let Some(palette) = &pixels.palette {
+      self
+        .writer
+        .write_chunk(ChunkType::PLTE, &palette.to_bytes());
+    }
+    if let Some(transparency) = &pixels.transparency {
+      self
+        .writer
+        .write_chunk(ChunkType::tRNS, &transparency.to_bytes());
+    }
+    if samples {
+      meta.encode_palette_samples(&mut self.writer, &self.image.header, pixels);
+    }
+    self.write_image_data(pixels)?;
+
+    let bytes: Vec<u8> = self.writer.finish();
+    match &self.options.credentials {
+      Some(signer) => signer.sign(&bytes),
+      None => Ok(bytes),
+    }
+  }
+
+  /// Write the `IHDR` chunk, describing the pixels rather than the image they
+  /// were decoded from
+  fn write_header(&mut self, pixels: &EncodedPixels) {
+    let mut data: Vec<u8> = Vec::with_capacity(13);
+    data.extend_from_slice(&pixels.width.to_be_bytes());
+    data.extend_from_slice(&pixels.height.to_be_bytes());
+    data.extend_from_slice(&[pixels.bit_depth as u8, pixels.color_type as u8, 0, 0, 0]);
+    self.writer.write_chunk(ChunkType::IHDR, &data);
+  }
+
+  /// Filter and compress the pixels into `IDAT` chunks
+  fn write_image_data(&mut self, pixels: &EncodedPixels) -> Result<(), RSMError> {
+    pixels.validate()?;
+    let bpp: usize = pixels.filter_distance();
+    let stride: usize = pixels.stride();
+
+    let mut filtered: Vec<u8> = Vec::with_capacity(pixels.data.len() + pixels.height as usize);
+    let mut previous: &[u8] = &vec![0u8; stride];
+    for row in pixels.data.chunks_exact(stride) {
+      self
+        .options
+        .filter
+        .filter(row, previous, bpp, &mut filtered);
+      previous = row;
+    }
+
+    let compressed: Vec<u8> = self.compress(&filtered, stride + 1)?;
+    for idat in compressed.chunks(Self::IDAT_SIZE) {
+      self.writer.write_chunk(ChunkType::IDAT, idat);
+    }
+    Ok(())
+  }
+
+  /// Compress filtered scanlines of the given size, on several threads when
+  /// requested
+  #[cfg_attr(not(feature = "parallel"), allow(unused_variables))]
+  fn compress(&self, filtered: &[u8], row_size: usize) -> Result<Vec<u8>, RSMError> {
+    let level: u8 = self.options.compression;
+
+    #[cfg(feature = "parallel")]
+    if self.options.thread_count() > 1 {
+      return deflate_parallel(filtered, row_size, level, self.options.thread_count());
+    }
+    deflate(filtered, level)
+  }
+}
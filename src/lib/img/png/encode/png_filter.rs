@@ -0,0 +1,114 @@
+use crate::lib::img::png::parse::chunks::idat::{
+  png_filters::FilterType, png_unfilter::paeth_predictor,
+};
+
+/// Strategy used to choose the filter applied to each scanline
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum FilterStrategy {
+  /// Apply the same filter to every scanline
+  Fixed(FilterType),
+
+  /// Choose the filter minimizing the sum of absolute differences of each
+  /// scanline, as recommended by the PNG specification
+  #[default]
+  Adaptive,
+}
+
+impl FilterStrategy {
+  const FILTERS: [FilterType; 5] = [
+    FilterType::None,
+    FilterType::Sub,
+    FilterType::Up,
+    FilterType::Average,
+    FilterType::Paeth,
+  ];
+
+  /// Filter a scanline, writing the filter type followed by the filtered bytes
+  pub(crate) fn filter(&self, current: &[u8], previous: &[u8], bpp: usize, out: &mut Vec<u8>) {
+    let filter: FilterType = match self {
+      Self::Fixed(filter) => *filter,
+      Self::Adaptive => {
+        let mut buffer: Vec<u8> = vec![0u8; current.len()];
+        let mut best: (FilterType, u64) = (FilterType::None, u64::MAX);
+
+        for filter in Self::FILTERS {
+          filter_row(filter, current, previous, bpp, &mut buffer);
+          let sum: u64 = buffer
+            .iter()
+            .map(|&byte| (byte as i8).unsigned_abs() as u64)
+            .sum();
+          if sum < best.1 {
+            best = (filter, sum);
+          }
+        }
+        best.0
+      }
+    };
+
+    let start: usize = out.len();
+    out.push(filter as u8);
+    out.resize(start + 1 + current.len(), 0);
+    filter_row(filter, current, previous, bpp, &mut out[start + 1..]);
+  }
+}
+
+/// Apply a filter to a scanline, given the unfiltered previous scanline, which
+/// is filled with zeros for the first scanline.
+pub(crate) fn filter_row(
+  filter: FilterType,
+  current: &[u8],
+  previous: &[u8],
+  bpp: usize,
+  out: &mut [u8],
+) {
+  for i in 0..current.len() {
+    let left: u8 = if i >= bpp { current[i - bpp] } else { 0 };
+    let above: u8 = previous[i];
+    let upper_left: u8 = if i >= bpp { previous[i - bpp] } else { 0 };
+
+    let prediction: u8 = match filter {
+      FilterType::None => 0,
+      FilterType::Sub => left,
+      FilterType::Up => above,
+      FilterType::Average => ((left as u16 + above as u16) / 2) as u8,
+      FilterType::Paeth => paeth_predictor(left, above, upper_left),
+    };
+    out[i] = current[i].wrapping_sub(prediction);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::img::png::parse::chunks::idat::png_unfilter::{
+    unfilter_average, unfilter_paeth, unfilter_sub, unfilter_up,
+  };
+  use proptest::{collection::vec, prop_assert_eq, proptest};
+
+  proptest! {
+    /// Test filtering then unfiltering a scanline restores it
+    #[test]
+    fn test_filter_round_trip(
+      rows in vec(0u8..=255, 24),
+      bpp in 1usize..=8,
+      filter in 0u8..=4,
+    ) {
+      let (previous, current) = rows.split_at(12);
+      let filter: FilterType = FilterType::try_from(filter).unwrap();
+
+      let mut filtered: Vec<u8> = Vec::new();
+      FilterStrategy::Fixed(filter).filter(current, previous, bpp, &mut filtered);
+      prop_assert_eq!(filtered[0], filter as u8);
+
+      let row: &mut [u8] = &mut filtered[1..];
+      match filter {
+        FilterType::None => {}
+        FilterType::Sub => unfilter_sub(row, bpp),
+        FilterType::Up => unfilter_up(row, previous),
+        FilterType::Average => unfilter_average(row, previous, bpp),
+        FilterType::Paeth => unfilter_paeth(row, previous, bpp),
+      }
+      prop_assert_eq!(&*row, current);
+    }
+  }
+}
@@ -0,0 +1,74 @@
+/// Function mixing the color of a source with the color of the destination
+/// below it, where both are opaque. Transparent parts of either keep the
+/// other color. Drawing mixes samples in linear light, so modes such as
+/// [`Overlay`](BlendMode::Overlay) give other results than editors blending
+/// sRGB-encoded samples.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum BlendMode {
+  /// Keep the source color
+  #[default]
+  Normal,
+
+  /// Multiply the colors, which darkens
+  Multiply,
+
+  /// Multiply the complements of the colors, which lightens
+  Screen,
+
+  /// Multiply dark destination colors and screen light ones, which raises
+  /// contrast
+  Overlay,
+
+  /// Keep the darkest color
+  Darken,
+
+  /// Keep the lightest color
+  Lighten,
+
+  /// Subtract the darkest color from the lightest
+  Difference,
+}
+
+impl BlendMode {
+  /// Mix samples from 0 to 1 of a source and a destination
+  pub fn blend(&self, source: f32, destination: f32) -> f32 {
+    let screen = |first: f32, second: f32| first + second - first * second;
+    match self {
+      Self::Normal => source,
+      Self::Multiply => source * destination,
+      Self::Screen => screen(source, destination),
+      Self::Overlay => match destination <= 0.5 {
+        true => source * 2.0 * destination,
+        false => screen(source, 2.0 * destination - 1.0),
+      },
+      Self::Darken => source.min(destination),
+      Self::Lighten => source.max(destination),
+      Self::Difference => (source - destination).abs(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_blend() {
+    let cases: [(BlendMode, [f32; 3]); 7] = [
+      (BlendMode::Normal, [0.25, 0.25, 1.0]),
+      (BlendMode::Multiply, [0.0, 0.1875, 0.0]),
+      (BlendMode::Screen, [0.25, 0.8125, 1.0]),
+      (BlendMode::Overlay, [0.0, 0.625, 0.0]),
+      (BlendMode::Darken, [0.0, 0.25, 0.0]),
+      (BlendMode::Lighten, [0.25, 0.75, 1.0]),
+      (BlendMode::Difference, [0.25, 0.5, 1.0]),
+    ];
+    for (mode, expected) in cases {
+      let blended: Vec<f32> = [(0.25, 0.0), (0.25, 0.75), (1.0, 0.0)]
+        .iter()
+        .map(|&(source, destination)| mode.blend(source, destination))
+        .collect();
+      assert_eq!(blended, expected, "{mode:?}");
+    }
+  }
+}
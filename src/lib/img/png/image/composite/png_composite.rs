@@ -0,0 +1,185 @@
+use crate::lib::{
+  img::png::{
+    image::{
+      composite::{png_blend_mode::BlendMode, png_composite_options::CompositeOptions},
+      png_linear_pixels::{LinearColor, LinearPixels},
+    },
+    parse::chunks::idat::png_pixel_data::PixelData,
+  },
+  util::err::rsm_error::RSMError,
+};
+
+impl PixelData {
+  /// Draw pixels onto these ones with their top left corner at an offset,
+  /// which may be negative, using the given [options](CompositeOptions).
+  /// Source pixels outside these ones are clipped, and only the pixels below
+  /// the source are changed, whatever the operator. Colors are combined in
+  /// linear light with premultiplied alpha, keeping the format of these
+  /// pixels, so [blend modes](BlendMode) also mix linear colors rather than
+  /// their sRGB encoding.
+  pub fn draw(
+    &mut self,
+    source: &PixelData,
+    x: i64,
+    y: i64,
+    options: CompositeOptions,
+  ) -> Result<(), RSMError> {
+    if !(0.0..=1.0).contains(&options.opacity) {
+      return Err(RSMError::OutOfBounds);
+    }
+    self.validate()?;
+    source.validate()?;
+    let (columns, rows) = (
+      clip(x, source.width, self.width),
+      clip(y, source.height, self.height),
+    );
+    let (Some((left, right)), Some((top, bottom))) = (columns, rows) else {
+      return Ok(());
+    };
+
+    // Only the overlapping pixels are converted to linear light
+    let (width, height) = (right - left, bottom - top);
+    let (source_left, source_top) = ((left as i64 - x) as usize, (top as i64 - y) as usize);
+    let mut region: PixelData = self.region(left, top, width, height);
+    let mut destination: LinearPixels = LinearPixels::from_pixels(&region)?;
+    let source: LinearPixels =
+      LinearPixels::from_pixels(&source.region(source_left, source_top, width, height))?;
+
+    for (pixel, color) in destination.data.iter_mut().zip(&source.data) {
+      *pixel = composite(color, pixel, &options);
+    }
+    region.set_linear(&destination);
+    self.paste(&region, left, top);
+    Ok(())
+  }
+}
+
+/// Range of destination pixels covered along a direction by source pixels
+/// starting at an offset, if any
+fn clip(offset: i64, size: u32, limit: u32) -> Option<(usize, usize)> {
+  let start: i64 = offset.max(0);
+  let end: i64 = offset.saturating_add(size as i64).min(limit as i64);
+  (start < end).then_some((start as usize, end as usize))
+}
+
+/// Combine a premultiplied source color with the destination below it,
+/// mixing the colors where both are present before applying the operator
+pub(crate) fn composite(
+  source: &LinearColor,
+  destination: &LinearColor,
+  options: &CompositeOptions,
+) -> LinearColor {
+  let mut source: LinearColor = source.map(|sample| sample * options.opacity);
+  let (source_alpha, destination_alpha) = (source[3], destination[3]);
+
+  if options.blend != BlendMode::Normal && source_alpha > 0.0 && destination_alpha > 0.0 {
+    for (sample, below) in source.iter_mut().zip(destination).take(3) {
+      let (color, below): (f32, f32) = (*sample / source_alpha, below / destination_alpha);
+      let blended: f32 = options.blend.blend(color, below);
+      *sample = source_alpha * ((1.0 - destination_alpha) * color + destination_alpha * blended);
+    }
+  }
+  options.operator.apply(&source, destination)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::img::png::{
+    image::composite::png_porter_duff::PorterDuff,
+    parse::chunks::idat::png_pixel_format::{ColorTarget, PixelLayout},
+  };
+
+  fn canvas() -> PixelData {
+    PixelData::from_rgba8(3, 2, &[[0, 0, 255, 255]; 6])
+  }
+
+  #[test]
+  fn test_draw() {
+    // The source is clipped to the top right corner
+    let mut pixels: PixelData = canvas();
+    let source: PixelData = PixelData::from_rgba8(2, 2, &[[255, 0, 0, 255]; 4]);
+    pixels
+      .draw(&source, 2, -1, CompositeOptions::new())
+      .unwrap();
+    let colors: Vec<[u8; 4]> = pixels.to_rgba8();
+    assert_eq!(colors[2], [255, 0, 0, 255]);
+    assert!(
+      colors
+        .iter()
+        .enumerate()
+        .all(|(index, color)| index == 2 || *color == [0, 0, 255, 255])
+    );
+
+    // Sources outside the pixels change nothing
+    for (x, y) in [(3, 0), (-2, 0), (0, 2), (i64::MAX, i64::MIN)] {
+      let mut pixels: PixelData = canvas();
+      pixels.draw(&source, x, y, CompositeOptions::new()).unwrap();
+      assert_eq!(pixels.to_rgba8(), canvas().to_rgba8());
+    }
+  }
+
+  #[test]
+  fn test_draw_region() {
+    // Pixels outside the source keep their exact samples, whatever the layout
+    let mut pixels: PixelData = PixelData {
+      data: (0..48u8).map(|i| i.wrapping_mul(37)).collect(),
+      width: 4,
+      height: 2,
+      color: ColorTarget::Rgb,
+      bit_depth: 16,
+      layout: PixelLayout::Planar,
+    };
+    let original: PixelData = pixels.region(0, 0, 4, 2);
+    let source: PixelData = PixelData::from_rgba8(1, 2, &[[255, 0, 0, 255]; 2]);
+    pixels.draw(&source, 2, 1, CompositeOptions::new()).unwrap();
+
+    let (before, after): (Vec<[u8; 4]>, Vec<[u8; 4]>) = (original.to_rgba8(), pixels.to_rgba8());
+    assert_eq!(after[6], [255, 0, 0, 255]);
+    for plane in pixels
+      .data
+      .chunks_exact(16)
+      .zip(original.data.chunks_exact(16))
+    {
+      let (drawn, kept) = plane;
+      assert_eq!(drawn[..12], kept[..12]);
+      assert_eq!(drawn[14..], kept[14..]);
+    }
+    assert_eq!(after[..6], before[..6]);
+  }
+
+  #[test]
+  fn test_opacity() {
+    let mut pixels: PixelData = PixelData::from_rgba8(1, 1, &[[0, 0, 0, 0]]);
+    let source: PixelData = PixelData::from_rgba8(1, 1, &[[255, 255, 255, 255]]);
+    pixels
+      .draw(&source, 0, 0, CompositeOptions::new().opacity(0.5))
+      .unwrap();
+    assert_eq!(pixels.to_rgba8(), [[255, 255, 255, 128]]);
+
+    for opacity in [-0.1, 1.5, f32::NAN] {
+      assert!(matches!(
+        pixels.draw(&source, 0, 0, CompositeOptions::new().opacity(opacity)),
+        Err(RSMError::OutOfBounds)
+      ));
+    }
+  }
+
+  #[test]
+  fn test_blend() {
+    let grey: LinearColor = [0.5, 0.5, 0.5, 1.0];
+    let options: CompositeOptions = CompositeOptions::new().blend(BlendMode::Multiply);
+    assert_eq!(composite(&grey, &grey, &options), [0.25, 0.25, 0.25, 1.0]);
+
+    // Only the covered part of the destination is blended
+    let half: LinearColor = [0.25, 0.25, 0.25, 0.5];
+    assert_eq!(
+      composite(&grey, &half, &options),
+      [0.375, 0.375, 0.375, 1.0]
+    );
+    assert_eq!(composite(&half, &[0.0; 4], &options), half);
+
+    let options: CompositeOptions = options.operator(PorterDuff::DestinationIn);
+    assert_eq!(composite(&half, &grey, &options), half);
+  }
+}
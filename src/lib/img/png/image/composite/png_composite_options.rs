@@ -0,0 +1,50 @@
+use crate::lib::img::png::image::composite::{
+  png_blend_mode::BlendMode, png_porter_duff::PorterDuff,
+};
+
+/// Options used when drawing pixels onto other pixels
+#[derive(Debug, Clone)]
+pub struct CompositeOptions {
+  pub(crate) operator: PorterDuff,
+  pub(crate) blend: BlendMode,
+
+  /// Factor applied to the alpha of the source, from 0 to 1
+  pub(crate) opacity: f32,
+}
+
+impl Default for CompositeOptions {
+  fn default() -> Self {
+    Self {
+      operator: PorterDuff::default(),
+      blend: BlendMode::default(),
+      opacity: 1.0,
+    }
+  }
+}
+
+impl CompositeOptions {
+  /// Create the default compositing options, drawing the source over the
+  /// destination without blending, fully opaque
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Set the operator combining the source with the destination
+  pub fn operator(mut self, operator: PorterDuff) -> Self {
+    self.operator = operator;
+    self
+  }
+
+  /// Set the mode mixing the colors where both the source and the destination
+  /// are present
+  pub fn blend(mut self, blend: BlendMode) -> Self {
+    self.blend = blend;
+    self
+  }
+
+  /// Set the opacity of the source, from 0 to 1
+  pub fn opacity(mut self, opacity: f32) -> Self {
+    self.opacity = opacity;
+    self
+  }
+}
@@ -0,0 +1,131 @@
+use crate::lib::img::png::{
+  image::png_linear_pixels::LinearColor, parse::chunks::fctl::png_alpha_blend::AlphaBlend,
+};
+
+/// Porter–Duff operator combining a source color with the destination color
+/// below it, depending on which of the two covers each part of a pixel
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum PorterDuff {
+  /// Neither color is kept
+  Clear,
+
+  /// The source replaces the destination
+  Source,
+
+  /// The destination is kept
+  Destination,
+
+  /// The source is drawn over the destination
+  #[default]
+  SourceOver,
+
+  /// The destination is drawn over the source
+  DestinationOver,
+
+  /// The source is kept where the destination covers it
+  SourceIn,
+
+  /// The destination is kept where the source covers it
+  DestinationIn,
+
+  /// The source is kept where the destination does not cover it
+  SourceOut,
+
+  /// The destination is kept where the source does not cover it
+  DestinationOut,
+
+  /// The source is drawn over the destination, only where the destination
+  /// covers it
+  SourceAtop,
+
+  /// The destination is drawn over the source, only where the source covers
+  /// it
+  DestinationAtop,
+
+  /// Each color is kept where the other does not cover it
+  Xor,
+
+  /// Both colors are added
+  Plus,
+}
+
+impl PorterDuff {
+  /// Fractions of the source and destination making up the result, given
+  /// their alpha
+  pub fn factors(&self, source_alpha: f32, destination_alpha: f32) -> (f32, f32) {
+    let (source, destination) = (source_alpha, destination_alpha);
+    match self {
+      Self::Clear => (0.0, 0.0),
+      Self::Source => (1.0, 0.0),
+      Self::Destination => (0.0, 1.0),
+      Self::SourceOver => (1.0, 1.0 - source),
+      Self::DestinationOver => (1.0 - destination, 1.0),
+      Self::SourceIn => (destination, 0.0),
+      Self::DestinationIn => (0.0, source),
+      Self::SourceOut => (1.0 - destination, 0.0),
+      Self::DestinationOut => (0.0, 1.0 - source),
+      Self::SourceAtop => (destination, 1.0 - source),
+      Self::DestinationAtop => (1.0 - destination, source),
+      Self::Xor => (1.0 - destination, 1.0 - source),
+      Self::Plus => (1.0, 1.0),
+    }
+  }
+
+  /// Combine premultiplied colors
+  pub(crate) fn apply(&self, source: &LinearColor, destination: &LinearColor) -> LinearColor {
+    let (source_factor, destination_factor) = self.factors(source[3], destination[3]);
+    let alpha: f32 = (source[3] * source_factor + destination[3] * destination_factor).min(1.0);
+    let mut color: LinearColor = [0.0; 4];
+    for ((result, source), destination) in color.iter_mut().zip(source).zip(destination).take(3) {
+      *result = (source * source_factor + destination * destination_factor).clamp(0.0, alpha);
+    }
+    color[3] = alpha;
+    color
+  }
+}
+
+impl From<&AlphaBlend> for PorterDuff {
+  /// Operator used to draw an animation frame onto the previous output
+  fn from(blend: &AlphaBlend) -> Self {
+    match blend {
+      AlphaBlend::Source => Self::Source,
+      AlphaBlend::Over => Self::SourceOver,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const RED: LinearColor = [1.0, 0.0, 0.0, 1.0];
+  const HALF_BLUE: LinearColor = [0.0, 0.0, 0.5, 0.5];
+
+  #[test]
+  fn test_operators() {
+    let cases: [(PorterDuff, LinearColor); 13] = [
+      (PorterDuff::Clear, [0.0; 4]),
+      (PorterDuff::Source, HALF_BLUE),
+      (PorterDuff::Destination, RED),
+      (PorterDuff::SourceOver, [0.5, 0.0, 0.5, 1.0]),
+      (PorterDuff::DestinationOver, RED),
+      (PorterDuff::SourceIn, HALF_BLUE),
+      (PorterDuff::DestinationIn, [0.5, 0.0, 0.0, 0.5]),
+      (PorterDuff::SourceOut, [0.0; 4]),
+      (PorterDuff::DestinationOut, [0.5, 0.0, 0.0, 0.5]),
+      (PorterDuff::SourceAtop, [0.5, 0.0, 0.5, 1.0]),
+      (PorterDuff::DestinationAtop, [0.5, 0.0, 0.0, 0.5]),
+      (PorterDuff::Xor, [0.5, 0.0, 0.0, 0.5]),
+      (PorterDuff::Plus, [1.0, 0.0, 0.5, 1.0]),
+    ];
+    for (operator, expected) in cases {
+      assert_eq!(operator.apply(&HALF_BLUE, &RED), expected, "{operator:?}");
+    }
+  }
+
+  #[test]
+  fn test_alpha_blend() {
+    assert_eq!(PorterDuff::from(&AlphaBlend::Source), PorterDuff::Source);
+    assert_eq!(PorterDuff::from(&AlphaBlend::Over), PorterDuff::SourceOver);
+  }
+}
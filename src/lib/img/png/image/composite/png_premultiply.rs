@@ -0,0 +1,62 @@
+/// Multiply the red, green and blue samples of 8-bit RGBA colors by their
+/// alpha
+pub fn premultiply(colors: &mut [[u8; 4]]) {
+  for color in colors {
+    let alpha: u32 = color[3] as u32;
+    for sample in &mut color[..3] {
+      *sample = ((*sample as u32 * alpha + 127) / 255) as u8;
+    }
+  }
+}
+
+/// Divide the red, green and blue samples of premultiplied 8-bit RGBA colors
+/// by their alpha. Fully transparent colors become transparent black.
+pub fn unpremultiply(colors: &mut [[u8; 4]]) {
+  for color in colors {
+    let alpha: u32 = color[3] as u32;
+    for sample in &mut color[..3] {
+      *sample = match alpha {
+        0 => 0,
+        _ => ((*sample as u32 * 255 + alpha / 2) / alpha).min(255) as u8,
+      };
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use proptest::{prelude::any, prop_assert, proptest};
+
+  proptest! {
+    /// Test unpremultiplying restores colors up to the precision of their
+    /// alpha
+    #[test]
+    fn test_round_trip(color in any::<[u8; 4]>()) {
+      let mut colors: [[u8; 4]; 1] = [color];
+      premultiply(&mut colors);
+      prop_assert!(colors[0][..3].iter().all(|&sample| sample <= color[3]));
+      unpremultiply(&mut colors);
+
+      let tolerance: i32 = match color[3] {
+        0 => 255,
+        alpha => 255 / alpha as i32 + 1,
+      };
+      for (restored, original) in colors[0].iter().zip(color) {
+        prop_assert!((*restored as i32 - original as i32).abs() <= tolerance);
+      }
+    }
+  }
+
+  #[test]
+  fn test_premultiply() {
+    let mut colors: [[u8; 4]; 3] = [[255, 128, 0, 128], [10, 20, 30, 255], [9, 9, 9, 0]];
+    premultiply(&mut colors);
+    assert_eq!(colors, [[128, 64, 0, 128], [10, 20, 30, 255], [0, 0, 0, 0]]);
+    unpremultiply(&mut colors);
+    assert_eq!(
+      colors,
+      [[255, 128, 0, 128], [10, 20, 30, 255], [0, 0, 0, 0]]
+    );
+  }
+}
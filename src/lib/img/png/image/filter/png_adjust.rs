@@ -0,0 +1,319 @@
+use crate::lib::{
+  img::png::{
+    image::filter::png_luma::Luma,
+    parse::chunks::idat::{
+      handle_idat::to_planar,
+      png_pixel_data::PixelData,
+      png_pixel_format::{ColorTarget, PixelLayout},
+    },
+  },
+  util::err::rsm_error::RSMError,
+};
+
+/// Color adjustments work on the samples as stored, encoded with the sRGB
+/// transfer function, as image editors do. Alpha is kept unless stated
+/// otherwise.
+impl PixelData {
+  /// Add an amount from -1 to 1 to the color samples
+  pub fn brightness(&mut self, amount: f32) -> Result<(), RSMError> {
+    if !(-1.0..=1.0).contains(&amount) {
+      return Err(RSMError::OutOfBounds);
+    }
+    self.map_colors(|color| color.map(|sample| sample + amount))
+  }
+
+  /// Scale the distance of the color samples from mid grey by a factor, which
+  /// is at least 0 and lowers contrast below 1
+  pub fn contrast(&mut self, factor: f32) -> Result<(), RSMError> {
+    if !factor.is_finite() || factor < 0.0 {
+      return Err(RSMError::OutOfBounds);
+    }
+    self.map_colors(|color| color.map(|sample| (sample - 0.5) * factor + 0.5))
+  }
+
+  /// Raise the color samples to the inverse of a positive gamma, which
+  /// brightens midtones above 1 and darkens them below
+  pub fn gamma(&mut self, gamma: f32) -> Result<(), RSMError> {
+    if !gamma.is_finite() || gamma <= 0.0 {
+      return Err(RSMError::OutOfBounds);
+    }
+    self.map_colors(|color| color.map(|sample| sample.powf(1.0 / gamma)))
+  }
+
+  /// Scale the distance of the colors from the grey of the same luma by a
+  /// factor, which is at least 0 where 0 gives grey
+  pub fn saturation(&mut self, factor: f32) -> Result<(), RSMError> {
+    if !factor.is_finite() || factor < 0.0 {
+      return Err(RSMError::OutOfBounds);
+    }
+    self.map_colors(|color| {
+      let luma: f32 = Luma::Rec709.luma(color);
+      color.map(|sample| luma + (sample - luma) * factor)
+    })
+  }
+
+  /// Rotate the hue of the colors by an angle in degrees, keeping their luma,
+  /// using the matrix of the CSS `hue-rotate` filter
+  pub fn hue(&mut self, degrees: f32) -> Result<(), RSMError> {
+    if !degrees.is_finite() {
+      return Err(RSMError::OutOfBounds);
+    }
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let matrix: [[f32; 3]; 3] = [
+      [
+        0.213 + cos * 0.787 - sin * 0.213,
+        0.715 - cos * 0.715 - sin * 0.715,
+        0.072 - cos * 0.072 + sin * 0.928,
+      ],
+      [
+        0.213 - cos * 0.213 + sin * 0.143,
+        0.715 + cos * 0.285 + sin * 0.140,
+        0.072 - cos * 0.072 - sin * 0.283,
+      ],
+      [
+        0.213 - cos * 0.213 - sin * 0.787,
+        0.715 - cos * 0.715 + sin * 0.715,
+        0.072 + cos * 0.928 + sin * 0.072,
+      ],
+    ];
+    self.map_colors(|color| {
+      matrix.map(|row| {
+        row
+          .iter()
+          .zip(color)
+          .map(|(weight, sample)| weight * sample)
+          .sum()
+      })
+    })
+  }
+
+  /// Replace the color samples by their complement
+  pub fn invert(&mut self) -> Result<(), RSMError> {
+    self.map_colors(|color| color.map(|sample| 1.0 - sample))
+  }
+
+  /// Make colors whose luma reaches a level from 0 to 1 white, and the others
+  /// black
+  pub fn threshold(&mut self, level: f32) -> Result<(), RSMError> {
+    if !(0.0..=1.0).contains(&level) {
+      return Err(RSMError::OutOfBounds);
+    }
+    self.map_colors(|color| match Luma::Rec709.luma(color) >= level {
+      true => [1.0; 3],
+      false => [0.0; 3],
+    })
+  }
+
+  /// Convert colors to their luma with the given weights, turning RGB pixels
+  /// into grey ones
+  pub fn grayscale(&mut self, luma: Luma) -> Result<(), RSMError> {
+    let color: ColorTarget = match self.color {
+      ColorTarget::Grey | ColorTarget::Rgb => ColorTarget::Grey,
+      ColorTarget::GreyAlpha | ColorTarget::Rgba => ColorTarget::GreyAlpha,
+    };
+    let (channels, has_alpha) = (self.color.channels(), self.color.has_alpha());
+    self.map_pixels(color, |samples, mapped| {
+      mapped[0] = match channels {
+        1 | 2 => samples[0],
+        _ => luma.luma([samples[0], samples[1], samples[2]]),
+      };
+      if has_alpha {
+        mapped[1] = samples[channels - 1];
+      }
+    })
+  }
+
+  /// Map the samples of each channel, alpha included, through a table of 256
+  /// entries, one table per channel of the pixels. 16-bit samples are
+  /// interpolated between the two closest entries.
+  pub fn apply_lut(&mut self, tables: &[[u8; 256]]) -> Result<(), RSMError> {
+    if tables.len() != self.color.channels() {
+      return Err(RSMError::InvalidLength);
+    }
+    self.map_pixels(self.color, |samples, mapped| {
+      for ((mapped, sample), table) in mapped.iter_mut().zip(samples).zip(tables) {
+        let position: f32 = sample * 255.0;
+        let low: usize = position.floor() as usize;
+        let (first, second) = (table[low] as f32, table[(low + 1).min(255)] as f32);
+        *mapped = (first + (second - first) * position.fract()) / 255.0;
+      }
+    })
+  }
+
+  /// Map the red, green and blue samples of each pixel from 0 to 1, where grey
+  /// pixels take the luma of their mapped grey
+  fn map_colors<F>(&mut self, map: F) -> Result<(), RSMError>
+  where
+    F: Fn([f32; 3]) -> [f32; 3],
+  {
+    let has_alpha: bool = self.color.has_alpha();
+    self.map_pixels(self.color, |samples, mapped| {
+      let colors: usize = samples.len() - has_alpha as usize;
+      match colors {
+        1 => mapped[0] = Luma::Rec709.luma(map([samples[0]; 3])),
+        _ => mapped[..3].copy_from_slice(&map([samples[0], samples[1], samples[2]])),
+      }
+      if has_alpha {
+        mapped[colors] = samples[colors];
+      }
+    })
+  }
+
+  /// Rebuild the pixels with a color type, where each pixel is mapped from its
+  /// samples from 0 to 1 to the samples of the new pixel, keeping the bit
+  /// depth and layout
+  fn map_pixels<F>(&mut self, color: ColorTarget, map: F) -> Result<(), RSMError>
+  where
+    F: Fn(&[f32], &mut [f32]),
+  {
+    self.validate()?;
+    let sample_bytes: usize = (self.bit_depth / 8) as usize;
+    let max: f32 = match sample_bytes {
+      2 => 65535.0,
+      _ => 255.0,
+    };
+    let (channels, mapped_channels) = (self.color.channels(), color.channels());
+
+    let mut samples: Vec<f32> = vec![0.0; channels];
+    let mut mapped: Vec<f32> = vec![0.0; mapped_channels];
+    let mut data: Vec<u8> = Vec::with_capacity(self.data.len() / channels * mapped_channels);
+    for pixel in self.interleaved().chunks_exact(channels * sample_bytes) {
+      for (sample, bytes) in samples.iter_mut().zip(pixel.chunks_exact(sample_bytes)) {
+        *sample = match bytes {
+          [value] => *value as f32,
+          _ => u16::from_be_bytes([bytes[0], bytes[1]]) as f32,
+        } / max;
+      }
+      map(&samples, &mut mapped);
+      for sample in &mapped {
+        let value: u16 = (sample.clamp(0.0, 1.0) * max).round() as u16;
+        match sample_bytes {
+          2 => data.extend_from_slice(&value.to_be_bytes()),
+          _ => data.push(value as u8),
+        }
+      }
+    }
+
+    if self.layout == PixelLayout::Planar {
+      data = to_planar(&data, mapped_channels, sample_bytes);
+    }
+    self.data = data;
+    self.color = color;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn pixels() -> PixelData {
+    PixelData::from_rgba8(2, 1, &[[255, 128, 0, 200], [10, 20, 30, 40]])
+  }
+
+  #[test]
+  fn test_adjustments() {
+    let adjusted = |adjust: fn(&mut PixelData) -> Result<(), RSMError>| -> Vec<[u8; 4]> {
+      let mut pixels: PixelData = pixels();
+      adjust(&mut pixels).unwrap();
+      pixels.to_rgba8()
+    };
+
+    assert_eq!(
+      adjusted(|pixels| pixels.brightness(0.1)),
+      [[255, 154, 26, 200], [36, 46, 56, 40]]
+    );
+    assert_eq!(
+      adjusted(|pixels| pixels.contrast(0.0)),
+      [[128, 128, 128, 200], [128, 128, 128, 40]]
+    );
+    assert_eq!(adjusted(|pixels| pixels.contrast(1.0)), pixels().to_rgba8());
+    assert_eq!(adjusted(|pixels| pixels.gamma(1.0)), pixels().to_rgba8());
+    assert!(adjusted(|pixels| pixels.gamma(2.2))[0][1] > 128);
+    assert_eq!(
+      adjusted(|pixels| pixels.saturation(1.0)),
+      pixels().to_rgba8()
+    );
+    assert_eq!(adjusted(|pixels| pixels.hue(0.0)), pixels().to_rgba8());
+    assert_eq!(adjusted(|pixels| pixels.hue(360.0)), pixels().to_rgba8());
+    assert_eq!(
+      adjusted(|pixels| pixels.invert()),
+      [[0, 127, 255, 200], [245, 235, 225, 40]]
+    );
+    assert_eq!(
+      adjusted(|pixels| pixels.threshold(0.5)),
+      [[255, 255, 255, 200], [0, 0, 0, 40]]
+    );
+
+    // Grey has no saturation nor hue
+    let grey: Vec<[u8; 4]> = adjusted(|pixels| pixels.saturation(0.0));
+    assert!(grey.iter().all(|[r, g, b, _]| r == g && g == b));
+    let mut rotated: PixelData = PixelData::from_rgba8(1, 1, &grey[..1]);
+    rotated.hue(120.0).unwrap();
+    assert_eq!(rotated.to_rgba8(), grey[..1]);
+
+    let mut pixels: PixelData = pixels();
+    for adjust in [
+      PixelData::brightness(&mut pixels, 1.5),
+      PixelData::contrast(&mut pixels, -1.0),
+      PixelData::gamma(&mut pixels, 0.0),
+      PixelData::saturation(&mut pixels, f32::INFINITY),
+      PixelData::hue(&mut pixels, f32::NAN),
+      PixelData::threshold(&mut pixels, 2.0),
+    ] {
+      assert!(matches!(adjust, Err(RSMError::OutOfBounds)));
+    }
+  }
+
+  #[test]
+  fn test_grayscale() {
+    for (luma, expected) in [(Luma::Rec601, 151), (Luma::Rec709, 146)] {
+      let mut pixels: PixelData = pixels();
+      pixels.grayscale(luma).unwrap();
+      assert_eq!(pixels.color, ColorTarget::GreyAlpha);
+      assert_eq!(pixels.data[..2], [expected, 200]);
+    }
+
+    let mut planar: PixelData = PixelData {
+      data: vec![0, 255, 0, 255, 0, 0],
+      width: 2,
+      height: 1,
+      color: ColorTarget::Rgb,
+      bit_depth: 8,
+      layout: PixelLayout::Planar,
+    };
+    planar.grayscale(Luma::Rec709).unwrap();
+    assert_eq!(
+      (planar.color, planar.data),
+      (ColorTarget::Grey, vec![0, 237])
+    );
+  }
+
+  #[test]
+  fn test_lut() {
+    let identity: [u8; 256] = std::array::from_fn(|index| index as u8);
+    let inverse: [u8; 256] = std::array::from_fn(|index| 255 - index as u8);
+
+    let mut pixels: PixelData = pixels();
+    pixels
+      .apply_lut(&[inverse, identity, identity, inverse])
+      .unwrap();
+    assert_eq!(pixels.to_rgba8(), [[0, 128, 0, 55], [245, 20, 30, 215]]);
+    assert!(matches!(
+      pixels.apply_lut(&[identity]),
+      Err(RSMError::InvalidLength)
+    ));
+
+    // 16-bit samples fall between entries
+    let mut deep: PixelData = PixelData {
+      data: vec![0x80, 0x80],
+      width: 1,
+      height: 1,
+      color: ColorTarget::Grey,
+      bit_depth: 16,
+      layout: PixelLayout::Interleaved,
+    };
+    deep.apply_lut(&[inverse]).unwrap();
+    assert_eq!(deep.data, [0x7f, 0x7f]);
+  }
+}
@@ -0,0 +1,226 @@
+use crate::lib::{
+  img::png::{
+    image::{
+      filter::png_convolve::{convolve_line, separable},
+      png_linear_pixels::{LinearColor, LinearPixels},
+      resample::png_resample::combine,
+    },
+    parse::chunks::idat::png_pixel_data::PixelData,
+  },
+  util::err::rsm_error::RSMError,
+};
+
+impl PixelData {
+  /// Blur the pixels with a Gaussian of a standard deviation in pixels,
+  /// repeating the pixels on the edges. Colors are weighted in linear light
+  /// with premultiplied alpha, keeping the format of the pixels.
+  pub fn gaussian_blur(&mut self, sigma: f32) -> Result<(), RSMError> {
+    if !sigma.is_finite() || sigma < 0.0 {
+      return Err(RSMError::OutOfBounds);
+    }
+    let pixels: LinearPixels = LinearPixels::from_pixels(self)?;
+    if pixels.data.is_empty() || sigma == 0.0 {
+      return Ok(());
+    }
+    self.set_linear(&gaussian(&pixels, sigma));
+    Ok(())
+  }
+
+  /// Blur the pixels by averaging the square of pixels within a radius around
+  /// each one, repeating the pixels on the edges. Each average updates a
+  /// running sum, so that large radii take as long as small ones.
+  pub fn box_blur(&mut self, radius: u32) -> Result<(), RSMError> {
+    let pixels: LinearPixels = LinearPixels::from_pixels(self)?;
+    if pixels.data.is_empty() || radius == 0 {
+      return Ok(());
+    }
+    self.set_linear(&separable(&pixels, |line| box_line(line, radius as usize)));
+    Ok(())
+  }
+
+  /// Sharpen the pixels by adding their difference with a Gaussian blur of a
+  /// standard deviation, multiplied by an amount. Pixels differing from the
+  /// blur by less than a threshold from 0 to 1 are kept, which avoids
+  /// sharpening noise in flat areas.
+  pub fn unsharp_mask(&mut self, sigma: f32, amount: f32, threshold: f32) -> Result<(), RSMError> {
+    if !sigma.is_finite() || sigma < 0.0 || !amount.is_finite() || amount < 0.0 {
+      return Err(RSMError::OutOfBounds);
+    }
+    if !(0.0..=1.0).contains(&threshold) {
+      return Err(RSMError::OutOfBounds);
+    }
+    let mut pixels: LinearPixels = LinearPixels::from_pixels(self)?;
+    if pixels.data.is_empty() || sigma == 0.0 {
+      return Ok(());
+    }
+
+    let blurred: LinearPixels = gaussian(&pixels, sigma);
+    for (pixel, blur) in pixels.data.iter_mut().zip(&blurred.data) {
+      let difference: f32 = pixel
+        .iter()
+        .zip(blur)
+        .map(|(sample, blurred)| (sample - blurred).abs())
+        .fold(0.0, f32::max);
+      if difference > threshold {
+        *pixel = combine([(&*pixel, 1.0 + amount), (blur, -amount)].into_iter());
+      }
+    }
+    self.set_linear(&pixels);
+    Ok(())
+  }
+}
+
+/// Blur linear pixels with a Gaussian, whose weights are taken up to three
+/// standard deviations away, and no further than the size of the pixels since
+/// farther taps only repeat the edges
+fn gaussian(pixels: &LinearPixels, sigma: f32) -> LinearPixels {
+  let radius: usize = ((sigma * 3.0).ceil() as usize).min(pixels.width.max(pixels.height));
+  let mut weights: Vec<f32> = (0..=2 * radius)
+    .map(|tap| {
+      let distance: f32 = tap as f32 - radius as f32;
+      (-distance * distance / (2.0 * sigma * sigma)).exp()
+    })
+    .collect();
+  let sum: f32 = weights.iter().sum();
+  weights.iter_mut().for_each(|weight| *weight /= sum);
+  separable(pixels, |line| convolve_line(line, &weights))
+}
+
+/// Average the colors within a radius around each color of a line, repeating
+/// the colors on the edges
+fn box_line(line: &[LinearColor], radius: usize) -> Vec<LinearColor> {
+  let last: usize = line.len() - 1;
+  let at = |position: isize| -> &LinearColor { &line[position.clamp(0, last as isize) as usize] };
+  let size: f64 = (2 * radius + 1) as f64;
+
+  // Sums are kept in double precision so that errors do not build up. The
+  // first window holds the radius repeated first colors, the colors of the
+  // line within the radius, then the last color repeated past its end.
+  let mut sum: [f64; 4] = [0.0; 4];
+  let repeated: [(&LinearColor, usize); 2] = [
+    (&line[0], radius),
+    (&line[last], radius.saturating_sub(last)),
+  ];
+  for (color, count) in repeated {
+    for (total, sample) in sum.iter_mut().zip(color) {
+      *total += *sample as f64 * count as f64;
+    }
+  }
+  for color in &line[..=radius.min(last)] {
+    for (total, sample) in sum.iter_mut().zip(color) {
+      *total += *sample as f64;
+    }
+  }
+
+  let mut averages: Vec<LinearColor> = Vec::with_capacity(line.len());
+  for position in 0..line.len() as isize {
+    averages.push(sum.map(|total| (total / size) as f32));
+    let (entering, leaving) = (
+      at(position + radius as isize + 1),
+      at(position - radius as isize),
+    );
+    for ((total, entering), leaving) in sum.iter_mut().zip(entering).zip(leaving) {
+      *total += (*entering - *leaving) as f64;
+    }
+  }
+  averages
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Black line with a white pixel in the middle
+  fn line(width: u32) -> PixelData {
+    let colors: Vec<[u8; 4]> = (0..width)
+      .map(|x| match x == width / 2 {
+        true => [255, 255, 255, 255],
+        false => [0, 0, 0, 255],
+      })
+      .collect();
+    PixelData::from_rgba8(width, 1, &colors)
+  }
+
+  #[test]
+  fn test_box_line() {
+    let line: Vec<LinearColor> = [0.0, 0.0, 0.9, 0.0, 0.3]
+      .iter()
+      .map(|&value| [value, value, value, 1.0])
+      .collect();
+    let averages: Vec<f32> = box_line(&line, 1).iter().map(|color| color[0]).collect();
+    for (average, expected) in averages.iter().zip([0.0, 0.3, 0.3, 0.4, 0.2]) {
+      assert!((average - expected).abs() < 1e-6, "{averages:?}");
+    }
+
+    // Radii beyond the line average it with its repeated edges
+    let averages: Vec<LinearColor> = box_line(&line[..2], 5);
+    assert!(
+      averages
+        .iter()
+        .all(|color| color[0] == 0.0 && color[3] == 1.0)
+    );
+  }
+
+  #[test]
+  fn test_blur() {
+    let mut gaussian: PixelData = line(9);
+    gaussian.gaussian_blur(1.0).unwrap();
+    let mut boxed: PixelData = line(9);
+    boxed.box_blur(1).unwrap();
+
+    for blurred in [gaussian, boxed] {
+      let red: Vec<u8> = blurred.to_rgba8().iter().map(|color| color[0]).collect();
+      assert!(red[4] < 255 && red[3] > 0 && red[5] > 0, "{red:?}");
+      assert_eq!(red[3], red[5]);
+      assert!(red[..4].windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    // Huge sizes read no further than the edges, where a box averages the
+    // whole image
+    let colors: [[u8; 4]; 4] = [[0, 0, 0, 255], [255, 255, 255, 255], [0; 4], [0; 4]];
+    let mut pixels: PixelData = PixelData::from_rgba8(2, 2, &colors);
+    pixels.gaussian_blur(1e30).unwrap();
+    assert!(
+      pixels
+        .to_rgba8()
+        .iter()
+        .all(|color| (64..192).contains(&color[3]))
+    );
+
+    let mut pixels: PixelData = PixelData::from_rgba8(2, 2, &colors);
+    pixels.box_blur(u32::MAX).unwrap();
+    let blurred: Vec<[u8; 4]> = pixels.to_rgba8();
+    assert!(
+      blurred.iter().all(|&color| color == blurred[0]),
+      "{blurred:?}"
+    );
+    assert_eq!(blurred[0][3], 128);
+
+    let mut pixels: PixelData = line(9);
+    pixels.gaussian_blur(0.0).unwrap();
+    assert_eq!(pixels.data, line(9).data);
+    assert!(pixels.gaussian_blur(-1.0).is_err());
+  }
+
+  #[test]
+  fn test_unsharp_mask() {
+    let colors: Vec<[u8; 4]> = (0..8)
+      .map(|x| match x < 4 {
+        true => [64, 64, 64, 255],
+        false => [192, 192, 192, 255],
+      })
+      .collect();
+    let mut pixels: PixelData = PixelData::from_rgba8(8, 1, &colors);
+    pixels.unsharp_mask(1.0, 1.0, 0.0).unwrap();
+    let red: Vec<u8> = pixels.to_rgba8().iter().map(|color| color[0]).collect();
+    // The edge gains contrast, away from it the pixels are kept
+    assert!(red[3] < 64 && red[4] > 192, "{red:?}");
+    assert_eq!((red[0], red[7]), (64, 192));
+
+    // A high threshold keeps every pixel
+    let mut pixels: PixelData = PixelData::from_rgba8(8, 1, &colors);
+    pixels.unsharp_mask(1.0, 1.0, 1.0).unwrap();
+    assert_eq!(pixels.to_rgba8(), colors);
+    assert!(pixels.unsharp_mask(1.0, -1.0, 0.0).is_err());
+  }
+}
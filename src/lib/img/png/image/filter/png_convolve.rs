@@ -0,0 +1,164 @@
+use crate::lib::{
+  img::png::{
+    image::{
+      filter::{png_edge_mode::EdgeMode, png_kernel::Kernel},
+      png_linear_pixels::{LinearColor, LinearPixels},
+      resample::png_resample::combine,
+    },
+    parse::chunks::idat::png_pixel_data::PixelData,
+  },
+  util::err::rsm_error::RSMError,
+};
+
+/// Color read beyond the edges in [transparent](EdgeMode::Transparent) mode
+const TRANSPARENT: LinearColor = [0.0; 4];
+
+impl PixelData {
+  /// Convolve the pixels with a [kernel](Kernel), reading beyond the edges
+  /// as given by an [edge mode](EdgeMode). Colors are weighted in linear
+  /// light with premultiplied alpha, which is convolved as well, keeping the
+  /// format of the pixels.
+  pub fn convolve(&mut self, kernel: &Kernel, edges: EdgeMode) -> Result<(), RSMError> {
+    let pixels: LinearPixels = LinearPixels::from_pixels(self)?;
+    let radius: isize = (kernel.size() / 2) as isize;
+    let mut convolved: LinearPixels = LinearPixels::new(pixels.width, pixels.height);
+
+    for (index, pixel) in convolved.data.iter_mut().enumerate() {
+      let (x, y) = (
+        (index % pixels.width) as isize,
+        (index / pixels.width) as isize,
+      );
+      let taps = kernel.weights().iter().enumerate().map(|(tap, weight)| {
+        let (dx, dy) = (
+          (tap % kernel.size()) as isize - radius,
+          (tap / kernel.size()) as isize - radius,
+        );
+        let color: &LinearColor = match (
+          edges.index(x + dx, pixels.width),
+          edges.index(y + dy, pixels.height),
+        ) {
+          (Some(column), Some(row)) => &pixels.data[row * pixels.width + column],
+          _ => &TRANSPARENT,
+        };
+        (color, *weight)
+      });
+      *pixel = combine(taps);
+    }
+    self.set_linear(&convolved);
+    Ok(())
+  }
+}
+
+/// Filter each row of linear pixels, then each column, where the filter maps
+/// a line of colors to the filtered line
+pub(crate) fn separable<F>(pixels: &LinearPixels, filter: F) -> LinearPixels
+where
+  F: Fn(&[LinearColor]) -> Vec<LinearColor>,
+{
+  let (width, height) = (pixels.width, pixels.height);
+  let mut horizontal: LinearPixels = LinearPixels::new(width, height);
+  for (source, row) in pixels
+    .data
+    .chunks_exact(width)
+    .zip(horizontal.data.chunks_exact_mut(width))
+  {
+    row.copy_from_slice(&filter(source));
+  }
+
+  let mut filtered: LinearPixels = LinearPixels::new(width, height);
+  let mut column: Vec<LinearColor> = Vec::with_capacity(height);
+  for x in 0..width {
+    column.clear();
+    column.extend((0..height).map(|y| horizontal.data[y * width + x]));
+    for (y, color) in filter(&column).into_iter().enumerate() {
+      filtered.data[y * width + x] = color;
+    }
+  }
+  filtered
+}
+
+/// Convolve a line of colors with weights centered on each color, repeating
+/// the colors on the edges
+pub(crate) fn convolve_line(line: &[LinearColor], weights: &[f32]) -> Vec<LinearColor> {
+  let radius: isize = (weights.len() / 2) as isize;
+  (0..line.len() as isize)
+    .map(|position| {
+      combine(weights.iter().enumerate().map(|(tap, weight)| {
+        let index: Option<usize> =
+          EdgeMode::Clamp.index(position + tap as isize - radius, line.len());
+        (&line[index.unwrap_or_default()], *weight)
+      }))
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::img::png::parse::chunks::idat::png_pixel_format::{ColorTarget, PixelLayout};
+
+  fn grey(data: Vec<u8>, width: u32) -> PixelData {
+    PixelData {
+      height: data.len() as u32 / width,
+      data,
+      width,
+      color: ColorTarget::Grey,
+      bit_depth: 8,
+      layout: PixelLayout::Interleaved,
+    }
+  }
+
+  #[test]
+  fn test_identity() {
+    let mut weights: Vec<f32> = vec![0.0; 25];
+    weights[12] = 1.0;
+    let kernel: Kernel = Kernel::new(5, weights).unwrap();
+    for edges in [
+      EdgeMode::Clamp,
+      EdgeMode::Wrap,
+      EdgeMode::Mirror,
+      EdgeMode::Transparent,
+    ] {
+      let mut pixels: PixelData = grey((0..12).map(|value| value * 20).collect(), 4);
+      pixels.convolve(&kernel, edges).unwrap();
+      assert_eq!(
+        pixels.data,
+        (0..12).map(|value| value * 20).collect::<Vec<u8>>()
+      );
+    }
+  }
+
+  #[test]
+  fn test_edges() {
+    // Shifting the pixels to the left reads beyond the right edge
+    let shift: Kernel = Kernel::new(3, vec![0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0]).unwrap();
+    let cases: [(EdgeMode, [u8; 3]); 4] = [
+      (EdgeMode::Clamp, [100, 200, 200]),
+      (EdgeMode::Wrap, [100, 200, 50]),
+      (EdgeMode::Mirror, [100, 200, 100]),
+      (EdgeMode::Transparent, [100, 200, 0]),
+    ];
+    for (edges, expected) in cases {
+      let mut pixels: PixelData = grey(vec![50, 100, 200], 3);
+      pixels.convolve(&shift, edges).unwrap();
+      assert_eq!(pixels.data, expected, "{edges:?}");
+    }
+  }
+
+  #[test]
+  fn test_flat_areas() {
+    for kernel in [
+      Kernel::sharpen(),
+      Kernel::new(3, vec![1.0; 9]).unwrap().normalized(),
+    ] {
+      let mut pixels: PixelData = PixelData::from_rgba8(3, 3, &[[90, 150, 30, 200]; 9]);
+      pixels.convolve(&kernel, EdgeMode::Clamp).unwrap();
+      assert!(
+        pixels
+          .to_rgba8()
+          .iter()
+          .all(|&color| color == [90, 150, 30, 200])
+      );
+    }
+  }
+}
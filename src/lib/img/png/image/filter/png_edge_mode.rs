@@ -0,0 +1,72 @@
+/// Pixels read beyond the edges of an image when filtering it
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum EdgeMode {
+  /// Repeat the pixels on the edges
+  #[default]
+  Clamp,
+
+  /// Continue with the pixels of the opposite edge
+  Wrap,
+
+  /// Reflect the pixels along the edges, without repeating them
+  Mirror,
+
+  /// Read transparent black
+  Transparent,
+}
+
+impl EdgeMode {
+  /// Index of the pixel read at a position of a line of pixels, or none for
+  /// transparent black
+  pub(crate) fn index(&self, position: isize, size: usize) -> Option<usize> {
+    let last: isize = size as isize - 1;
+    match self {
+      _ if (0..=last).contains(&position) => Some(position as usize),
+      Self::Clamp => Some(position.clamp(0, last) as usize),
+      Self::Wrap => Some(position.rem_euclid(size as isize) as usize),
+      Self::Mirror => {
+        let period: isize = (2 * last).max(1);
+        let position: isize = position.rem_euclid(period);
+        Some(match position > last {
+          true => period - position,
+          false => position,
+        } as usize)
+      }
+      Self::Transparent => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_index() {
+    let indices = |mode: EdgeMode, size: usize| -> Vec<Option<usize>> {
+      (-3..size as isize + 3)
+        .map(|position| mode.index(position, size))
+        .collect()
+    };
+    let some =
+      |indices: &[usize]| -> Vec<Option<usize>> { indices.iter().copied().map(Some).collect() };
+
+    assert_eq!(
+      indices(EdgeMode::Clamp, 3),
+      some(&[0, 0, 0, 0, 1, 2, 2, 2, 2])
+    );
+    assert_eq!(
+      indices(EdgeMode::Wrap, 3),
+      some(&[0, 1, 2, 0, 1, 2, 0, 1, 2])
+    );
+    assert_eq!(
+      indices(EdgeMode::Mirror, 3),
+      some(&[1, 2, 1, 0, 1, 2, 1, 0, 1])
+    );
+    assert_eq!(indices(EdgeMode::Mirror, 1), some(&[0; 7]));
+    assert_eq!(
+      indices(EdgeMode::Transparent, 2),
+      [None, None, None, Some(0), Some(1), None, None, None]
+    );
+  }
+}
@@ -0,0 +1,78 @@
+use crate::lib::util::err::rsm_error::RSMError;
+
+/// Square grid of weights given to the pixels around each pixel when
+/// convolving an image, such as 3x3 or 5x5
+#[derive(Debug, Clone, PartialEq)]
+pub struct Kernel {
+  size: usize,
+  weights: Vec<f32>,
+}
+
+impl Kernel {
+  /// Create a kernel from rows of weights, whose odd size is their number
+  pub fn new(size: usize, weights: Vec<f32>) -> Result<Self, RSMError> {
+    if size.is_multiple_of(2) {
+      return Err(RSMError::OutOfBounds);
+    }
+    if weights.len() != size * size {
+      return Err(RSMError::InvalidLength);
+    }
+    if weights.iter().any(|weight| !weight.is_finite()) {
+      return Err(RSMError::InvalidContent);
+    }
+    Ok(Self { size, weights })
+  }
+
+  /// Kernel sharpening edges
+  pub fn sharpen() -> Self {
+    Self {
+      size: 3,
+      weights: vec![0.0, -1.0, 0.0, -1.0, 5.0, -1.0, 0.0, -1.0, 0.0],
+    }
+  }
+
+  /// Divide the weights by their sum, if it is not 0, so that flat areas
+  /// keep their color
+  pub fn normalized(mut self) -> Self {
+    let sum: f32 = self.weights.iter().sum();
+    if sum != 0.0 {
+      self.weights.iter_mut().for_each(|weight| *weight /= sum);
+    }
+    self
+  }
+
+  /// Number of rows and columns of weights
+  pub fn size(&self) -> usize {
+    self.size
+  }
+
+  /// Weights row by row
+  pub fn weights(&self) -> &[f32] {
+    &self.weights
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_kernel() {
+    assert!(matches!(
+      Kernel::new(4, vec![0.0; 16]),
+      Err(RSMError::OutOfBounds)
+    ));
+    assert!(matches!(
+      Kernel::new(3, vec![0.0; 8]),
+      Err(RSMError::InvalidLength)
+    ));
+    assert!(matches!(
+      Kernel::new(1, vec![f32::NAN]),
+      Err(RSMError::InvalidContent)
+    ));
+
+    let kernel: Kernel = Kernel::new(3, vec![1.0; 9]).unwrap().normalized();
+    assert_eq!(kernel.weights(), [1.0 / 9.0; 9]);
+    assert_eq!(Kernel::sharpen().normalized(), Kernel::sharpen());
+  }
+}
@@ -0,0 +1,30 @@
+/// Weights of red, green and blue making up the luma of a color
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Luma {
+  /// Weights of ITU-R BT.601, used for standard definition video and JPEG
+  Rec601,
+
+  /// Weights of ITU-R BT.709, matching the primaries of sRGB, as used when
+  /// decoding
+  #[default]
+  Rec709,
+}
+
+impl Luma {
+  /// Weights of red, green and blue, summing to 1
+  pub fn coefficients(&self) -> [f32; 3] {
+    match self {
+      Self::Rec601 => [0.299, 0.587, 0.114],
+      Self::Rec709 => [0.2126, 0.7152, 0.0722],
+    }
+  }
+
+  /// Luma of red, green and blue samples
+  pub fn luma(&self, color: [f32; 3]) -> f32 {
+    color
+      .iter()
+      .zip(self.coefficients())
+      .map(|(sample, weight)| sample * weight)
+      .sum()
+  }
+}
@@ -1,6 +1,7 @@
 use crate::lib::img::png::parse::{
   chunks::{idat::png_pixel_data::PixelData, ihdr::png_header::PNGHeader},
-  states::data::png_metadata::PNGMetadata,
+  png_decode_warning::DecodeWarning,
+  states::data::{png_metadata::PNGMetadata, png_trailing_data::TrailingData},
 };
 
 /// Represents a [PNG](https://w3c.github.io/png) image.
@@ -9,4 +10,10 @@ pub struct PNGImage {
   pub header: PNGHeader,
   pub meta: PNGMetadata,
   pub data: PixelData,
+
+  /// Ancillary chunks that were rejected while decoding
+  pub warnings: Vec<DecodeWarning>,
+
+  /// Bytes found after the IEND chunk
+  pub trailing_data: Option<TrailingData>,
 }
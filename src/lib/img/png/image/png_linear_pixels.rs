@@ -0,0 +1,250 @@
+use crate::lib::{
+  img::png::parse::chunks::idat::{
+    handle_idat::to_planar,
+    png_pixel_data::PixelData,
+    png_pixel_format::{ColorTarget, PixelLayout},
+  },
+  util::err::rsm_error::RSMError,
+};
+
+/// RGBA color from 0 to 1 in linear light, whose red, green and blue samples
+/// are multiplied by its alpha
+pub(crate) type LinearColor = [f32; 4];
+
+/// Pixels as linear light colors with premultiplied alpha, which is how they
+/// are resampled and blended without dark fringes
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LinearPixels {
+  pub width: usize,
+  pub height: usize,
+  pub data: Vec<LinearColor>,
+}
+
+impl LinearPixels {
+  /// Create transparent pixels
+  pub(crate) fn new(width: usize, height: usize) -> Self {
+    Self {
+      width,
+      height,
+      data: vec![[0.0; 4]; width * height],
+    }
+  }
+
+  /// Convert decoded pixels, whose samples are encoded with the sRGB transfer
+  /// function
+  pub(crate) fn from_pixels(pixels: &PixelData) -> Result<Self, RSMError> {
+    let (width, height) = (pixels.width as usize, pixels.height as usize);
+    let mut data: Vec<LinearColor> = Vec::with_capacity(width * height);
+    linear_rows(pixels, |_, row| data.extend_from_slice(row))?;
+    Ok(Self {
+      width,
+      height,
+      data,
+    })
+  }
+
+  /// Convert the pixels back to samples of the format of decoded pixels. Luma
+  /// is computed using the Rec. 709 coefficients, as when decoding.
+  pub(crate) fn to_pixels(
+    &self,
+    color: ColorTarget,
+    bit_depth: u8,
+    layout: PixelLayout,
+  ) -> PixelData {
+    let max: f32 = match bit_depth {
+      16 => 65535.0,
+      _ => 255.0,
+    };
+    let sample_bytes: usize = (bit_depth / 8) as usize;
+    let mut data: Vec<u8> = Vec::with_capacity(self.data.len() * color.channels() * sample_bytes);
+
+    for &[r, g, b, a] in &self.data {
+      let a: f32 = a.clamp(0.0, 1.0);
+      let encode = |value: f32| match a > 0.0 {
+        true => to_srgb((value / a).clamp(0.0, 1.0)),
+        false => 0.0,
+      };
+      let [r, g, b] = [encode(r), encode(g), encode(b)];
+      let luma: f32 = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+
+      let samples: &[f32] = match color {
+        ColorTarget::Grey => &[luma],
+        ColorTarget::GreyAlpha => &[luma, a],
+        ColorTarget::Rgb => &[r, g, b],
+        ColorTarget::Rgba => &[r, g, b, a],
+      };
+      for &sample in samples {
+        let value: u16 = (sample * max).round() as u16;
+        match sample_bytes {
+          2 => data.extend_from_slice(&value.to_be_bytes()),
+          _ => data.push(value as u8),
+        }
+      }
+    }
+
+    if layout == PixelLayout::Planar {
+      data = to_planar(&data, color.channels(), sample_bytes);
+    }
+    PixelData {
+      data,
+      width: self.width as u32,
+      height: self.height as u32,
+      color,
+      bit_depth,
+      layout,
+    }
+  }
+}
+
+/// Convert decoded pixels, whose samples are encoded with the sRGB transfer
+/// function, one row at a time, passing each row to a function along with its
+/// index
+pub(crate) fn linear_rows<F>(pixels: &PixelData, mut function: F) -> Result<(), RSMError>
+where
+  F: FnMut(usize, &[LinearColor]),
+{
+  let (width, height) = (pixels.width as usize, pixels.height as usize);
+  let channels: usize = pixels.color.channels();
+  let sample_bytes: usize = match pixels.bit_depth {
+    8 => 1,
+    16 => 2,
+    _ => return Err(RSMError::InvalidContent),
+  };
+  let data = pixels.interleaved();
+  if data.len() != width * height * channels * sample_bytes {
+    return Err(RSMError::InvalidLength);
+  }
+  if data.is_empty() {
+    return Ok(());
+  }
+
+  // 8-bit samples are decoded once for each value
+  let table: Vec<f32> = (0..=255)
+    .map(|value| to_linear(value as f32 / 255.0))
+    .collect();
+  let sample = |bytes: &[u8]| -> f32 {
+    match bytes {
+      [value] => *value as f32 / 255.0,
+      _ => u16::from_be_bytes([bytes[0], bytes[1]]) as f32 / 65535.0,
+    }
+  };
+  let color = |bytes: &[u8]| -> f32 {
+    match bytes {
+      [value] => table[*value as usize],
+      _ => to_linear(sample(bytes)),
+    }
+  };
+
+  let pixel_bytes: usize = channels * sample_bytes;
+  let mut row: Vec<LinearColor> = Vec::with_capacity(width);
+  for (y, bytes) in data.chunks_exact(width * pixel_bytes).enumerate() {
+    row.clear();
+    row.extend(bytes.chunks_exact(pixel_bytes).map(|pixel| {
+      let channel = |index: usize| -> &[u8] { &pixel[index * sample_bytes..][..sample_bytes] };
+      let (r, g, b) = match channels {
+        1 | 2 => {
+          let grey: f32 = color(channel(0));
+          (grey, grey, grey)
+        }
+        _ => (color(channel(0)), color(channel(1)), color(channel(2))),
+      };
+      let a: f32 = match pixels.color.has_alpha() {
+        true => sample(channel(channels - 1)),
+        false => 1.0,
+      };
+      [r * a, g * a, b * a, a]
+    }));
+    function(y, &row);
+  }
+  Ok(())
+}
+
+/// Decode a sample encoded with the sRGB transfer function
+pub(crate) fn to_linear(value: f32) -> f32 {
+  match value <= 0.04045 {
+    true => value / 12.92,
+    false => ((value + 0.055) / 1.055).powf(2.4),
+  }
+}
+
+/// Encode a linear sample with the sRGB transfer function
+pub(crate) fn to_srgb(value: f32) -> f32 {
+  match value <= 0.003_130_8 {
+    true => value * 12.92,
+    false => 1.055 * value.powf(1.0 / 2.4) - 0.055,
+  }
+}
+
+/// Convert an 8-bit RGBA color to linear light
+pub(crate) fn linear_rgba8([r, g, b, a]: [u8; 4]) -> LinearColor {
+  let alpha: f32 = a as f32 / 255.0;
+  let linear = |sample: u8| to_linear(sample as f32 / 255.0) * alpha;
+  [linear(r), linear(g), linear(b), alpha]
+}
+
+impl PixelData {
+  /// Replace the pixels by linear ones, keeping their format
+  pub(crate) fn set_linear(&mut self, pixels: &LinearPixels) {
+    *self = pixels.to_pixels(self.color, self.bit_depth, self.layout);
+  }
+
+  /// Encode an 8-bit RGBA color as the interleaved samples of a pixel
+  pub(crate) fn pixel_bytes(&self, rgba: [u8; 4]) -> Vec<u8> {
+    let pixel: LinearPixels = LinearPixels {
+      width: 1,
+      height: 1,
+      data: vec![linear_rgba8(rgba)],
+    };
+    pixel
+      .to_pixels(self.color, self.bit_depth, PixelLayout::Interleaved)
+      .data
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use proptest::{prelude::any, prop_assert_eq, proptest};
+
+  proptest! {
+    /// Test converting pixels that went through linear light restores them
+    #[test]
+    fn test_round_trip(data in any::<[u8; 12]>(), planar in any::<bool>()) {
+      let layout: PixelLayout = match planar {
+        true => PixelLayout::Planar,
+        false => PixelLayout::Interleaved,
+      };
+      for (color, bit_depth) in [(ColorTarget::Rgba, 8), (ColorTarget::GreyAlpha, 16), (ColorTarget::Rgb, 16)] {
+        let pixel_size: usize = color.channels() * bit_depth as usize / 8;
+        let mut pixels: PixelData = PixelData {
+          data: data[..data.len() / pixel_size * pixel_size].to_vec(),
+          width: (data.len() / pixel_size) as u32,
+          height: 1,
+          color,
+          bit_depth,
+          layout,
+        };
+
+        // The first conversion loses the color of transparent pixels
+        pixels.set_linear(&LinearPixels::from_pixels(&pixels).unwrap());
+        let linear: LinearPixels = LinearPixels::from_pixels(&pixels).unwrap();
+        prop_assert_eq!(linear.to_pixels(color, bit_depth, layout).data, pixels.data);
+      }
+    }
+  }
+
+  #[test]
+  fn test_linear_light() {
+    let pixels: PixelData =
+      PixelData::from_rgba8(2, 1, &[[255, 255, 255, 255], [188, 188, 188, 128]]);
+    let linear: LinearPixels = LinearPixels::from_pixels(&pixels).unwrap();
+    assert_eq!(linear.data[0], [1.0; 4]);
+    assert!((linear.data[1][0] / linear.data[1][3] - 0.5).abs() < 0.01);
+    assert_eq!(
+      linear
+        .to_pixels(ColorTarget::GreyAlpha, 8, PixelLayout::Interleaved)
+        .data,
+      [255, 255, 188, 128]
+    );
+  }
+}
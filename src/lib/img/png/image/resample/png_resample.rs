@@ -0,0 +1,292 @@
+use crate::lib::{
+  img::png::{
+    image::{
+      png_linear_pixels::{LinearColor, LinearPixels, linear_rows},
+      resample::png_resample_filter::ResampleFilter,
+    },
+    parse::chunks::idat::png_pixel_data::PixelData,
+  },
+  util::err::rsm_error::RSMError,
+};
+
+/// Source pixels contributing to a resampled pixel: the first one, followed
+/// by the weight of each
+type Contributions = (usize, Vec<f32>);
+
+impl PixelData {
+  /// Resize the pixels with a [filter](ResampleFilter), keeping their format.
+  /// Each direction is resampled in turn, in linear light with premultiplied
+  /// alpha so that transparent pixels do not darken their neighbours.
+  pub fn resize(
+    &mut self,
+    width: u32,
+    height: u32,
+    filter: ResampleFilter,
+  ) -> Result<(), RSMError> {
+    if width == 0 || height == 0 {
+      return Err(RSMError::OutOfBounds);
+    }
+    let pixels: LinearPixels = LinearPixels::from_pixels(self)?;
+    if pixels.data.is_empty() {
+      return Err(RSMError::InvalidContent);
+    }
+    self.set_linear(&resample(&pixels, width as usize, height as usize, filter));
+    Ok(())
+  }
+
+  /// Shrink the pixels to fit within a size, keeping their aspect ratio.
+  /// Pixels that already fit are kept. Large reductions first average blocks
+  /// of pixels while converting them row by row, then finish with a Lanczos
+  /// filter from at least twice the final size.
+  pub fn thumbnail(&mut self, max_width: u32, max_height: u32) -> Result<(), RSMError> {
+    if max_width == 0 || max_height == 0 {
+      return Err(RSMError::OutOfBounds);
+    }
+    if self.width <= max_width && self.height <= max_height {
+      return Ok(());
+    }
+
+    let scale: f64 =
+      (max_width as f64 / self.width as f64).min(max_height as f64 / self.height as f64);
+    let width: usize = ((self.width as f64 * scale).round() as usize).clamp(1, max_width as usize);
+    let height: usize =
+      ((self.height as f64 * scale).round() as usize).clamp(1, max_height as usize);
+
+    let factor: usize = (self.width as usize / width).min(self.height as usize / height) / 2;
+    let pixels: LinearPixels = match factor >= 2 {
+      true => box_reduce(self, factor)?,
+      false => LinearPixels::from_pixels(self)?,
+    };
+    self.set_linear(&resample(&pixels, width, height, ResampleFilter::Lanczos3));
+    Ok(())
+  }
+}
+
+/// Resample pixels horizontally, then vertically
+pub(crate) fn resample(
+  pixels: &LinearPixels,
+  width: usize,
+  height: usize,
+  filter: ResampleFilter,
+) -> LinearPixels {
+  let mut horizontal: LinearPixels = LinearPixels::new(width, pixels.height);
+  let columns: Vec<Contributions> = contributions(pixels.width, width, filter);
+  for (source, row) in pixels
+    .data
+    .chunks_exact(pixels.width)
+    .zip(horizontal.data.chunks_exact_mut(width))
+  {
+    for (pixel, (first, weights)) in row.iter_mut().zip(&columns) {
+      *pixel = combine(
+        weights
+          .iter()
+          .enumerate()
+          .map(|(i, w)| (&source[first + i], *w)),
+      );
+    }
+  }
+
+  let mut vertical: LinearPixels = LinearPixels::new(width, height);
+  let rows: Vec<Contributions> = contributions(pixels.height, height, filter);
+  for (row, (first, weights)) in vertical.data.chunks_exact_mut(width).zip(&rows) {
+    for (x, pixel) in row.iter_mut().enumerate() {
+      *pixel = combine(
+        weights
+          .iter()
+          .enumerate()
+          .map(|(i, w)| (&horizontal.data[(first + i) * width + x], *w)),
+      );
+    }
+  }
+  vertical
+}
+
+/// Find the source pixels contributing to each resampled pixel along a
+/// direction, with weights summing to 1. Filters are stretched when reducing
+/// so that every source pixel contributes.
+fn contributions(source: usize, target: usize, filter: ResampleFilter) -> Vec<Contributions> {
+  let scale: f32 = source as f32 / target as f32;
+  let stretch: f32 = scale.max(1.0);
+  let support: f32 = filter.support() * stretch;
+
+  (0..target)
+    .map(|index| {
+      let center: f32 = (index as f32 + 0.5) * scale;
+      if filter == ResampleFilter::Nearest {
+        return ((center as usize).min(source - 1), vec![1.0]);
+      }
+
+      let first: usize = (center - support).floor().max(0.0) as usize;
+      let last: usize = ((center + support).ceil() as usize).min(source);
+      let mut weights: Vec<f32> = (first..last)
+        .map(|position| filter.weight((position as f32 + 0.5 - center) / stretch))
+        .collect();
+
+      let sum: f32 = weights.iter().sum();
+      match sum == 0.0 {
+        true => ((center as usize).min(source - 1), vec![1.0]),
+        false => {
+          weights.iter_mut().for_each(|weight| *weight /= sum);
+          (first, weights)
+        }
+      }
+    })
+    .collect()
+}
+
+/// Sum weighted colors, keeping the result within the range of premultiplied
+/// colors, which filters with negative weights may leave
+pub(crate) fn combine<'c>(colors: impl Iterator<Item = (&'c LinearColor, f32)>) -> LinearColor {
+  let mut sum: LinearColor = [0.0; 4];
+  for (color, weight) in colors {
+    for (total, sample) in sum.iter_mut().zip(color) {
+      *total += sample * weight;
+    }
+  }
+  let alpha: f32 = sum[3].clamp(0.0, 1.0);
+  [
+    sum[0].clamp(0.0, alpha),
+    sum[1].clamp(0.0, alpha),
+    sum[2].clamp(0.0, alpha),
+    alpha,
+  ]
+}
+
+/// Average blocks of pixels of a size in linear light, converting one row at
+/// a time, where blocks on the right and bottom edges may be smaller
+fn box_reduce(pixels: &PixelData, size: usize) -> Result<LinearPixels, RSMError> {
+  let (width, height) = (pixels.width as usize, pixels.height as usize);
+  let mut reduced: LinearPixels = LinearPixels::new(width.div_ceil(size), height.div_ceil(size));
+  let mut counts: Vec<f32> = vec![0.0; reduced.data.len()];
+
+  let reduced_width: usize = reduced.width;
+  linear_rows(pixels, |y, row| {
+    let start: usize = (y / size) * reduced_width;
+    for (x, color) in row.iter().enumerate() {
+      let index: usize = start + x / size;
+      for (total, sample) in reduced.data[index].iter_mut().zip(color) {
+        *total += sample;
+      }
+      counts[index] += 1.0;
+    }
+  })?;
+
+  for (color, count) in reduced.data.iter_mut().zip(counts) {
+    color.iter_mut().for_each(|sample| *sample /= count);
+  }
+  Ok(reduced)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::img::png::parse::chunks::idat::png_pixel_format::{ColorTarget, PixelLayout};
+
+  const FILTERS: [ResampleFilter; 5] = [
+    ResampleFilter::Nearest,
+    ResampleFilter::Bilinear,
+    ResampleFilter::CatmullRom,
+    ResampleFilter::Mitchell,
+    ResampleFilter::Lanczos3,
+  ];
+
+  #[test]
+  fn test_contributions() {
+    for filter in FILTERS {
+      for (source, target) in [(10, 3), (3, 10), (7, 7), (1, 5)] {
+        for (first, weights) in contributions(source, target, filter) {
+          assert!(first + weights.len() <= source);
+          assert!((weights.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn test_solid_color() {
+    for filter in FILTERS {
+      let mut pixels: PixelData = PixelData::from_rgba8(5, 3, &[[200, 30, 90, 160]; 15]);
+      pixels.resize(12, 2, filter).unwrap();
+      assert_eq!((pixels.width, pixels.height), (12, 2));
+      assert!(
+        pixels
+          .data
+          .chunks_exact(4)
+          .all(|pixel| pixel == [200, 30, 90, 160])
+      );
+    }
+  }
+
+  #[test]
+  fn test_nearest() {
+    let mut pixels: PixelData = PixelData {
+      data: vec![1, 2, 3, 4],
+      width: 2,
+      height: 2,
+      color: ColorTarget::Grey,
+      bit_depth: 8,
+      layout: PixelLayout::Interleaved,
+    };
+    pixels.resize(4, 2, ResampleFilter::Nearest).unwrap();
+    assert_eq!(pixels.data, [1, 1, 2, 2, 3, 3, 4, 4]);
+    assert!(pixels.resize(0, 2, ResampleFilter::Nearest).is_err());
+  }
+
+  #[test]
+  fn test_premultiplied_linear_light() {
+    // Halving black and white averages light rather than samples
+    let mut pixels: PixelData =
+      PixelData::from_rgba8(2, 1, &[[0, 0, 0, 255], [255, 255, 255, 255]]);
+    pixels.resize(1, 1, ResampleFilter::Bilinear).unwrap();
+    assert_eq!(pixels.data, [188, 188, 188, 255]);
+
+    // Transparent black does not darken red
+    let mut pixels: PixelData = PixelData::from_rgba8(2, 1, &[[0, 0, 0, 0], [255, 0, 0, 255]]);
+    pixels.resize(1, 1, ResampleFilter::Bilinear).unwrap();
+    assert_eq!(pixels.data, [255, 0, 0, 128]);
+  }
+
+  #[test]
+  fn test_box_reduce() {
+    let colors: Vec<[u8; 4]> = (0..9u8).map(|i| [i * 30, 0, 0, 255]).collect();
+    let pixels: PixelData = PixelData::from_rgba8(3, 3, &colors);
+    let linear: LinearPixels = LinearPixels::from_pixels(&pixels).unwrap();
+    let reduced: LinearPixels = box_reduce(&pixels, 2).unwrap();
+    assert_eq!((reduced.width, reduced.height), (2, 2));
+
+    // Blocks on the edges average fewer pixels
+    let average = |indices: &[usize]| -> f32 {
+      indices.iter().map(|&i| linear.data[i][0]).sum::<f32>() / indices.len() as f32
+    };
+    let expected: [f32; 4] = [
+      average(&[0, 1, 3, 4]),
+      average(&[2, 5]),
+      average(&[6, 7]),
+      average(&[8]),
+    ];
+    for (color, expected) in reduced.data.iter().zip(expected) {
+      assert!((color[0] - expected).abs() < 1e-6 && color[3] == 1.0);
+    }
+  }
+
+  #[test]
+  fn test_thumbnail() {
+    let colors: Vec<[u8; 4]> = (0..400 * 100)
+      .map(|i| [(i % 400 / 2) as u8, 0, 0, 255])
+      .collect();
+    let mut pixels: PixelData = PixelData::from_rgba8(400, 100, &colors);
+    pixels.thumbnail(50, 50).unwrap();
+    assert_eq!((pixels.width, pixels.height), (50, 13));
+    // The ramp from left to right is kept
+    let row: Vec<u8> = pixels
+      .data
+      .chunks_exact(4)
+      .take(50)
+      .map(|pixel| pixel[0])
+      .collect();
+    assert!(row.windows(2).all(|pair| pair[0] <= pair[1]));
+
+    pixels.thumbnail(100, 100).unwrap();
+    assert_eq!((pixels.width, pixels.height), (50, 13));
+  }
+}
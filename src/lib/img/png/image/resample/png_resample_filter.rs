@@ -0,0 +1,98 @@
+use std::f32::consts::PI;
+
+/// Filter weighting the source pixels that make up each resampled pixel
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum ResampleFilter {
+  /// Copy the closest source pixel
+  Nearest,
+
+  /// Interpolate linearly between the two closest source pixels
+  Bilinear,
+
+  /// Bicubic filter passing through the source pixels, which keeps edges
+  /// sharp
+  CatmullRom,
+
+  /// Bicubic filter trading some sharpness for less ringing
+  Mitchell,
+
+  /// Windowed sinc filter over 3 source pixels on each side, which is the
+  /// sharpest
+  #[default]
+  Lanczos3,
+}
+
+impl ResampleFilter {
+  /// Distance from the center of a pixel beyond which weights are 0, in
+  /// source pixels when enlarging
+  pub(crate) fn support(&self) -> f32 {
+    match self {
+      Self::Nearest => 0.5,
+      Self::Bilinear => 1.0,
+      Self::CatmullRom | Self::Mitchell => 2.0,
+      Self::Lanczos3 => 3.0,
+    }
+  }
+
+  /// Weight of a source pixel at a distance from the center of a pixel
+  pub(crate) fn weight(&self, distance: f32) -> f32 {
+    let x: f32 = distance.abs();
+    match self {
+      Self::Nearest => (x < 0.5) as u8 as f32,
+      Self::Bilinear => (1.0 - x).max(0.0),
+      Self::CatmullRom => cubic(x, 0.0, 0.5),
+      Self::Mitchell => cubic(x, 1.0 / 3.0, 1.0 / 3.0),
+      Self::Lanczos3 => match x < 3.0 {
+        true => sinc(x) * sinc(x / 3.0),
+        false => 0.0,
+      },
+    }
+  }
+}
+
+/// Mitchell–Netravali cubic filter with parameters B and C
+fn cubic(x: f32, b: f32, c: f32) -> f32 {
+  let value: f32 = match x {
+    _ if x < 1.0 => {
+      (12.0 - 9.0 * b - 6.0 * c) * x * x * x
+        + (-18.0 + 12.0 * b + 6.0 * c) * x * x
+        + (6.0 - 2.0 * b)
+    }
+    _ if x < 2.0 => {
+      (-b - 6.0 * c) * x * x * x
+        + (6.0 * b + 30.0 * c) * x * x
+        + (-12.0 * b - 48.0 * c) * x
+        + (8.0 * b + 24.0 * c)
+    }
+    _ => 0.0,
+  };
+  value / 6.0
+}
+
+fn sinc(x: f32) -> f32 {
+  match x == 0.0 {
+    true => 1.0,
+    false => (PI * x).sin() / (PI * x),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_filters() {
+    for filter in [
+      ResampleFilter::Bilinear,
+      ResampleFilter::CatmullRom,
+      ResampleFilter::Lanczos3,
+    ] {
+      // Interpolating filters keep source pixels when not moving them
+      assert!((filter.weight(0.0) - 1.0).abs() < 1e-6);
+      assert!(filter.weight(1.0).abs() < 1e-6);
+      assert_eq!(filter.weight(filter.support()), 0.0);
+    }
+    assert!((ResampleFilter::Mitchell.weight(0.0) - 8.0 / 9.0).abs() < 1e-6);
+    assert!((ResampleFilter::Mitchell.weight(1.0) - 1.0 / 18.0).abs() < 1e-6);
+  }
+}
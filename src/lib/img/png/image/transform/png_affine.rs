@@ -0,0 +1,322 @@
+use crate::lib::{
+  img::png::{
+    image::{
+      png_linear_pixels::{LinearColor, LinearPixels, linear_rgba8},
+      resample::{png_resample::combine, png_resample_filter::ResampleFilter},
+    },
+    parse::{chunks::idat::png_pixel_data::PixelData, png_limits::Limits},
+  },
+  util::err::rsm_error::RSMError,
+};
+
+/// Most source pixels weighted along a direction by the widest filter
+const MAX_TAPS: usize = 8;
+
+/// Affine transform mapping a point `(x, y)` of the source pixels to
+/// `(a x + b y + c, d x + e y + f)` in the transformed pixels, where pixels
+/// span one unit and the top left corner is the origin
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Affine {
+  matrix: [f64; 6],
+}
+
+impl Default for Affine {
+  fn default() -> Self {
+    Self::IDENTITY
+  }
+}
+
+impl Affine {
+  /// Transform keeping every point in place
+  pub const IDENTITY: Affine = Affine {
+    matrix: [1.0, 0.0, 0.0, 0.0, 1.0, 0.0],
+  };
+
+  /// Create a transform from its coefficients `[a, b, c, d, e, f]`
+  pub fn new(matrix: [f64; 6]) -> Self {
+    Self { matrix }
+  }
+
+  /// Coefficients `[a, b, c, d, e, f]` of the transform
+  pub fn matrix(&self) -> [f64; 6] {
+    self.matrix
+  }
+
+  /// Move points by an offset
+  pub fn translate(x: f64, y: f64) -> Self {
+    Self::new([1.0, 0.0, x, 0.0, 1.0, y])
+  }
+
+  /// Scale points from the origin
+  pub fn scale(x: f64, y: f64) -> Self {
+    Self::new([x, 0.0, 0.0, 0.0, y, 0.0])
+  }
+
+  /// Rotate points clockwise around the origin, as displayed with the y axis
+  /// pointing down
+  pub fn rotate(degrees: f64) -> Self {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    Self::new([cos, -sin, 0.0, sin, cos, 0.0])
+  }
+
+  /// Slant points horizontally by `x` times their y coordinate, and
+  /// vertically by `y` times their x coordinate
+  pub fn shear(x: f64, y: f64) -> Self {
+    Self::new([1.0, x, 0.0, y, 1.0, 0.0])
+  }
+
+  /// Combine with a transform applied afterwards
+  pub fn then(self, next: Affine) -> Self {
+    let [a, b, c, d, e, f] = self.matrix;
+    let [na, nb, nc, nd, ne, nf] = next.matrix;
+    Self::new([
+      na * a + nb * d,
+      na * b + nb * e,
+      na * c + nb * f + nc,
+      nd * a + ne * d,
+      nd * b + ne * e,
+      nd * c + ne * f + nf,
+    ])
+  }
+
+  /// Transform a point
+  pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+    let [a, b, c, d, e, f] = self.matrix;
+    (a * x + b * y + c, d * x + e * y + f)
+  }
+
+  /// Transform undoing this one, if it does not collapse points onto a line
+  pub fn inverse(&self) -> Option<Self> {
+    let [a, b, c, d, e, f] = self.matrix;
+    let determinant: f64 = a * e - b * d;
+    if determinant.abs() < 1e-12 {
+      return None;
+    }
+    Some(Self::new([
+      e / determinant,
+      -b / determinant,
+      (b * f - c * e) / determinant,
+      -d / determinant,
+      a / determinant,
+      (c * d - a * f) / determinant,
+    ]))
+  }
+}
+
+impl PixelData {
+  /// Transform the pixels into new dimensions with an [affine
+  /// transform](Affine), keeping their format. Each pixel is interpolated with
+  /// a [filter](ResampleFilter) around the source point it comes from, in
+  /// linear light with premultiplied alpha. Areas outside the source pixels
+  /// are set to an 8-bit RGBA color. Dimensions exceeding the default
+  /// decoding [limits](Limits) fail with
+  /// [`RSMError::LimitExceeded`].
+  pub fn warp(
+    &mut self,
+    transform: &Affine,
+    width: u32,
+    height: u32,
+    filter: ResampleFilter,
+    fill: [u8; 4],
+  ) -> Result<(), RSMError> {
+    if width == 0 || height == 0 {
+      return Err(RSMError::OutOfBounds);
+    }
+    let limits: Limits = Limits::default();
+    let count: usize = (width as usize)
+      .checked_mul(height as usize)
+      .ok_or(RSMError::LimitExceeded)?;
+    let bytes: u64 = (count as u64).saturating_mul(size_of::<LinearColor>() as u64);
+    if count as u64 > limits.max_pixels || bytes > limits.max_alloc {
+      return Err(RSMError::LimitExceeded);
+    }
+    let inverse: Affine = transform.inverse().ok_or(RSMError::InvalidContent)?;
+    let pixels: LinearPixels = LinearPixels::from_pixels(self)?;
+    let fill: LinearColor = linear_rgba8(fill);
+
+    let mut warped: LinearPixels = LinearPixels::new(width as usize, height as usize);
+    for (y, row) in warped.data.chunks_exact_mut(width as usize).enumerate() {
+      for (x, pixel) in row.iter_mut().enumerate() {
+        let (sx, sy) = inverse.apply(x as f64 + 0.5, y as f64 + 0.5);
+        *pixel = interpolate(&pixels, sx as f32, sy as f32, filter, &fill);
+      }
+    }
+    self.set_linear(&warped);
+    Ok(())
+  }
+
+  /// Rotate the pixels clockwise by any angle around their center, enlarging
+  /// them to hold every rotated pixel. Uncovered areas are set to an 8-bit
+  /// RGBA color.
+  pub fn rotate(
+    &mut self,
+    degrees: f64,
+    filter: ResampleFilter,
+    fill: [u8; 4],
+  ) -> Result<(), RSMError> {
+    if !degrees.is_finite() {
+      return Err(RSMError::OutOfBounds);
+    }
+    let (source_width, source_height) = (self.width as f64, self.height as f64);
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    // Rounding errors must not add a row for right angles
+    let size = |extent: f64| (extent - 1e-6).ceil().max(1.0);
+    let width: f64 = size((source_width * cos).abs() + (source_height * sin).abs());
+    let height: f64 = size((source_width * sin).abs() + (source_height * cos).abs());
+
+    let transform: Affine = Affine::translate(-source_width / 2.0, -source_height / 2.0)
+      .then(Affine::rotate(degrees))
+      .then(Affine::translate(width / 2.0, height / 2.0));
+    self.warp(&transform, width as u32, height as u32, filter, fill)
+  }
+}
+
+/// Interpolate the color at a point of linear pixels, where the pixels beyond
+/// their edges have the fill color
+fn interpolate(
+  pixels: &LinearPixels,
+  x: f32,
+  y: f32,
+  filter: ResampleFilter,
+  fill: &LinearColor,
+) -> LinearColor {
+  let color = |column: isize, row: isize| -> &LinearColor {
+    let inside: bool =
+      (0..pixels.width as isize).contains(&column) && (0..pixels.height as isize).contains(&row);
+    match inside {
+      true => &pixels.data[row as usize * pixels.width + column as usize],
+      false => fill,
+    }
+  };
+  if filter == ResampleFilter::Nearest {
+    return *color(x.floor() as isize, y.floor() as isize);
+  }
+
+  let support: f32 = filter.support();
+  // Positions of the source pixels along a direction with their weights, and
+  // how many are used
+  let taps = |center: f32| -> ([(isize, f32); MAX_TAPS], usize) {
+    let mut taps: [(isize, f32); MAX_TAPS] = [(0, 0.0); MAX_TAPS];
+    let mut count: usize = 0;
+    let first: isize = (center - support).floor() as isize;
+    let last: isize = (center + support).ceil() as isize;
+    for position in (first..last).take(MAX_TAPS) {
+      let weight: f32 = filter.weight(position as f32 + 0.5 - center);
+      if weight != 0.0 {
+        taps[count] = (position, weight);
+        count += 1;
+      }
+    }
+    (taps, count)
+  };
+  let ((columns, column_count), (rows, row_count)) = (taps(x), taps(y));
+  let (columns, rows) = (&columns[..column_count], &rows[..row_count]);
+
+  let sum: f32 = columns.iter().map(|(_, weight)| weight).sum::<f32>()
+    * rows.iter().map(|(_, weight)| weight).sum::<f32>();
+  if sum == 0.0 {
+    return *color(x.floor() as isize, y.floor() as isize);
+  }
+  combine(rows.iter().flat_map(|&(row, row_weight)| {
+    columns
+      .iter()
+      .map(move |&(column, weight)| (color(column, row), row_weight * weight / sum))
+  }))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn colors() -> Vec<[u8; 4]> {
+    (0..12u8)
+      .map(|i| [i * 20, 255 - i * 20, i * 7, 255])
+      .collect()
+  }
+
+  #[test]
+  fn test_affine() {
+    let transform: Affine = Affine::scale(2.0, 3.0)
+      .then(Affine::rotate(90.0))
+      .then(Affine::translate(1.0, 0.0));
+    let (x, y) = transform.apply(1.0, 1.0);
+    assert!((x - -2.0).abs() < 1e-9 && (y - 2.0).abs() < 1e-9);
+
+    let (x, y) = transform.inverse().unwrap().apply(x, y);
+    assert!((x - 1.0).abs() < 1e-9 && (y - 1.0).abs() < 1e-9);
+    assert!(Affine::scale(0.0, 1.0).inverse().is_none());
+  }
+
+  #[test]
+  fn test_warp() {
+    for filter in [
+      ResampleFilter::Nearest,
+      ResampleFilter::Bilinear,
+      ResampleFilter::Lanczos3,
+    ] {
+      let mut pixels: PixelData = PixelData::from_rgba8(4, 3, &colors());
+      pixels
+        .warp(&Affine::IDENTITY, 4, 3, filter, [0; 4])
+        .unwrap();
+      assert_eq!(pixels.to_rgba8(), colors());
+    }
+
+    // Whole pixels are moved, and the uncovered ones filled
+    let mut pixels: PixelData = PixelData::from_rgba8(4, 3, &colors());
+    let translation: Affine = Affine::translate(1.0, 0.0);
+    pixels
+      .warp(&translation, 4, 3, ResampleFilter::Bilinear, [1, 2, 3, 255])
+      .unwrap();
+    let warped: Vec<[u8; 4]> = pixels.to_rgba8();
+    assert_eq!(warped[0], [1, 2, 3, 255]);
+    assert_eq!(warped[1..4], colors()[0..3]);
+
+    assert!(matches!(
+      pixels.warp(
+        &Affine::scale(0.0, 0.0),
+        4,
+        3,
+        ResampleFilter::Nearest,
+        [0; 4]
+      ),
+      Err(RSMError::InvalidContent)
+    ));
+    assert!(matches!(
+      pixels.warp(
+        &Affine::IDENTITY,
+        u32::MAX,
+        u32::MAX,
+        ResampleFilter::Nearest,
+        [0; 4]
+      ),
+      Err(RSMError::LimitExceeded)
+    ));
+  }
+
+  #[test]
+  fn test_rotate() {
+    let mut rotated: PixelData = PixelData::from_rgba8(4, 3, &colors());
+    rotated
+      .rotate(90.0, ResampleFilter::Nearest, [0; 4])
+      .unwrap();
+    let mut expected: PixelData = PixelData::from_rgba8(4, 3, &colors());
+    expected.rotate_90();
+    assert_eq!((rotated.width, rotated.height), (3, 4));
+    assert_eq!(rotated.data, expected.data);
+
+    let mut rotated: PixelData = PixelData::from_rgba8(4, 3, &colors());
+    rotated
+      .rotate(45.0, ResampleFilter::Bilinear, [0; 4])
+      .unwrap();
+    assert_eq!((rotated.width, rotated.height), (5, 5));
+    // The corners are outside the rotated pixels
+    assert_eq!(rotated.to_rgba8()[0], [0; 4]);
+
+    for degrees in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+      assert!(matches!(
+        rotated.rotate(degrees, ResampleFilter::Nearest, [0; 4]),
+        Err(RSMError::OutOfBounds)
+      ));
+    }
+  }
+}
@@ -0,0 +1,43 @@
+use crate::lib::{
+  img::png::{image::png_image::PNGImage, parse::chunks::idat::png_pixel_data::PixelData},
+  util::err::rsm_error::RSMError,
+};
+
+impl PixelData {
+  /// Transform the pixels so that an image with the given EXIF orientation
+  /// (from 1 to 8) displays upright.
+  pub fn apply_orientation(&mut self, orientation: u16) -> Result<(), RSMError> {
+    match orientation {
+      1 => {}
+      2 => self.flip_horizontal(),
+      3 => self.rotate_180(),
+      4 => self.flip_vertical(),
+      5 => self.transpose(),
+      6 => self.rotate_90(),
+      7 => self.transverse(),
+      8 => self.rotate_270(),
+      _ => return Err(RSMError::OutOfBounds),
+    }
+    Ok(())
+  }
+}
+
+impl PNGImage {
+  /// Transform the pixels according to the orientation stored in the EXIF
+  /// data, which is then reset so the image is not transformed twice. The
+  /// dimensions of the header are swapped for orientations 5 to 8.
+  pub fn apply_orientation(&mut self) -> Result<(), RSMError> {
+    let Some(exif) = &mut self.meta.exif else {
+      return Ok(());
+    };
+
+    if let Some(orientation) = exif.orientation() {
+      self.data.apply_orientation(orientation)?;
+      exif.set_orientation(1)?;
+      if (5..=8).contains(&orientation) {
+        std::mem::swap(&mut self.header.width, &mut self.header.height);
+      }
+    }
+    Ok(())
+  }
+}
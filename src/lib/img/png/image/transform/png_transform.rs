@@ -0,0 +1,238 @@
+use crate::lib::{
+  img::png::parse::{chunks::idat::png_pixel_data::PixelData, png_limits::Limits},
+  util::err::rsm_error::RSMError,
+};
+
+impl PixelData {
+  /// Mirror the pixels along the vertical axis
+  pub fn flip_horizontal(&mut self) {
+    let width: usize = self.width as usize;
+    self.remap(self.width, self.height, |x, y| (width - 1 - x, y));
+  }
+
+  /// Mirror the pixels along the horizontal axis
+  pub fn flip_vertical(&mut self) {
+    let height: usize = self.height as usize;
+    self.remap(self.width, self.height, |x, y| (x, height - 1 - y));
+  }
+
+  /// Rotate the pixels by 90 degrees clockwise
+  pub fn rotate_90(&mut self) {
+    let height: usize = self.height as usize;
+    self.remap(self.height, self.width, |x, y| (y, height - 1 - x));
+  }
+
+  /// Rotate the pixels by 180 degrees
+  pub fn rotate_180(&mut self) {
+    let (width, height) = (self.width as usize, self.height as usize);
+    self.remap(self.width, self.height, |x, y| {
+      (width - 1 - x, height - 1 - y)
+    });
+  }
+
+  /// Rotate the pixels by 270 degrees clockwise
+  pub fn rotate_270(&mut self) {
+    let width: usize = self.width as usize;
+    self.remap(self.height, self.width, |x, y| (width - 1 - y, x));
+  }
+
+  /// Mirror the pixels along the main diagonal (top-left to bottom-right)
+  pub fn transpose(&mut self) {
+    self.remap(self.height, self.width, |x, y| (y, x));
+  }
+
+  /// Mirror the pixels along the anti-diagonal (top-right to bottom-left)
+  pub fn transverse(&mut self) {
+    let (width, height) = (self.width as usize, self.height as usize);
+    self.remap(self.height, self.width, |x, y| {
+      (width - 1 - y, height - 1 - x)
+    });
+  }
+
+  /// Keep the pixels within a rectangle, which must lie within the pixels
+  pub fn crop(&mut self, x: u32, y: u32, width: u32, height: u32) -> Result<(), RSMError> {
+    self.validate()?;
+    let inside =
+      |start: u32, size: u32, limit: u32| start.checked_add(size).is_some_and(|end| end <= limit);
+    if width == 0 || height == 0 || !inside(x, width, self.width) || !inside(y, height, self.height)
+    {
+      return Err(RSMError::OutOfBounds);
+    }
+
+    let (x, y) = (x as usize, y as usize);
+    self.remap(width, height, |cx, cy| (cx + x, cy + y));
+    Ok(())
+  }
+
+  /// Add borders of the given widths around the pixels, filled with an 8-bit
+  /// RGBA color. Dimensions exceeding the default decoding [limits](Limits)
+  /// fail with [`RSMError::LimitExceeded`].
+  pub fn pad(
+    &mut self,
+    top: u32,
+    right: u32,
+    bottom: u32,
+    left: u32,
+    fill: [u8; 4],
+  ) -> Result<(), RSMError> {
+    self.validate()?;
+    let width: u32 = [left, right]
+      .iter()
+      .try_fold(self.width, |size, border| size.checked_add(*border))
+      .ok_or(RSMError::OutOfBounds)?;
+    let height: u32 = [top, bottom]
+      .iter()
+      .try_fold(self.height, |size, border| size.checked_add(*border))
+      .ok_or(RSMError::OutOfBounds)?;
+    if width == 0 || height == 0 {
+      return Err(RSMError::OutOfBounds);
+    }
+    let fill: Vec<u8> = self.pixel_bytes(fill);
+    let limits: Limits = Limits::default();
+    let count: usize = (width as usize)
+      .checked_mul(height as usize)
+      .ok_or(RSMError::LimitExceeded)?;
+    let bytes: u64 = (count as u64).saturating_mul(fill.len() as u64);
+    if count as u64 > limits.max_pixels || bytes > limits.max_alloc {
+      return Err(RSMError::LimitExceeded);
+    }
+
+    let (left, top) = (left as usize, top as usize);
+    let (source_width, source_height) = (self.width as usize, self.height as usize);
+    self.remap_filled(
+      width,
+      height,
+      |x, y| {
+        let (sx, sy) = (x.checked_sub(left)?, y.checked_sub(top)?);
+        (sx < source_width && sy < source_height).then_some((sx, sy))
+      },
+      &fill,
+    );
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::lib::{
+    img::png::parse::chunks::idat::{
+      png_pixel_data::PixelData,
+      png_pixel_format::{ColorTarget, PixelLayout},
+    },
+    util::err::rsm_error::RSMError,
+  };
+
+  /// 3x2 greyscale pixels:
+  /// ```text
+  /// 1 2 3
+  /// 4 5 6
+  /// ```
+  fn pixels(layout: PixelLayout) -> PixelData {
+    PixelData {
+      data: vec![1, 2, 3, 4, 5, 6],
+      width: 3,
+      height: 2,
+      color: ColorTarget::Grey,
+      bit_depth: 8,
+      layout,
+    }
+  }
+
+  fn transform(operation: fn(&mut PixelData)) -> (u32, u32, Vec<u8>) {
+    let mut pixels: PixelData = pixels(PixelLayout::Interleaved);
+    operation(&mut pixels);
+    (pixels.width, pixels.height, pixels.data)
+  }
+
+  #[test]
+  fn test_transforms() {
+    assert_eq!(
+      transform(PixelData::flip_horizontal),
+      (3, 2, vec![3, 2, 1, 6, 5, 4])
+    );
+    assert_eq!(
+      transform(PixelData::flip_vertical),
+      (3, 2, vec![4, 5, 6, 1, 2, 3])
+    );
+    assert_eq!(
+      transform(PixelData::rotate_90),
+      (2, 3, vec![4, 1, 5, 2, 6, 3])
+    );
+    assert_eq!(
+      transform(PixelData::rotate_180),
+      (3, 2, vec![6, 5, 4, 3, 2, 1])
+    );
+    assert_eq!(
+      transform(PixelData::rotate_270),
+      (2, 3, vec![3, 6, 2, 5, 1, 4])
+    );
+    assert_eq!(
+      transform(PixelData::transpose),
+      (2, 3, vec![1, 4, 2, 5, 3, 6])
+    );
+    assert_eq!(
+      transform(PixelData::transverse),
+      (2, 3, vec![6, 3, 5, 2, 4, 1])
+    );
+  }
+
+  #[test]
+  fn test_planar_transform() {
+    let mut planar: PixelData = PixelData {
+      data: vec![1, 2, 3, 4, 10, 20, 30, 40],
+      width: 2,
+      height: 2,
+      color: ColorTarget::GreyAlpha,
+      bit_depth: 8,
+      layout: PixelLayout::Planar,
+    };
+    planar.rotate_90();
+    assert_eq!(planar.data, [3, 1, 4, 2, 30, 10, 40, 20]);
+  }
+
+  #[test]
+  fn test_crop() {
+    for layout in [PixelLayout::Interleaved, PixelLayout::Planar] {
+      let mut cropped: PixelData = pixels(layout);
+      cropped.crop(1, 0, 2, 2).unwrap();
+      assert_eq!((cropped.width, cropped.height), (2, 2));
+      assert_eq!(cropped.data, [2, 3, 5, 6]);
+    }
+
+    let mut pixels: PixelData = pixels(PixelLayout::Interleaved);
+    assert!(pixels.crop(2, 0, 2, 1).is_err());
+    assert!(pixels.crop(0, 0, 0, 1).is_err());
+    assert!(pixels.crop(u32::MAX, 0, 2, 1).is_err());
+  }
+
+  #[test]
+  fn test_pad() {
+    let mut padded: PixelData = pixels(PixelLayout::Interleaved);
+    padded.pad(1, 0, 0, 1, [9, 9, 9, 255]).unwrap();
+    assert_eq!((padded.width, padded.height), (4, 3));
+    assert_eq!(padded.data, [9, 9, 9, 9, 9, 1, 2, 3, 9, 4, 5, 6]);
+
+    let mut planar: PixelData = PixelData {
+      data: vec![1, 2, 10, 20],
+      width: 2,
+      height: 1,
+      color: ColorTarget::GreyAlpha,
+      bit_depth: 8,
+      layout: PixelLayout::Planar,
+    };
+    planar.pad(0, 1, 0, 0, [0, 0, 0, 0]).unwrap();
+    assert_eq!(planar.data, [1, 2, 0, 10, 20, 0]);
+
+    // Sizes beyond the limits fail before allocating
+    assert!(matches!(
+      padded.pad(0, u32::MAX - 4, 0, 0, [0; 4]),
+      Err(RSMError::LimitExceeded)
+    ));
+    assert!(matches!(
+      padded.pad(0, 1 << 16, 1 << 12, 0, [0; 4]),
+      Err(RSMError::LimitExceeded)
+    ));
+    padded.pad(0, 1, 0, 0, [0; 4]).unwrap();
+    assert_eq!(padded.width, 5);
+  }
+}
@@ -1,10 +1,64 @@
 pub mod chunk {
   pub mod png_chunk;
   pub mod png_chunk_type;
+  pub mod png_unknown_chunk;
+}
+
+pub mod encode {
+  pub mod png_credentials_signer;
+
+  /// Compression of the image data on several threads
+  #[cfg(feature = "parallel")]
+  pub(crate) mod png_deflate;
+  pub mod png_encode_metadata;
+  pub mod png_encode_options;
+  pub(crate) mod png_encoded_pixels;
+  pub mod png_encoder;
+  pub mod png_filter;
 }
 
 pub mod image {
+  /// Alpha compositing of pixels
+  pub mod composite {
+    pub mod png_blend_mode;
+    pub mod png_composite;
+    pub mod png_composite_options;
+    pub mod png_porter_duff;
+    pub mod png_premultiply;
+  }
+
+  /// Convolution, blurs and color adjustments of the pixels
+  pub mod filter {
+    pub mod png_adjust;
+    pub mod png_blur;
+    pub mod png_convolve;
+    pub mod png_edge_mode;
+    pub mod png_kernel;
+    pub mod png_luma;
+  }
+
   pub mod png_image;
+  pub(crate) mod png_linear_pixels;
+
+  /// Resizing of the pixels
+  pub mod resample {
+    pub mod png_resample;
+    pub mod png_resample_filter;
+  }
+
+  /// Geometric transforms of the pixels
+  pub mod transform {
+    pub mod png_affine;
+    pub mod png_orientation;
+    pub mod png_transform;
+  }
+}
+
+/// Lossless recompression of PNG images
+pub mod optimize {
+  pub mod png_optimize_options;
+  pub mod png_optimizer;
+  pub(crate) mod png_reductions;
 }
 
 pub mod parse {
@@ -13,12 +67,15 @@ pub mod parse {
   pub mod states {
     pub mod data {
       pub mod png_metadata;
+      pub mod png_trailing_data;
       pub mod set_data;
     }
     pub mod png_state;
     pub mod read_idat;
+    pub mod read_iend;
     pub mod read_ihdr;
     pub mod read_post_idat;
+    pub mod read_post_iend;
     pub mod read_post_ihdr;
     pub mod read_signature;
   }
@@ -26,9 +83,24 @@ pub mod parse {
     pub mod png_int;
   }
 
+  pub mod png_decode_options;
+  pub mod png_decode_warning;
+  pub mod png_limits;
   pub mod png_parser;
 }
 
+/// Reduction of the colors of PNG images to a palette
+pub mod quantize {
+  pub mod png_dithering;
+  pub(crate) mod png_histogram;
+  pub(crate) mod png_kmeans;
+  pub(crate) mod png_median_cut;
+  pub(crate) mod png_octree;
+  pub mod png_quantize;
+  pub mod png_quantize_options;
+  pub mod png_quantizer;
+}
+
 pub mod read {
   mod png_read;
 }
@@ -36,3 +108,11 @@ pub mod read {
 pub mod reader {
   pub mod png_reader;
 }
+
+pub mod write {
+  mod png_write;
+}
+
+pub mod writer {
+  pub mod png_writer;
+}
@@ -0,0 +1,66 @@
+use crate::lib::img::png::{
+  encode::png_filter::FilterStrategy, parse::chunks::idat::png_filters::FilterType,
+};
+
+/// Options used when optimizing PNG images
+#[derive(Debug, Clone)]
+pub struct OptimizeOptions {
+  /// Filter strategies tried for each reduction of the pixels
+  pub(crate) filters: Vec<FilterStrategy>,
+
+  /// Compression levels tried for each filter strategy, from 0 (none) to 12
+  /// (smallest)
+  pub(crate) levels: Vec<u8>,
+
+  /// Only keep the metadata describing colors
+  pub(crate) strip: bool,
+}
+
+impl Default for OptimizeOptions {
+  fn default() -> Self {
+    let fixed = [
+      FilterType::None,
+      FilterType::Sub,
+      FilterType::Up,
+      FilterType::Average,
+      FilterType::Paeth,
+    ]
+    .map(FilterStrategy::Fixed);
+
+    Self {
+      filters: [FilterStrategy::Adaptive]
+        .into_iter()
+        .chain(fixed)
+        .collect(),
+      levels: vec![12],
+      strip: false,
+    }
+  }
+}
+
+impl OptimizeOptions {
+  /// Create the default optimization options, trying every filter strategy at
+  /// the highest compression level
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Set the filter strategies to try
+  pub fn filters(mut self, filters: Vec<FilterStrategy>) -> Self {
+    self.filters = filters;
+    self
+  }
+
+  /// Set the compression levels to try, from 0 (none) to 12 (smallest)
+  pub fn levels(mut self, levels: Vec<u8>) -> Self {
+    self.levels = levels;
+    self
+  }
+
+  /// Set whether metadata should be stripped, keeping only the chunks that
+  /// describe colors (`iCCP`, `sRGB`, `gAMA`, `cHRM`, `cICP` and `cLLI`)
+  pub fn strip(mut self, strip: bool) -> Self {
+    self.strip = strip;
+    self
+  }
+}
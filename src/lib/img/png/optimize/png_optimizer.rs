@@ -0,0 +1,87 @@
+use crate::lib::{
+  img::png::{
+    encode::{
+      png_encode_options::EncodeOptions, png_encoded_pixels::EncodedPixels, png_encoder::PNGEncoder,
+    },
+    image::png_image::PNGImage,
+    optimize::{png_optimize_options::OptimizeOptions, png_reductions::reductions},
+    parse::{
+      chunks::idat::png_pixel_format::{BitDepthHandling, ColorTarget},
+      png_decode_options::DecodeOptions,
+    },
+  },
+  util::err::rsm_error::RSMError,
+};
+
+impl PNGImage {
+  /// Encode the image as the smallest PNG file found with the default
+  /// [options](OptimizeOptions).
+  #[inline]
+  pub fn optimize(&self) -> Result<Vec<u8>, RSMError> {
+    self.optimize_with(OptimizeOptions::default())
+  }
+
+  /// Encode the image as the smallest PNG file found using the given
+  /// [options](OptimizeOptions). The pixels are reduced to fewer channels, a
+  /// lower bit depth or a palette whenever every sample is kept intact, then
+  /// each reduction is encoded with every filter strategy and compression
+  /// level to try. Reductions never take the pixels out of the color space of
+  /// their ICC profile.
+  pub fn optimize_with(&self, options: OptimizeOptions) -> Result<Vec<u8>, RSMError> {
+    let mut smallest: Option<Vec<u8>> = None;
+    for pixels in reductions(&self.data, self.meta.icc_profile.as_ref())? {
+      for &filter in &options.filters {
+        for &level in &options.levels {
+          let encode_options: EncodeOptions =
+            EncodeOptions::new().filter(filter).compression(level);
+          let bytes: Vec<u8> = self.encode_reduction(&pixels, &encode_options, options.strip)?;
+
+          if smallest
+            .as_ref()
+            .is_none_or(|smallest| bytes.len() < smallest.len())
+          {
+            smallest = Some(bytes);
+          }
+        }
+      }
+    }
+    smallest.ok_or(RSMError::InvalidContent)
+  }
+
+  /// Optimize the bytes of a PNG file with the default
+  /// [options](OptimizeOptions).
+  #[inline]
+  pub fn optimize_bytes(data: &[u8]) -> Result<Vec<u8>, RSMError> {
+    Self::optimize_bytes_with(data, OptimizeOptions::default())
+  }
+
+  /// Optimize the bytes of a PNG file using the given
+  /// [options](OptimizeOptions), decoding its pixels without loss first. The
+  /// original bytes are returned when they are not larger, and for animated
+  /// images, whose frames cannot be encoded.
+  pub fn optimize_bytes_with(data: &[u8], options: OptimizeOptions) -> Result<Vec<u8>, RSMError> {
+    let decode_options: DecodeOptions = DecodeOptions::new()
+      .color(ColorTarget::Rgba)
+      .bit_depth(BitDepthHandling::Keep)
+      .keep_unknown_chunks(true);
+    let image: PNGImage = Self::read_bytes_with(data, decode_options)?;
+    if image.meta.animation_control.is_some() {
+      return Ok(data.to_vec());
+    }
+
+    let optimized: Vec<u8> = image.optimize_with(options)?;
+    match optimized.len() < data.len() {
+      true => Ok(optimized),
+      false => Ok(data.to_vec()),
+    }
+  }
+
+  fn encode_reduction(
+    &self,
+    pixels: &EncodedPixels,
+    options: &EncodeOptions,
+    strip: bool,
+  ) -> Result<Vec<u8>, RSMError> {
+    PNGEncoder::new(self, options).encode_pixels(pixels, strip)
+  }
+}
@@ -0,0 +1,419 @@
+use crate::lib::{
+  img::png::{
+    encode::png_encoded_pixels::{EncodedPixels, index_bit_depth, pack},
+    parse::chunks::{
+      iccp::png_icc_profile::ICCProfile,
+      idat::png_pixel_data::PixelData,
+      ihdr::{png_bit_depth::BitDepth, png_color_type::ColorType},
+      plte::png_palette::Palette,
+      trns::png_transparency::Transparency,
+    },
+  },
+  util::err::rsm_error::RSMError,
+};
+use std::{
+  borrow::Cow,
+  collections::{HashMap, hash_map::Entry},
+};
+
+/// Samples of decoded pixels, as values of at most 16 bits
+struct Samples {
+  values: Vec<u16>,
+  channels: usize,
+
+  /// Largest value of a sample, 255 or 65535
+  max: u16,
+}
+
+/// Alpha channel that the reduced pixels need
+#[derive(Debug, PartialEq)]
+enum Alpha {
+  /// Every pixel is opaque
+  Opaque,
+
+  /// Pixels are either opaque or fully transparent, and the color of the
+  /// transparent ones is never used by the opaque ones
+  Key(Vec<u16>),
+
+  /// Alpha varies between pixels
+  Channel,
+}
+
+impl Samples {
+  /// Read the samples of the pixels, keeping the most significant byte of
+  /// 16-bit samples when the least significant byte always repeats it
+  fn new(pixels: &EncodedPixels) -> Self {
+    let channels: usize = pixels.channels();
+    if pixels.bit_depth == BitDepth::D8 {
+      let values: Vec<u16> = pixels.data.iter().map(|&sample| sample as u16).collect();
+      return Self {
+        values,
+        channels,
+        max: 255,
+      };
+    }
+
+    let redundant: bool = pixels.data.chunks_exact(2).all(|pair| pair[0] == pair[1]);
+    let (values, max) = match redundant {
+      true => (
+        pixels.data.iter().step_by(2).map(|&s| s as u16).collect(),
+        255,
+      ),
+      false => (
+        pixels
+          .data
+          .chunks_exact(2)
+          .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+          .collect(),
+        u16::MAX,
+      ),
+    };
+    Self {
+      values,
+      channels,
+      max,
+    }
+  }
+
+  fn pixels(&self) -> impl Iterator<Item = &[u16]> {
+    self.values.chunks_exact(self.channels)
+  }
+
+  /// Determine if the red, green and blue samples of every pixel are equal
+  fn is_grey(&self) -> bool {
+    self.channels < 3
+      || self
+        .pixels()
+        .all(|pixel| pixel[0] == pixel[1] && pixel[1] == pixel[2])
+  }
+
+  /// Find the alpha channel the pixels need, given the channels holding
+  /// their color
+  fn alpha(&self, colors: &[usize]) -> Alpha {
+    if self.channels % 2 == 1
+      || self
+        .pixels()
+        .all(|pixel| pixel[self.channels - 1] == self.max)
+    {
+      return Alpha::Opaque;
+    }
+
+    let color =
+      |pixel: &[u16]| -> Vec<u16> { colors.iter().map(|&channel| pixel[channel]).collect() };
+    let mut key: Option<Vec<u16>> = None;
+    for pixel in self.pixels() {
+      match pixel[self.channels - 1] {
+        0 if key.is_none() => key = Some(color(pixel)),
+        0 if key.as_deref() == Some(&color(pixel)) => {}
+        alpha if alpha == self.max => {}
+        _ => return Alpha::Channel,
+      }
+    }
+
+    match key {
+      Some(key)
+        if self
+          .pixels()
+          .all(|pixel| pixel[self.channels - 1] == 0 || color(pixel) != key) =>
+      {
+        Alpha::Key(key)
+      }
+      _ => Alpha::Channel,
+    }
+  }
+}
+
+/// Find encodings of the pixels that keep every sample intact while using
+/// fewer bits: the pixels with the fewest channels and bits per sample, and
+/// the same pixels as indices into a palette when they hold at most 256
+/// colors. An ICC profile only applies to the color types of its color space,
+/// so the pixels keep their color channels with an RGB profile, and are not
+/// turned into a palette with a greyscale one.
+pub(crate) fn reductions(
+  pixels: &PixelData,
+  profile: Option<&ICCProfile>,
+) -> Result<Vec<EncodedPixels<'static>>, RSMError> {
+  let source: EncodedPixels = EncodedPixels::new(pixels)?;
+  let samples: Samples = Samples::new(&source);
+
+  let grey_profile: Option<bool> = profile.map(ICCProfile::is_grey);
+  let colors: &[usize] = match samples.is_grey() && grey_profile != Some(false) {
+    true => &[0],
+    false => &[0, 1, 2],
+  };
+  let alpha: Alpha = samples.alpha(colors);
+  let mut candidates: Vec<EncodedPixels<'static>> =
+    vec![reduce_channels(&source, &samples, colors, &alpha)];
+
+  if samples.max == 255
+    && grey_profile != Some(true)
+    && let Some(indexed) = reduce_to_palette(&source, &samples, colors)
+  {
+    candidates.push(indexed);
+  }
+  Ok(candidates)
+}
+
+/// Keep the channels holding the color, and alpha if needed, with the smallest
+/// bit depth
+fn reduce_channels(
+  source: &EncodedPixels,
+  samples: &Samples,
+  colors: &[usize],
+  alpha: &Alpha,
+) -> EncodedPixels<'static> {
+  let mut channels: Vec<usize> = colors.to_vec();
+  if *alpha == Alpha::Channel {
+    channels.push(samples.channels - 1);
+  }
+  let color_type: ColorType = match (colors.len(), *alpha == Alpha::Channel) {
+    (1, false) => ColorType::Greyscale,
+    (1, true) => ColorType::GreyscaleAlpha,
+    (_, false) => ColorType::Truecolor,
+    (_, true) => ColorType::TruecolorAlpha,
+  };
+
+  let mut values: Vec<u16> = samples
+    .pixels()
+    .flat_map(|pixel| channels.iter().map(|&channel| pixel[channel]))
+    .collect();
+  let mut key: Option<Vec<u16>> = match alpha {
+    Alpha::Key(key) => Some(key.clone()),
+    _ => None,
+  };
+
+  // Greyscale samples may fit in fewer bits when they are evenly spaced
+  let bit_depth: BitDepth = match (samples.max, color_type) {
+    (u16::MAX, _) => BitDepth::D16,
+    (_, ColorType::Greyscale) => {
+      let (bit_depth, scale) = [(BitDepth::D1, 255), (BitDepth::D2, 85), (BitDepth::D4, 17)]
+        .into_iter()
+        .find(|(_, scale)| values.iter().all(|value| value % scale == 0))
+        .unwrap_or((BitDepth::D8, 1));
+      values.iter_mut().for_each(|value| *value /= scale);
+      key.iter_mut().flatten().for_each(|value| *value /= scale);
+      bit_depth
+    }
+    _ => BitDepth::D8,
+  };
+
+  let transparency: Option<Transparency> = key.map(|key| match key[..] {
+    [r, g, b] => Transparency::RgbKey(r, g, b),
+    _ => Transparency::GrayKey(key[0]),
+  });
+  EncodedPixels {
+    width: source.width,
+    height: source.height,
+    color_type,
+    bit_depth,
+    data: Cow::Owned(pack(
+      &values,
+      source.width as usize * channels.len(),
+      bit_depth,
+    )),
+    palette: None,
+    transparency,
+  }
+}
+
+/// Replace pixels by indices into a palette of their colors, if they hold at
+/// most 256 colors. Entries with alpha come first, so that the alpha values
+/// stop at the last of them, and entries are sorted by luma otherwise so that
+/// similar colors get close indices.
+fn reduce_to_palette(
+  source: &EncodedPixels,
+  samples: &Samples,
+  colors: &[usize],
+) -> Option<EncodedPixels<'static>> {
+  let rgba = |pixel: &[u16]| -> [u8; 4] {
+    let [r, g, b] = match colors {
+      [grey] => [pixel[*grey] as u8; 3],
+      _ => [pixel[0] as u8, pixel[1] as u8, pixel[2] as u8],
+    };
+    let a: u8 = match samples.channels % 2 {
+      0 => pixel[samples.channels - 1] as u8,
+      _ => 255,
+    };
+    [r, g, b, a]
+  };
+
+  let mut entries: Vec<[u8; 4]> = Vec::new();
+  let mut seen: HashMap<[u8; 4], u8> = HashMap::new();
+  for pixel in samples.pixels() {
+    let color: [u8; 4] = rgba(pixel);
+    if let Entry::Vacant(entry) = seen.entry(color) {
+      if entries.len() == Palette::MAX_ENTRIES {
+        return None;
+      }
+      entry.insert(0);
+      entries.push(color);
+    }
+  }
+
+  let luma = |[r, g, b, _]: [u8; 4]| 299 * r as u32 + 587 * g as u32 + 114 * b as u32;
+  entries.sort_by_key(|&color| (color[3] == 255, luma(color), color));
+  for (index, color) in entries.iter().enumerate() {
+    seen.insert(*color, index as u8);
+  }
+
+  let alpha: Vec<u8> = entries
+    .iter()
+    .map(|color| color[3])
+    .take_while(|&alpha| alpha != 255)
+    .collect();
+  let indices: Vec<u16> = samples
+    .pixels()
+    .map(|pixel| seen[&rgba(pixel)] as u16)
+    .collect();
+  let bit_depth: BitDepth = index_bit_depth(entries.len());
+
+  Some(EncodedPixels {
+    width: source.width,
+    height: source.height,
+    color_type: ColorType::IndexedColor,
+    bit_depth,
+    data: Cow::Owned(pack(&indices, source.width as usize, bit_depth)),
+    palette: Palette::new(entries.iter().map(|&[r, g, b, _]| [r, g, b]).collect()).ok(),
+    transparency: (!alpha.is_empty()).then_some(Transparency::PaletteAlpha(alpha)),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::img::png::parse::chunks::idat::png_pixel_format::{ColorTarget, PixelLayout};
+
+  fn pixels(color: ColorTarget, bit_depth: u8, width: u32, data: Vec<u8>) -> PixelData {
+    let height: u32 =
+      (data.len() / (color.channels() * bit_depth as usize / 8) / width as usize) as u32;
+    PixelData {
+      data,
+      width,
+      height,
+      color,
+      bit_depth,
+      layout: PixelLayout::Interleaved,
+    }
+  }
+
+  #[test]
+  fn test_sixteen_bits() {
+    // The least significant bytes repeat the most significant ones
+    let data: Vec<u8> = vec![0x12, 0x12, 0x34, 0x34, 0x56, 0x56, 0xff, 0xff];
+    let candidates: Vec<EncodedPixels> =
+      reductions(&pixels(ColorTarget::Rgba, 16, 1, data), None).unwrap();
+    assert_eq!(candidates[0].color_type, ColorType::Truecolor);
+    assert_eq!(candidates[0].bit_depth, BitDepth::D8);
+    assert_eq!(candidates[0].data[..], [0x12, 0x34, 0x56]);
+
+    let data: Vec<u8> = vec![0x12, 0x13, 0x12, 0x13, 0x12, 0x13];
+    let candidates: Vec<EncodedPixels> =
+      reductions(&pixels(ColorTarget::Rgb, 16, 1, data), None).unwrap();
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0].color_type, ColorType::Greyscale);
+    assert_eq!(candidates[0].bit_depth, BitDepth::D16);
+    assert_eq!(candidates[0].data[..], [0x12, 0x13]);
+  }
+
+  #[test]
+  fn test_grey_depth() {
+    let data: Vec<u8> = [0, 0, 0, 255, 85, 85, 85, 255, 255, 255, 255, 255].to_vec();
+    let candidates: Vec<EncodedPixels> =
+      reductions(&pixels(ColorTarget::Rgba, 8, 3, data), None).unwrap();
+    assert_eq!(candidates[0].color_type, ColorType::Greyscale);
+    assert_eq!(candidates[0].bit_depth, BitDepth::D2);
+    assert_eq!(candidates[0].data[..], [0b0001_1100]);
+    assert_eq!(candidates[1].bit_depth, BitDepth::D2);
+  }
+
+  #[test]
+  fn test_transparency_key() {
+    // Transparent pixels share a color that no opaque pixel uses
+    let data: Vec<u8> = [9, 9, 9, 0, 1, 2, 3, 255, 9, 9, 9, 0].to_vec();
+    let candidates: Vec<EncodedPixels> =
+      reductions(&pixels(ColorTarget::Rgba, 8, 3, data), None).unwrap();
+    assert_eq!(candidates[0].color_type, ColorType::Truecolor);
+    assert_eq!(
+      candidates[0].transparency,
+      Some(Transparency::RgbKey(9, 9, 9))
+    );
+
+    let data: Vec<u8> = [9, 9, 9, 0, 9, 9, 9, 255].to_vec();
+    let candidates: Vec<EncodedPixels> =
+      reductions(&pixels(ColorTarget::Rgba, 8, 2, data), None).unwrap();
+    assert_eq!(candidates[0].color_type, ColorType::GreyscaleAlpha);
+    assert_eq!(candidates[0].transparency, None);
+  }
+
+  #[test]
+  fn test_palette() {
+    let data: Vec<u8> = [
+      [200, 0, 0, 255],
+      [0, 0, 0, 255],
+      [0, 0, 255, 0],
+      [10, 0, 0, 128],
+    ]
+    .iter()
+    .cycle()
+    .take(6)
+    .flatten()
+    .copied()
+    .collect();
+    let candidates: Vec<EncodedPixels> =
+      reductions(&pixels(ColorTarget::Rgba, 8, 6, data), None).unwrap();
+    assert_eq!(candidates[0].color_type, ColorType::TruecolorAlpha);
+
+    let indexed: &EncodedPixels = &candidates[1];
+    assert_eq!(indexed.color_type, ColorType::IndexedColor);
+    assert_eq!(indexed.bit_depth, BitDepth::D2);
+    assert_eq!(
+      indexed.palette.as_ref().unwrap().colors(),
+      [[10, 0, 0], [0, 0, 255], [0, 0, 0], [200, 0, 0]]
+    );
+    assert_eq!(
+      indexed.transparency,
+      Some(Transparency::PaletteAlpha(vec![128, 0]))
+    );
+    assert_eq!(indexed.data[..], [0b1110_0100, 0b1110_0000]);
+  }
+
+  #[test]
+  fn test_icc_profile() {
+    let profile = |space: &[u8; 4]| -> ICCProfile {
+      let mut code: Vec<u8> = vec![0u8; 128];
+      code[16..20].copy_from_slice(space);
+      ICCProfile {
+        name: "Profile".to_string(),
+        code,
+      }
+    };
+    let data: Vec<u8> = [0, 0, 0, 85, 85, 85, 255, 255, 255].to_vec();
+
+    // Grey pixels stay in color with an RGB profile
+    let rgb: ICCProfile = profile(b"RGB ");
+    let candidates: Vec<EncodedPixels> =
+      reductions(&pixels(ColorTarget::Rgb, 8, 3, data.clone()), Some(&rgb)).unwrap();
+    assert_eq!(candidates.len(), 2);
+    assert_eq!(candidates[0].color_type, ColorType::Truecolor);
+    assert_eq!(candidates[1].color_type, ColorType::IndexedColor);
+
+    // And are never indexed with a greyscale one
+    let grey: ICCProfile = profile(b"GRAY");
+    let candidates: Vec<EncodedPixels> =
+      reductions(&pixels(ColorTarget::Rgb, 8, 3, data), Some(&grey)).unwrap();
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0].color_type, ColorType::Greyscale);
+    assert_eq!(candidates[0].bit_depth, BitDepth::D2);
+  }
+
+  #[test]
+  fn test_too_many_colors() {
+    let data: Vec<u8> = (0..=256u32)
+      .flat_map(|n| [n as u8, (n >> 8) as u8, 0])
+      .collect();
+    let candidates: Vec<EncodedPixels> =
+      reductions(&pixels(ColorTarget::Rgb, 8, 257, data), None).unwrap();
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0].color_type, ColorType::Truecolor);
+  }
+}
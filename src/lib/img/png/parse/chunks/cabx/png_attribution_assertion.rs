@@ -0,0 +1,148 @@
+use c2pa::ManifestAssertion;
+use serde_json::Value;
+
+/// Assertion made by a manifest about the image
+#[derive(Debug, PartialEq, Clone)]
+pub enum AttributionAssertion {
+  /// Actions performed on the image (`c2pa.actions`)
+  Actions(Vec<AttributionAction>),
+
+  /// Permissions to use the image for data mining and AI training
+  /// (`c2pa.training-mining` or `cawg.training-mining`)
+  TrainingMining(Vec<TrainingMiningEntry>),
+
+  /// Any other assertion, with its data as JSON when it is structured
+  Other { label: String, data: Option<String> },
+}
+
+/// Action performed on the image
+#[derive(Debug, PartialEq, Clone)]
+pub struct AttributionAction {
+  /// Name of the action (e.g. `c2pa.created`, `c2pa.edited`)
+  pub action: String,
+  pub when: Option<String>,
+
+  /// Name of the software that performed the action
+  pub software_agent: Option<String>,
+
+  /// IPTC digital source type, describing how the image was produced (e.g. by
+  /// a camera or by a generative model)
+  pub digital_source_type: Option<String>,
+  pub description: Option<String>,
+  pub reason: Option<String>,
+}
+
+/// Permission given for a use of the image
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MiningPermission {
+  Allowed,
+  NotAllowed,
+
+  /// Allowed under constraints, as described by the entry
+  Constrained,
+}
+
+/// Entry of a training and data mining assertion
+#[derive(Debug, PartialEq, Clone)]
+pub struct TrainingMiningEntry {
+  /// Use of the image (e.g. `c2pa.ai_training`, `c2pa.data_mining`)
+  pub usage: String,
+  pub permission: MiningPermission,
+
+  /// Description of the constraints on the use
+  pub constraint_info: Option<String>,
+}
+
+impl AttributionAssertion {
+  /// Labels of actions assertions
+  const ACTIONS: [&str; 2] = ["c2pa.actions", "c2pa.actions.v2"];
+
+  /// Labels of training and data mining assertions
+  const TRAINING_MINING: [&str; 2] = ["c2pa.training-mining", "cawg.training-mining"];
+
+  /// Label of the assertion
+  pub fn label(&self) -> &str {
+    match self {
+      Self::Actions(_) => Self::ACTIONS[0],
+      Self::TrainingMining(_) => Self::TRAINING_MINING[0],
+      Self::Other { label, .. } => label,
+    }
+  }
+
+  fn read_actions(data: &Value) -> Option<Vec<AttributionAction>> {
+    let text = |action: &Value, key: &str| action.get(key)?.as_str().map(String::from);
+
+    let actions: Vec<AttributionAction> = data
+      .get("actions")?
+      .as_array()?
+      .iter()
+      .filter_map(|action| {
+        // The software agent is a name in version 1 and a structure since
+        // version 2 of the assertion
+        let software_agent: Option<String> = match action.get("softwareAgent") {
+          Some(Value::String(name)) => Some(name.clone()),
+          Some(agent) => text(agent, "name"),
+          None => None,
+        };
+
+        Some(AttributionAction {
+          action: text(action, "action")?,
+          when: text(action, "when"),
+          software_agent,
+          digital_source_type: text(action, "digitalSourceType"),
+          description: text(action, "description"),
+          reason: text(action, "reason"),
+        })
+      })
+      .collect();
+    Some(actions)
+  }
+
+  fn read_training_mining(data: &Value) -> Option<Vec<TrainingMiningEntry>> {
+    let entries: Vec<TrainingMiningEntry> = data
+      .get("entries")?
+      .as_object()?
+      .iter()
+      .filter_map(|(usage, entry)| {
+        let permission: MiningPermission = match entry.get("use")?.as_str()? {
+          "allowed" => MiningPermission::Allowed,
+          "notAllowed" => MiningPermission::NotAllowed,
+          "constrained" => MiningPermission::Constrained,
+          _ => return None,
+        };
+
+        Some(TrainingMiningEntry {
+          usage: usage.clone(),
+          permission,
+          constraint_info: entry
+            .get("constraint_info")
+            .and_then(Value::as_str)
+            .map(String::from),
+        })
+      })
+      .collect();
+    Some(entries)
+  }
+}
+
+impl From<&ManifestAssertion> for AttributionAssertion {
+  fn from(assertion: &ManifestAssertion) -> Self {
+    let label: &str = assertion.label();
+    let data: Option<&Value> = assertion.value().ok();
+
+    let known: Option<Self> = data.and_then(|data| {
+      if Self::ACTIONS.contains(&label) {
+        Self::read_actions(data).map(Self::Actions)
+      } else if Self::TRAINING_MINING.contains(&label) {
+        Self::read_training_mining(data).map(Self::TrainingMining)
+      } else {
+        None
+      }
+    });
+
+    known.unwrap_or_else(|| Self::Other {
+      label: label.to_string(),
+      data: data.map(Value::to_string),
+    })
+  }
+}
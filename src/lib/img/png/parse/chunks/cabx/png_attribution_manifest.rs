@@ -1,9 +1,125 @@
+use crate::lib::img::png::parse::chunks::cabx::{
+  png_attribution_assertion::AttributionAssertion, png_content_credentials::ValidationIssue,
+};
+use c2pa::{Ingredient, Manifest, Relationship};
+
 /// Manifests obtained from the `caBX` chunk
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct AttributionManifest {
   pub issuer: Option<String>,
   pub label: Option<String>,
   pub name: Option<String>,
   pub time: Option<String>,
   pub title: Option<String>,
+
+  /// Software that created the manifest
+  pub claim_generator: Option<String>,
+
+  /// Media type of the asset described by the manifest
+  pub format: Option<String>,
+
+  /// Algorithm used to sign the manifest
+  pub algorithm: Option<String>,
+  pub assertions: Vec<AttributionAssertion>,
+
+  /// Assets the image was made from, whose own manifests can be found by
+  /// their label within the same [credentials](super::png_content_credentials::ContentCredentials)
+  pub ingredients: Vec<AttributionIngredient>,
+  pub thumbnail: Option<AttributionThumbnail>,
+}
+
+/// Asset from which the image was made
+#[derive(Debug, PartialEq, Clone)]
+pub struct AttributionIngredient {
+  pub title: Option<String>,
+  pub format: Option<String>,
+  pub instance_id: String,
+  pub relationship: IngredientRelationship,
+
+  /// Label of the manifest of the ingredient
+  pub manifest: Option<String>,
+
+  /// Failures found when the ingredient was validated
+  pub failures: Vec<ValidationIssue>,
+  pub thumbnail: Option<AttributionThumbnail>,
+}
+
+/// Relationship between the image and an ingredient
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum IngredientRelationship {
+  /// The image is derived from the ingredient
+  ParentOf,
+
+  /// The ingredient is a part of the image
+  ComponentOf,
+
+  /// The ingredient was an input to a process that produced the image
+  InputTo,
+}
+
+/// Thumbnail of the image or of an ingredient
+#[derive(Debug, PartialEq, Clone)]
+pub struct AttributionThumbnail {
+  /// Media type of the thumbnail
+  pub format: String,
+  pub data: Vec<u8>,
+}
+
+impl From<&Manifest> for AttributionManifest {
+  fn from(manifest: &Manifest) -> Self {
+    let algorithm: Option<String> = manifest
+      .signature_info()
+      .and_then(|info| info.alg)
+      .map(|alg| alg.to_string());
+
+    Self {
+      issuer: manifest.issuer(),
+      label: manifest.label().map(String::from),
+      name: manifest.common_name(),
+      time: manifest.time(),
+      title: manifest.title().map(String::from),
+      claim_generator: manifest.claim_generator().map(String::from),
+      format: manifest.format().map(String::from),
+      algorithm,
+      assertions: manifest.assertions().iter().map(Into::into).collect(),
+      ingredients: manifest.ingredients().iter().map(Into::into).collect(),
+      thumbnail: manifest
+        .thumbnail()
+        .map(|(format, data)| AttributionThumbnail {
+          format: format.to_string(),
+          data: data.into_owned(),
+        }),
+    }
+  }
+}
+
+impl From<&Ingredient> for AttributionIngredient {
+  fn from(ingredient: &Ingredient) -> Self {
+    let relationship: IngredientRelationship = match ingredient.relationship() {
+      Relationship::ParentOf => IngredientRelationship::ParentOf,
+      Relationship::ComponentOf => IngredientRelationship::ComponentOf,
+      Relationship::InputTo => IngredientRelationship::InputTo,
+    };
+
+    Self {
+      title: ingredient.title().map(String::from),
+      format: ingredient.format().map(String::from),
+      instance_id: ingredient.instance_id().to_string(),
+      relationship,
+      manifest: ingredient.active_manifest().map(String::from),
+      failures: ingredient
+        .validation_status()
+        .unwrap_or_default()
+        .iter()
+        .filter(|status| !status.passed())
+        .map(Into::into)
+        .collect(),
+      thumbnail: ingredient
+        .thumbnail()
+        .map(|(format, data)| AttributionThumbnail {
+          format: format.to_string(),
+          data: data.into_owned(),
+        }),
+    }
+  }
 }
@@ -0,0 +1,137 @@
+use crate::lib::img::png::parse::chunks::cabx::png_attribution_manifest::{
+  AttributionIngredient, AttributionManifest,
+};
+use c2pa::{Reader, ValidationState, validation_status::ValidationStatus};
+
+/// Overall state of content credentials, following the C2PA validation states
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CredentialState {
+  /// The manifests are malformed, or a cryptographic check failed
+  Invalid,
+
+  /// The manifests are well-formed and their cryptographic checks succeeded
+  Valid,
+
+  /// The manifests are valid and signed by a trusted certificate
+  Trusted,
+}
+
+/// Failure found when validating content credentials
+#[derive(Debug, PartialEq, Clone)]
+pub struct ValidationIssue {
+  /// C2PA status code (e.g. `assertion.dataHash.mismatch`)
+  pub code: String,
+
+  /// Part of the manifests the failure applies to
+  pub url: Option<String>,
+  pub explanation: Option<String>,
+}
+
+impl From<&ValidationStatus> for ValidationIssue {
+  fn from(status: &ValidationStatus) -> Self {
+    Self {
+      code: status.code().to_string(),
+      url: status.url().map(String::from),
+      explanation: status.explanation().map(String::from),
+    }
+  }
+}
+
+/// Content credentials (C2PA manifest store) obtained from the `caBX` chunk,
+/// validated against the bytes of the image
+#[derive(Debug, PartialEq, Clone)]
+pub struct ContentCredentials {
+  /// Label of the manifest describing the image itself
+  pub active_manifest: Option<String>,
+  pub manifests: Vec<AttributionManifest>,
+  pub state: CredentialState,
+
+  /// Failures found when validating the manifests
+  pub failures: Vec<ValidationIssue>,
+}
+
+impl ContentCredentials {
+  /// Status codes reporting that the hard binding of a manifest does not match
+  /// the bytes of the image
+  const HASH_MISMATCHES: [&str; 3] = [
+    "assertion.dataHash.mismatch",
+    "assertion.boxesHash.mismatch",
+    "assertion.bmffHash.mismatch",
+  ];
+
+  /// Status code reporting a signing certificate that is not in a trust list,
+  /// which does not invalidate the signature itself
+  const UNTRUSTED: &str = "signingCredential.untrusted";
+
+  /// Manifest describing the image itself
+  pub fn active(&self) -> Option<&AttributionManifest> {
+    self.manifest(self.active_manifest.as_deref()?)
+  }
+
+  /// Obtain a manifest by its label
+  pub fn manifest(&self, label: &str) -> Option<&AttributionManifest> {
+    self
+      .manifests
+      .iter()
+      .find(|manifest| manifest.label.as_deref() == Some(label))
+  }
+
+  /// Obtain the manifest of an ingredient, which describes how the ingredient
+  /// itself was made
+  pub fn ingredient_manifest(
+    &self,
+    ingredient: &AttributionIngredient,
+  ) -> Option<&AttributionManifest> {
+    self.manifest(ingredient.manifest.as_deref()?)
+  }
+
+  /// Determine if the credentials are valid, trusted or not
+  pub fn is_valid(&self) -> bool {
+    self.state != CredentialState::Invalid
+  }
+
+  /// Determine if the signatures of the manifests are valid, regardless of
+  /// whether their certificates are trusted
+  pub fn is_signature_valid(&self) -> bool {
+    !self.failures.iter().any(|failure| {
+      let code: &str = &failure.code;
+      (code.starts_with("claimSignature.") || code.starts_with("signingCredential."))
+        && code != Self::UNTRUSTED
+    })
+  }
+
+  /// Determine if the image was modified since it was signed, such that its
+  /// bytes no longer match the hard binding of the active manifest
+  pub fn has_hash_mismatch(&self) -> bool {
+    self
+      .failures
+      .iter()
+      .any(|failure| Self::HASH_MISMATCHES.contains(&failure.code.as_str()))
+  }
+}
+
+impl From<&Reader> for ContentCredentials {
+  fn from(reader: &Reader) -> Self {
+    let state: CredentialState = match reader.validation_state() {
+      ValidationState::Invalid => CredentialState::Invalid,
+      ValidationState::Valid => CredentialState::Valid,
+      ValidationState::Trusted => CredentialState::Trusted,
+    };
+
+    let mut manifests: Vec<AttributionManifest> = reader.iter_manifests().map(Into::into).collect();
+    manifests.sort_by(|a, b| a.label.cmp(&b.label));
+
+    Self {
+      active_manifest: reader.active_label().map(String::from),
+      manifests,
+      state,
+      failures: reader
+        .validation_status()
+        .unwrap_or_default()
+        .iter()
+        .filter(|status| !status.passed())
+        .map(Into::into)
+        .collect(),
+    }
+  }
+}
@@ -1,25 +1,321 @@
-use crate::lib::util::err::rsm_error::RSMError;
-use exif::Exif;
-use std::collections::HashMap;
+use crate::lib::{
+  img::png::parse::chunks::exif::{
+    png_exif_date_time::ExifDateTime,
+    png_exif_field::{ExifDirectory, ExifField, ExifIfd, ExifTag, ExifValue, Rational},
+  },
+  util::err::rsm_error::RSMError,
+};
+use exif::{Field, In, Tag, experimental::Writer};
+use std::io::Cursor;
 
 /// Represents data obtained from an `eXIf` chunk
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct PNGExifData {
-  pub data: HashMap<String, String>,
+  /// Fields of the primary and thumbnail IFDs. Fields describing the layout of
+  /// the data (e.g. pointers to nested directories) are not included, as they
+  /// are computed when encoding.
+  pub fields: Vec<ExifField>,
+
+  /// JPEG data of the thumbnail image
+  pub thumbnail: Option<Vec<u8>>,
+
+  /// Determines if the data is encoded in little-endian byte order
+  pub little_endian: bool,
 }
 
-impl TryFrom<Exif> for PNGExifData {
-  type Error = RSMError;
+impl PNGExifData {
+  /// Tags describing the layout of the data
+  const LAYOUT_TAGS: [Tag; 9] = [
+    Tag::ExifIFDPointer,
+    Tag::GPSInfoIFDPointer,
+    Tag::InteropIFDPointer,
+    Tag::StripOffsets,
+    Tag::StripByteCounts,
+    Tag::TileOffsets,
+    Tag::TileByteCounts,
+    Tag::JPEGInterchangeFormat,
+    Tag::JPEGInterchangeFormatLength,
+  ];
+
+  /// Parse TIFF-structured EXIF data. Fields whose tag or value type is not
+  /// supported are skipped.
+  pub fn from_bytes(data: &[u8]) -> Result<Self, RSMError> {
+    let (fields, little_endian) = exif::parse_exif(data).map_err(|_| RSMError::InvalidContent)?;
+    let thumbnail: Option<Vec<u8>> = Self::read_thumbnail(&fields, data);
+
+    let fields: Vec<ExifField> = fields
+      .into_iter()
+      .filter(|field| !Self::LAYOUT_TAGS.contains(&field.tag))
+      .filter(|field| field.ifd_num == In::PRIMARY || field.ifd_num == In::THUMBNAIL)
+      .filter_map(|field| {
+        let ifd: ExifIfd = if field.ifd_num == In::PRIMARY {
+          ExifIfd::Primary
+        } else {
+          ExifIfd::Thumbnail
+        };
+
+        Some(ExifField {
+          ifd,
+          tag: field.tag.try_into().ok()?,
+          value: field.value.try_into().ok()?,
+        })
+      })
+      .collect();
+
+    Ok(Self {
+      fields,
+      thumbnail,
+      little_endian,
+    })
+  }
+
+  /// Read the JPEG data of the thumbnail image
+  fn read_thumbnail(fields: &[Field], data: &[u8]) -> Option<Vec<u8>> {
+    let get = |tag: Tag| -> Option<usize> {
+      let field = fields
+        .iter()
+        .find(|field| field.tag == tag && field.ifd_num == In::THUMBNAIL)?;
+      Some(field.value.get_uint(0)? as usize)
+    };
+
+    let offset: usize = get(Tag::JPEGInterchangeFormat)?;
+    let length: usize = get(Tag::JPEGInterchangeFormatLength)?;
+    data
+      .get(offset..offset.checked_add(length)?)
+      .map(<[u8]>::to_vec)
+  }
 
-  fn try_from(value: Exif) -> std::result::Result<Self, Self::Error> {
-    let mut data: HashMap<String, String> = HashMap::new();
+  /// Encode the data as TIFF-structured EXIF data, as stored in the `eXIf`
+  /// chunk.
+  pub fn to_bytes(&self) -> Result<Vec<u8>, RSMError> {
+    let fields: Vec<Field> = self
+      .fields
+      .iter()
+      .map(Field::try_from)
+      .collect::<Result<_, RSMError>>()?;
 
-    for field in value.fields() {
-      let tag: String = field.tag.to_string();
-      let value: String = field.display_value().to_string();
+    let mut writer: Writer<'_> = Writer::new();
+    fields.iter().for_each(|field| writer.push_field(field));
 
-      data.insert(tag, value);
+    if let Some(thumbnail) = &self.thumbnail {
+      writer.set_jpeg(thumbnail, In::THUMBNAIL);
     }
-    Ok(Self { data })
+
+    let mut buffer: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+    writer
+      .write(&mut buffer, self.little_endian)
+      .map_err(|_| RSMError::InvalidContent)?;
+    Ok(buffer.into_inner())
+  }
+
+  /// Obtain the value of a tag from the primary IFD
+  pub fn get(&self, tag: ExifTag) -> Option<&ExifValue> {
+    self.get_in(tag, ExifIfd::Primary)
+  }
+
+  /// Obtain the value of a tag from a given IFD
+  pub fn get_in(&self, tag: ExifTag, ifd: ExifIfd) -> Option<&ExifValue> {
+    self
+      .fields
+      .iter()
+      .find(|field| field.tag == tag && field.ifd == ifd)
+      .map(|field| &field.value)
+  }
+
+  /// Set the value of a tag in the primary IFD
+  pub fn set(&mut self, tag: ExifTag, value: ExifValue) {
+    self.set_in(tag, ExifIfd::Primary, value)
+  }
+
+  /// Set the value of a tag in a given IFD
+  pub fn set_in(&mut self, tag: ExifTag, ifd: ExifIfd, value: ExifValue) {
+    match self
+      .fields
+      .iter_mut()
+      .find(|field| field.tag == tag && field.ifd == ifd)
+    {
+      Some(field) => field.value = value,
+      None => self.fields.push(ExifField { ifd, tag, value }),
+    }
+  }
+
+  /// Remove a tag from the primary IFD, returning its value
+  pub fn remove(&mut self, tag: ExifTag) -> Option<ExifValue> {
+    self.remove_in(tag, ExifIfd::Primary)
+  }
+
+  /// Remove a tag from a given IFD, returning its value
+  pub fn remove_in(&mut self, tag: ExifTag, ifd: ExifIfd) -> Option<ExifValue> {
+    let index: usize = self
+      .fields
+      .iter()
+      .position(|field| field.tag == tag && field.ifd == ifd)?;
+    Some(self.fields.remove(index).value)
+  }
+
+  /// Remove every tag of a [directory](ExifDirectory) from both IFDs
+  pub fn remove_directory(&mut self, directory: ExifDirectory) {
+    self.fields.retain(|field| field.tag.directory != directory);
+  }
+
+  /// Remove the GPS attributes, which locate where the image was captured
+  pub fn remove_gps(&mut self) {
+    self.remove_directory(ExifDirectory::Gps)
+  }
+
+  /// Orientation of the image, from 1 to 8
+  pub fn orientation(&self) -> Option<u16> {
+    let orientation: u32 = self.get(ExifTag::ORIENTATION)?.as_uint()?;
+    (1..=8).contains(&orientation).then_some(orientation as u16)
+  }
+
+  /// Set the orientation of the image, which must be from 1 to 8
+  pub fn set_orientation(&mut self, orientation: u16) -> Result<(), RSMError> {
+    if !(1..=8).contains(&orientation) {
+      return Err(RSMError::OutOfBounds);
+    }
+    self.set(ExifTag::ORIENTATION, ExifValue::Short(vec![orientation]));
+    Ok(())
+  }
+
+  /// Date and time at which the image was captured
+  pub fn capture_time(&self) -> Option<ExifDateTime> {
+    let text: &str = self.get(ExifTag::DATE_TIME_ORIGINAL)?.as_str()?;
+    ExifDateTime::try_from(text).ok()
+  }
+
+  /// Set the date and time at which the image was captured
+  pub fn set_capture_time(&mut self, date_time: ExifDateTime) {
+    let value: ExifValue = ExifValue::Ascii(vec![date_time.to_string()]);
+    self.set(ExifTag::DATE_TIME_ORIGINAL, value);
+  }
+
+  /// Manufacturer of the camera
+  pub fn make(&self) -> Option<&str> {
+    self.get(ExifTag::MAKE)?.as_str()
+  }
+
+  /// Model of the camera
+  pub fn model(&self) -> Option<&str> {
+    self.get(ExifTag::MODEL)?.as_str()
+  }
+
+  /// GPS coordinates as decimal degrees (latitude, longitude), which are
+  /// negative in the southern and western hemispheres.
+  pub fn gps_coordinates(&self) -> Option<(f64, f64)> {
+    let latitude: f64 = self.gps_degrees(ExifTag::GPS_LATITUDE, ExifTag::GPS_LATITUDE_REF, "S")?;
+    let longitude: f64 =
+      self.gps_degrees(ExifTag::GPS_LONGITUDE, ExifTag::GPS_LONGITUDE_REF, "W")?;
+    Some((latitude, longitude))
+  }
+
+  /// Read degrees, minutes and seconds as decimal degrees
+  fn gps_degrees(&self, tag: ExifTag, reference: ExifTag, negative: &str) -> Option<f64> {
+    let [degrees, minutes, seconds]: &[Rational; 3] =
+      self.get(tag)?.as_rationals()?.try_into().ok()?;
+    let value: f64 = degrees.to_f64() + minutes.to_f64() / 60.0 + seconds.to_f64() / 3600.0;
+
+    if !value.is_finite() {
+      return None;
+    }
+    match self.get(reference).and_then(ExifValue::as_str) {
+      Some(direction) if direction == negative => Some(-value),
+      _ => Some(value),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn rationals(values: [(u32, u32); 3]) -> ExifValue {
+    ExifValue::Rational(values.map(|(num, denom)| Rational { num, denom }).to_vec())
+  }
+
+  fn sample() -> PNGExifData {
+    let mut exif: PNGExifData = PNGExifData {
+      fields: Vec::new(),
+      thumbnail: None,
+      little_endian: false,
+    };
+
+    exif.set(ExifTag::MAKE, ExifValue::Ascii(vec!["rsm".into()]));
+    exif.set_orientation(6).unwrap();
+    exif.set_capture_time(ExifDateTime::try_from("2024:02:29 13:45:30").unwrap());
+    exif.set(
+      ExifTag::GPS_LATITUDE_REF,
+      ExifValue::Ascii(vec!["N".into()]),
+    );
+    exif.set(
+      ExifTag::GPS_LATITUDE,
+      rationals([(45, 1), (30, 1), (1512, 100)]),
+    );
+    exif.set(
+      ExifTag::GPS_LONGITUDE_REF,
+      ExifValue::Ascii(vec!["W".into()]),
+    );
+    exif.set(
+      ExifTag::GPS_LONGITUDE,
+      rationals([(73, 1), (34, 1), (0, 1)]),
+    );
+    exif
+  }
+
+  #[test]
+  fn test_exif_round_trip() {
+    for little_endian in [false, true] {
+      let exif: PNGExifData = PNGExifData {
+        little_endian,
+        ..sample()
+      };
+      let decoded: PNGExifData = PNGExifData::from_bytes(&exif.to_bytes().unwrap()).unwrap();
+
+      assert_eq!(decoded.make(), Some("rsm"));
+      assert_eq!(decoded.orientation(), Some(6));
+      assert_eq!(decoded.capture_time(), exif.capture_time());
+      assert_eq!(
+        decoded.get(ExifTag::GPS_LATITUDE),
+        exif.get(ExifTag::GPS_LATITUDE)
+      );
+    }
+  }
+
+  #[test]
+  fn test_gps_coordinates() {
+    let (latitude, longitude) = sample().gps_coordinates().unwrap();
+
+    assert!((latitude - 45.5042).abs() < 1e-9);
+    assert!((longitude + 73.5666666).abs() < 1e-6);
+  }
+
+  #[test]
+  fn test_remove_gps() {
+    let mut exif: PNGExifData = sample();
+    exif.remove_gps();
+
+    let decoded: PNGExifData = PNGExifData::from_bytes(&exif.to_bytes().unwrap()).unwrap();
+    assert!(decoded.gps_coordinates().is_none());
+    assert_eq!(decoded.orientation(), Some(6));
+    assert!(decoded.capture_time().is_some());
+  }
+
+  #[test]
+  fn test_unsupported_fields() {
+    // Big-endian TIFF with an orientation and a field of unknown type 99
+    let data: [u8; 38] = [
+      b'M', b'M', 0, 42, 0, 0, 0, 8, 0, 2, 0x01, 0x12, 0, 3, 0, 0, 0, 1, 0, 6, 0, 0, 0x99, 0x99, 0,
+      99, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0,
+    ];
+    let exif: PNGExifData = PNGExifData::from_bytes(&data).unwrap();
+    assert_eq!(exif.fields.len(), 1);
+    assert_eq!(exif.orientation(), Some(6));
+  }
+
+  #[test]
+  fn test_invalid_orientation() {
+    let mut exif: PNGExifData = sample();
+    assert!(exif.set_orientation(9).is_err());
+    assert_eq!(exif.orientation(), Some(6));
   }
 }
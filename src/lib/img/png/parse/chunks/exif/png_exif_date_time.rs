@@ -0,0 +1,64 @@
+use crate::lib::util::err::rsm_error::RSMError;
+use std::fmt::{Display, Formatter, Result};
+
+/// Date and time stored in EXIF data, as `YYYY:MM:DD HH:MM:SS`. The time is
+/// local to where it was recorded.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ExifDateTime {
+  pub year: u16,
+  pub month: u8,
+  pub day: u8,
+  pub hour: u8,
+  pub minute: u8,
+  pub second: u8,
+}
+
+impl TryFrom<&str> for ExifDateTime {
+  type Error = RSMError;
+
+  fn try_from(text: &str) -> std::result::Result<Self, Self::Error> {
+    let bytes: &[u8] = text.as_bytes();
+    if bytes.len() < 19 || bytes[4] != b':' || bytes[7] != b':' || bytes[13] != b':' {
+      return Err(RSMError::InvalidContent);
+    }
+
+    let number = |start: usize, end: usize| -> std::result::Result<u16, RSMError> {
+      text
+        .get(start..end)
+        .and_then(|digits| digits.parse().ok())
+        .ok_or(RSMError::InvalidContent)
+    };
+
+    let date_time: ExifDateTime = ExifDateTime {
+      year: number(0, 4)?,
+      month: number(5, 7)? as u8,
+      day: number(8, 10)? as u8,
+      hour: number(11, 13)? as u8,
+      minute: number(14, 16)? as u8,
+      second: number(17, 19)? as u8,
+    };
+
+    let ExifDateTime {
+      month,
+      day,
+      hour,
+      minute,
+      second,
+      ..
+    } = date_time;
+    if month == 0 || month > 12 || day == 0 || day > 31 || hour > 23 || minute > 59 || second > 60 {
+      return Err(RSMError::InvalidContent);
+    }
+    Ok(date_time)
+  }
+}
+
+impl Display for ExifDateTime {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    write!(
+      f,
+      "{:04}:{:02}:{:02} {:02}:{:02}:{:02}",
+      self.year, self.month, self.day, self.hour, self.minute, self.second
+    )
+  }
+}
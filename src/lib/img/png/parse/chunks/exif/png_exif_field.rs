@@ -0,0 +1,313 @@
+use crate::lib::util::err::rsm_error::RSMError;
+use exif::{Context, Field, In, Tag, Value};
+use std::fmt::{Display, Formatter, Result};
+
+/// Image file directory (IFD) to which a field belongs
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ExifIfd {
+  /// 0th IFD, describing the primary image
+  Primary,
+
+  /// 1st IFD, describing the thumbnail image
+  Thumbnail,
+}
+
+/// Directory nested within an IFD that defines the meaning of a tag number
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ExifDirectory {
+  /// TIFF attributes, stored in the IFD itself
+  Tiff,
+
+  /// Exif attributes
+  Exif,
+
+  /// GPS attributes
+  Gps,
+
+  /// Interoperability attributes
+  Interop,
+}
+
+/// Tag identifying an EXIF field
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ExifTag {
+  pub directory: ExifDirectory,
+  pub number: u16,
+}
+
+macro_rules! define_exif_tags {
+  ($($(#[$comment: meta])* $name: ident = ($directory: ident, $number: expr)),+ $(,)?) => {
+    impl ExifTag {
+      $(
+        $(#[$comment])*
+        pub const $name: ExifTag = ExifTag {
+          directory: ExifDirectory::$directory,
+          number: $number,
+        };
+      )+
+    }
+  };
+}
+
+define_exif_tags! {
+  /// Manufacturer of the camera
+  MAKE = (Tiff, 0x010f),
+
+  /// Model of the camera
+  MODEL = (Tiff, 0x0110),
+
+  /// Orientation of the image relative to the rows and columns
+  ORIENTATION = (Tiff, 0x0112),
+
+  /// Software used to produce the image
+  SOFTWARE = (Tiff, 0x0131),
+
+  /// Date and time at which the file was changed
+  DATE_TIME = (Tiff, 0x0132),
+
+  /// Date and time at which the image was captured
+  DATE_TIME_ORIGINAL = (Exif, 0x9003),
+
+  /// Offset from UTC of the time at which the image was captured
+  OFFSET_TIME_ORIGINAL = (Exif, 0x9011),
+
+  /// North (`N`) or south (`S`) latitude
+  GPS_LATITUDE_REF = (Gps, 0x0001),
+
+  /// Latitude as degrees, minutes and seconds
+  GPS_LATITUDE = (Gps, 0x0002),
+
+  /// East (`E`) or west (`W`) longitude
+  GPS_LONGITUDE_REF = (Gps, 0x0003),
+
+  /// Longitude as degrees, minutes and seconds
+  GPS_LONGITUDE = (Gps, 0x0004),
+
+  /// Altitude above (`0`) or below (`1`) sea level
+  GPS_ALTITUDE_REF = (Gps, 0x0005),
+
+  /// Altitude in meters
+  GPS_ALTITUDE = (Gps, 0x0006),
+}
+
+impl ExifTag {
+  fn to_exif(self) -> Tag {
+    let context: Context = match self.directory {
+      ExifDirectory::Tiff => Context::Tiff,
+      ExifDirectory::Exif => Context::Exif,
+      ExifDirectory::Gps => Context::Gps,
+      ExifDirectory::Interop => Context::Interop,
+    };
+    Tag(context, self.number)
+  }
+}
+
+impl TryFrom<Tag> for ExifTag {
+  type Error = RSMError;
+
+  fn try_from(tag: Tag) -> std::result::Result<Self, Self::Error> {
+    let directory: ExifDirectory = match tag.context() {
+      Context::Tiff => ExifDirectory::Tiff,
+      Context::Exif => ExifDirectory::Exif,
+      Context::Gps => ExifDirectory::Gps,
+      Context::Interop => ExifDirectory::Interop,
+      _ => return Err(RSMError::InvalidContent),
+    };
+    Ok(Self {
+      directory,
+      number: tag.number(),
+    })
+  }
+}
+
+impl Display for ExifTag {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    write!(f, "{}", self.to_exif())
+  }
+}
+
+/// Unsigned rational number, kept exact
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Rational {
+  pub num: u32,
+  pub denom: u32,
+}
+
+impl Rational {
+  /// Approximate the rational number as a decimal value
+  pub fn to_f64(&self) -> f64 {
+    self.num as f64 / self.denom as f64
+  }
+}
+
+/// Signed rational number, kept exact
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SRational {
+  pub num: i32,
+  pub denom: i32,
+}
+
+impl SRational {
+  /// Approximate the rational number as a decimal value
+  pub fn to_f64(&self) -> f64 {
+    self.num as f64 / self.denom as f64
+  }
+}
+
+/// Value of an EXIF field
+#[derive(Debug, PartialEq, Clone)]
+pub enum ExifValue {
+  Byte(Vec<u8>),
+
+  /// ASCII strings, without their trailing null character
+  Ascii(Vec<String>),
+  Short(Vec<u16>),
+  Long(Vec<u32>),
+  Rational(Vec<Rational>),
+  SByte(Vec<i8>),
+  Undefined(Vec<u8>),
+  SShort(Vec<i16>),
+  SLong(Vec<i32>),
+  SRational(Vec<SRational>),
+  Float(Vec<f32>),
+  Double(Vec<f64>),
+}
+
+impl ExifValue {
+  /// Obtain the first string of an ASCII value
+  pub fn as_str(&self) -> Option<&str> {
+    match self {
+      Self::Ascii(strings) => strings.first().map(String::as_str),
+      _ => None,
+    }
+  }
+
+  /// Obtain the first integer of an unsigned integer value
+  pub fn as_uint(&self) -> Option<u32> {
+    match self {
+      Self::Byte(values) => values.first().map(|&v| v as u32),
+      Self::Short(values) => values.first().map(|&v| v as u32),
+      Self::Long(values) => values.first().copied(),
+      _ => None,
+    }
+  }
+
+  /// Obtain the rationals of an unsigned rational value
+  pub fn as_rationals(&self) -> Option<&[Rational]> {
+    match self {
+      Self::Rational(values) => Some(values),
+      _ => None,
+    }
+  }
+}
+
+impl TryFrom<Value> for ExifValue {
+  type Error = RSMError;
+
+  fn try_from(value: Value) -> std::result::Result<Self, Self::Error> {
+    // ASCII is read as Latin-1 to preserve non-conforming bytes
+    let latin_1 = |bytes: Vec<u8>| bytes.into_iter().map(|b| b as char).collect();
+
+    Ok(match value {
+      Value::Byte(v) => Self::Byte(v),
+      Value::Ascii(v) => Self::Ascii(v.into_iter().map(latin_1).collect()),
+      Value::Short(v) => Self::Short(v),
+      Value::Long(v) => Self::Long(v),
+      Value::Rational(v) => Self::Rational(
+        v.into_iter()
+          .map(|r| Rational {
+            num: r.num,
+            denom: r.denom,
+          })
+          .collect(),
+      ),
+      Value::SByte(v) => Self::SByte(v),
+      Value::Undefined(v, _) => Self::Undefined(v),
+      Value::SShort(v) => Self::SShort(v),
+      Value::SLong(v) => Self::SLong(v),
+      Value::SRational(v) => Self::SRational(
+        v.into_iter()
+          .map(|r| SRational {
+            num: r.num,
+            denom: r.denom,
+          })
+          .collect(),
+      ),
+      Value::Float(v) => Self::Float(v),
+      Value::Double(v) => Self::Double(v),
+      Value::Unknown(..) => return Err(RSMError::InvalidContent),
+    })
+  }
+}
+
+impl TryFrom<&ExifValue> for Value {
+  type Error = RSMError;
+
+  fn try_from(value: &ExifValue) -> std::result::Result<Self, Self::Error> {
+    let latin_1 = |text: &String| -> std::result::Result<Vec<u8>, RSMError> {
+      text
+        .chars()
+        .map(|c| u8::try_from(c).map_err(|_| RSMError::InvalidContent))
+        .collect()
+    };
+
+    Ok(match value {
+      ExifValue::Byte(v) => Value::Byte(v.clone()),
+      ExifValue::Ascii(v) => Value::Ascii(
+        v.iter()
+          .map(latin_1)
+          .collect::<std::result::Result<_, _>>()?,
+      ),
+      ExifValue::Short(v) => Value::Short(v.clone()),
+      ExifValue::Long(v) => Value::Long(v.clone()),
+      ExifValue::Rational(v) => Value::Rational(
+        v.iter()
+          .map(|r| exif::Rational {
+            num: r.num,
+            denom: r.denom,
+          })
+          .collect(),
+      ),
+      ExifValue::SByte(v) => Value::SByte(v.clone()),
+      ExifValue::Undefined(v) => Value::Undefined(v.clone(), 0),
+      ExifValue::SShort(v) => Value::SShort(v.clone()),
+      ExifValue::SLong(v) => Value::SLong(v.clone()),
+      ExifValue::SRational(v) => Value::SRational(
+        v.iter()
+          .map(|r| exif::SRational {
+            num: r.num,
+            denom: r.denom,
+          })
+          .collect(),
+      ),
+      ExifValue::Float(v) => Value::Float(v.clone()),
+      ExifValue::Double(v) => Value::Double(v.clone()),
+    })
+  }
+}
+
+/// A field of EXIF data
+#[derive(Debug, PartialEq, Clone)]
+pub struct ExifField {
+  /// The [IFD](ExifIfd) the field belongs to
+  pub ifd: ExifIfd,
+  pub tag: ExifTag,
+  pub value: ExifValue,
+}
+
+impl TryFrom<&ExifField> for Field {
+  type Error = RSMError;
+
+  fn try_from(field: &ExifField) -> std::result::Result<Self, Self::Error> {
+    let ifd_num: In = match field.ifd {
+      ExifIfd::Primary => In::PRIMARY,
+      ExifIfd::Thumbnail => In::THUMBNAIL,
+    };
+
+    Ok(Field {
+      tag: field.tag.to_exif(),
+      ifd_num,
+      value: (&field.value).try_into()?,
+    })
+  }
+}
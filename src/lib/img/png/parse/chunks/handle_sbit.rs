@@ -16,7 +16,7 @@ pub(crate) fn handle_sbit(data: &[u8], color_type: ColorType) -> Result<&[u8], R
     ColorType::GreyscaleAlpha => 2,
 
     // Color type: 6
-    ColorType::TruecolorAlpha => 6,
+    ColorType::TruecolorAlpha => 4,
   };
   get_bytes(0..range_end, data)
 }
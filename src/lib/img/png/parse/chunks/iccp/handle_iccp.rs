@@ -1,12 +1,17 @@
-use libdeflater::Decompressor;
-
 use crate::lib::{
-  img::png::parse::chunks::{iccp::png_icc_profile::ICCProfile, utils::read_text},
+  img::png::parse::{
+    chunks::{
+      iccp::png_icc_profile::ICCProfile,
+      ihdr::png_compression_method::CompressionMethod,
+      utils::{inflate, read_text},
+    },
+    png_limits::Limits,
+  },
   util::err::rsm_error::RSMError,
 };
 
 /// Handle `iCCP` (Embedded ICC profile) chunk
-pub(crate) fn handle_iccp(data: &[u8]) -> Result<ICCProfile, RSMError> {
+pub(crate) fn handle_iccp(data: &[u8], limits: &Limits) -> Result<ICCProfile, RSMError> {
   let mut parts = data.splitn(2, |&v| v == 0);
 
   let keyword: &[u8] = parts.next().unwrap();
@@ -15,24 +20,41 @@ pub(crate) fn handle_iccp(data: &[u8]) -> Result<ICCProfile, RSMError> {
   };
   let keyword_str: String = read_text(keyword)?;
 
-  if let Some(text) = parts.next() {
-    if text.contains(&0) {
+  if let Some(profile) = parts.next() {
+    let (&method, compressed_data) = profile.split_first().ok_or(RSMError::NotEnoughContent)?;
+
+    let method: CompressionMethod = method.try_into()?;
+    if method != CompressionMethod::Deflate {
       return Err(RSMError::InvalidContent);
     }
 
-    let compressed_data = &text[1..];
-    let mut buffer: Vec<u8> = vec![0u8; compressed_data.len() * 4]; // guess
-    let mut decompressor: Decompressor = Decompressor::new();
-
-    decompressor
-      .zlib_decompress(compressed_data, &mut buffer)
-      .map_err(|_| RSMError::DecompressionError)?;
-
     Ok(ICCProfile {
       name: keyword_str,
-      code: buffer,
+      code: inflate(compressed_data, limits.max_metadata_size)?,
     })
   } else {
     Err(RSMError::InvalidContent)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use libdeflater::{CompressionLvl, Compressor};
+
+  #[test]
+  fn test_iccp_exact_profile() {
+    let profile: Vec<u8> = (0..=u8::MAX).cycle().take(3000).collect();
+
+    let mut compressor: Compressor = Compressor::new(CompressionLvl::default());
+    let mut compressed: Vec<u8> = vec![0u8; compressor.zlib_compress_bound(profile.len())];
+    let size: usize = compressor.zlib_compress(&profile, &mut compressed).unwrap();
+
+    let mut data: Vec<u8> = b"sRGB\0\0".to_vec();
+    data.extend_from_slice(&compressed[..size]);
+
+    let icc: ICCProfile = handle_iccp(&data, &Limits::default()).unwrap();
+    assert_eq!(icc.name, "sRGB");
+    assert_eq!(icc.code, profile);
+  }
+}
@@ -4,3 +4,11 @@ pub struct ICCProfile {
   pub name: String,
   pub code: Vec<u8>,
 }
+
+impl ICCProfile {
+  /// Determine if the profile describes greyscale data, which PNG only allows
+  /// for greyscale color types, reading the color space of its header
+  pub fn is_grey(&self) -> bool {
+    self.code.get(16..20) == Some(b"GRAY")
+  }
+}
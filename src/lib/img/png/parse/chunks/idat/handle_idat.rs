@@ -3,48 +3,72 @@ use crate::lib::{
     chunks::{
       idat::{
         png_filters::FilterType,
+        png_image_stream::ImageStream,
         png_pixel_data::PixelData,
+        png_pixel_format::{BitDepthHandling, ColorTarget, PixelLayout},
         png_subimage::SubImage,
-        png_unfilter::{unfilter_average, unfilter_paeth, unfilter_sub, unfilter_up},
+        png_unfilter::Unfilter,
       },
       ihdr::{
-        png_color_type::ColorType, png_header::PNGHeader, png_interlace_method::InterlaceMethod,
+        png_bit_depth::BitDepth, png_color_type::ColorType, png_header::PNGHeader,
+        png_interlace_method::InterlaceMethod,
       },
+      plte::png_palette::Palette,
+      trns::png_transparency::Transparency,
+      utils::inflate_slices,
     },
+    png_decode_options::DecodeOptions,
     states::data::png_metadata::PNGMetadata,
   },
   util::err::rsm_error::RSMError,
 };
 use libdeflater::Decompressor;
 
+#[cfg(feature = "parallel")]
+use crate::lib::img::png::parse::chunks::idat::png_parallel;
+
 /// Handle the IDAT (Image data) chunk
 pub(crate) fn handle_idat(
-  data: &[u8],
+  stream: &ImageStream<'_>,
   header: &PNGHeader,
   meta: &PNGMetadata,
+  options: &DecodeOptions,
 ) -> Result<PixelData, RSMError> {
-  let (mut decompressed, images) = decompress_data(data, header)?;
-  handle_scanlines(&mut decompressed, &images, header)?;
+  let (decompressed, images) = decompress_data(stream, header, meta, options)?;
+
+  let scanline_bytes: Vec<Vec<&[u8]>> = handle_bytes(&decompressed, &images);
+  let mut pixels: Vec<u8> = map_pixels(&scanline_bytes, &images, header, meta, options);
 
-  let scanline_bytes: Vec<&[u8]> = handle_bytes(&decompressed, &images);
-  let pixels: Vec<u8> = map_pixels(&scanline_bytes, &images, header, meta);
+  let sample_bytes: usize = get_sample_bytes(header, options);
+  if options.layout == PixelLayout::Planar {
+    pixels = to_planar(&pixels, options.color.channels(), sample_bytes);
+  }
 
   let pixel_data: PixelData = PixelData {
     data: pixels,
     width: *header.width,
     height: *header.height,
+    color: options.color,
+    bit_depth: 8 * sample_bytes as u8,
+    layout: options.layout,
   };
   Ok(pixel_data)
 }
 
-/// Decompress Deflate compressed data from the IDAT chunk
-fn decompress_data(data: &[u8], header: &PNGHeader) -> Result<(Vec<u8>, Vec<SubImage>), RSMError> {
+/// Decompress Deflate compressed data from the IDAT chunks and unfilter it
+#[cfg_attr(not(feature = "parallel"), allow(unused_variables))]
+fn decompress_data(
+  stream: &ImageStream<'_>,
+  header: &PNGHeader,
+  meta: &PNGMetadata,
+  options: &DecodeOptions,
+) -> Result<(Vec<u8>, Vec<SubImage>), RSMError> {
   let mut subimages: Vec<SubImage> = Vec::new();
 
   let expected_size: usize = match header.interlace_method {
     InterlaceMethod::Null => {
       let row_bytes = get_bytes_per_scanline(*header.width, header);
-      let buffer_length = (*header.height * row_bytes) as usize;
+      let buffer_length = *header.height as usize * row_bytes;
 
       subimages.push(SubImage {
         width: *header.width,
@@ -67,16 +91,63 @@ fn decompress_data(data: &[u8], header: &PNGHeader) -> Result<(Vec<u8>, Vec<SubI
     }
   };
 
-  let mut decompressed: Vec<u8> = vec![0u8; expected_size];
-  let mut decompressor: Decompressor = Decompressor::new();
+  if get_allocation(expected_size, header, options) > options.limits.max_alloc {
+    return Err(RSMError::LimitExceeded);
+  }
 
-  decompressor
-    .zlib_decompress(data, &mut decompressed)
-    .map_err(|_| RSMError::DecompressionError)?;
+  let mut decompressed: Vec<u8> = vec![0u8; expected_size];
+  let unfilter: Unfilter = Unfilter::new(get_bytes_per_pixel(header));
+
+  #[cfg(feature = "parallel")]
+  if options.thread_count() > 1 {
+    png_parallel::inflate_scanlines(
+      stream,
+      &mut decompressed,
+      &subimages,
+      &unfilter,
+      header,
+      meta,
+      options,
+    )?;
+    return Ok((decompressed, subimages));
+  }
 
+  inflate_data(&stream.slices, &mut decompressed)?;
+  handle_scanlines(&mut decompressed, &subimages, &unfilter)?;
   Ok((decompressed, subimages))
 }
 
+/// Compute the amount of bytes allocated to decode an image: the decompressed
+/// data and the canvas it is mapped to are both allocated, while the
+/// compressed data is read in place.
+pub(super) fn get_allocation(
+  expected_size: usize,
+  header: &PNGHeader,
+  options: &DecodeOptions,
+) -> u64 {
+  let pixel_size: u64 = (options.color.channels() * get_sample_bytes(header, options)) as u64;
+  let canvas_size: u64 = *header.width as u64 * *header.height as u64 * pixel_size;
+  expected_size as u64 + canvas_size
+}
+
+/// Inflate the data of the IDAT chunks into the output
+pub(super) fn inflate_data(data: &[&[u8]], output: &mut [u8]) -> Result<(), RSMError> {
+  // A single chunk is inflated at once, which is faster than streaming, while
+  // data split over several chunks is streamed in place rather than copied
+  match data {
+    [data] => {
+      let mut decompressor: Decompressor = Decompressor::new();
+      decompressor
+        .zlib_decompress(data, output)
+        .map_err(|_| RSMError::DecompressionError)?;
+    }
+    slices => {
+      inflate_slices(slices, output)?;
+    }
+  }
+  Ok(())
+}
+
 /// Compute the bits per color channel based on the [color type](ColorType) per
 /// pixel, given by the image header.
 fn get_channels_per_pixels(header: &PNGHeader) -> u32 {
@@ -95,14 +166,23 @@ fn get_channels_per_pixels(header: &PNGHeader) -> u32 {
   }
 }
 
+/// Compute the amount of bytes per decoded sample, where 16-bit samples are
+/// only kept when requested.
+fn get_sample_bytes(header: &PNGHeader, options: &DecodeOptions) -> usize {
+  match (header.bit_depth, options.bit_depth) {
+    (BitDepth::D16, BitDepthHandling::Keep) => 2,
+    _ => 1,
+  }
+}
+
 /// Compute the amount of bytes per scanline depending on the width of an image
 /// or the width of multiple subimages.
-fn get_bytes_per_scanline(width: u32, header: &PNGHeader) -> u32 {
-  let depth: u32 = header.bit_depth as u32;
+fn get_bytes_per_scanline(width: u32, header: &PNGHeader) -> usize {
+  let depth: u64 = header.bit_depth as u64;
 
-  let bpp: u32 = get_channels_per_pixels(header) * depth;
-  let total_bits = bpp * width;
-  let bytes_per_row: u32 = total_bits.div_ceil(8);
+  let bpp: u64 = get_channels_per_pixels(header) as u64 * depth;
+  let total_bits: u64 = bpp * width as u64;
+  let bytes_per_row: usize = total_bits.div_ceil(8) as usize;
 
   // Add 1 for the filter byte at the start of a scanline
   1 + bytes_per_row
@@ -136,8 +216,8 @@ fn handle_adam7(header: &PNGHeader) -> (usize, Vec<SubImage>) {
     }
 
     if pass_width > 0 && pass_height > 0 {
-      let bytes_scanline: u32 = get_bytes_per_scanline(pass_width, header);
-      let buffer_length = (bytes_scanline * pass_height) as usize;
+      let bytes_scanline: usize = get_bytes_per_scanline(pass_width, header);
+      let buffer_length = bytes_scanline * pass_height as usize;
 
       images.push(SubImage {
         width: pass_width,
@@ -159,108 +239,171 @@ fn handle_adam7(header: &PNGHeader) -> (usize, Vec<SubImage>) {
 }
 
 /// Map the subimages to scanlines and the unfiltering them.
-fn handle_scanlines<'a>(
-  decompressed: &'a mut [u8],
-  images: &Vec<SubImage>,
-  header: &PNGHeader,
-) -> Result<&'a [u8], RSMError> {
+fn handle_scanlines(
+  decompressed: &mut [u8],
+  images: &[SubImage],
+  unfilter: &Unfilter,
+) -> Result<(), RSMError> {
   for image in images {
     // Get pass bytes
     let start_index: usize = image.buffer_offset;
     let end_index: usize = start_index + image.buffer_length;
     let pass: &mut [u8] = &mut decompressed[start_index..end_index];
-    let row_size = image.bytes_per_scanline as usize;
 
     // Remove 1 for the filter byte
-    let pixel_bytes_per_row = row_size - 1;
-    let mut previous: Vec<u8> = vec![0u8; pixel_bytes_per_row];
-
-    for index in 0..image.height {
-      let row_start = (index as usize) * row_size;
-      let filter_method: FilterType = pass[row_start].try_into()?;
-      let row: &mut [u8] = &mut pass[row_start + 1..row_start + row_size];
+    let first: Vec<u8> = vec![0u8; image.bytes_per_scanline - 1];
+    unfilter_rows(pass, image.bytes_per_scanline, &first, unfilter)?;
+  }
+  Ok(())
+}
 
-      unfilter_scanline(filter_method, row, &previous, header);
-      previous.copy_from_slice(row);
-    }
+/// Unfilter consecutive scanlines in place, the first one referring to the
+/// given previous row
+pub(super) fn unfilter_rows(
+  rows: &mut [u8],
+  row_size: usize,
+  first_previous: &[u8],
+  unfilter: &Unfilter,
+) -> Result<(), RSMError> {
+  let pixel_bytes_per_row: usize = row_size - 1;
+
+  for index in 0..rows.len() / row_size {
+    // The previous row is unfiltered in place, just before the current one
+    let (before, rest) = rows.split_at_mut(index * row_size);
+    let previous: &[u8] = match index {
+      0 => first_previous,
+      _ => &before[before.len() - pixel_bytes_per_row..],
+    };
+
+    let filter_method: FilterType = rest[0].try_into()?;
+    unfilter.apply(filter_method, &mut rest[1..row_size], previous);
   }
-  Ok(decompressed)
+  Ok(())
 }
 
-/// Undo the filter on a scanline.
-fn unfilter_scanline(method: FilterType, current: &mut [u8], previous: &[u8], header: &PNGHeader) {
-  let channels = get_channels_per_pixels(header);
-  let bits_per_pixel: u32 = channels * (header.bit_depth as u32);
-  let bpp = bits_per_pixel.div_ceil(8).max(1) as usize;
-
-  match method {
-    FilterType::None => {}
-    FilterType::Sub => unfilter_sub(current, bpp),
-    FilterType::Up => unfilter_up(current, previous),
-    FilterType::Average => unfilter_average(current, previous, bpp),
-    FilterType::Paeth => unfilter_paeth(current, previous, bpp),
-  };
+/// Number of bytes per complete pixel, rounded up to one byte for bit depths
+/// below 8
+fn get_bytes_per_pixel(header: &PNGHeader) -> usize {
+  let bits_per_pixel: u32 = get_channels_per_pixels(header) * (header.bit_depth as u32);
+  bits_per_pixel.div_ceil(8).max(1) as usize
 }
 
-/// Map unfiltered bytes to raw scanlines for mapping
-fn handle_bytes<'a>(bytes: &'a [u8], images: &Vec<SubImage>) -> Vec<&'a [u8]> {
-  let mut scanlines: Vec<&[u8]> = Vec::new();
+/// Map unfiltered bytes to the raw scanlines of each subimage
+fn handle_bytes<'a>(bytes: &'a [u8], images: &[SubImage]) -> Vec<Vec<&'a [u8]>> {
+  let mut passes: Vec<Vec<&[u8]>> = Vec::new();
 
   for image in images {
     let pass_bytes: &[u8] = &bytes[image.buffer_offset..image.buffer_offset + image.buffer_length];
-    let row_size = image.bytes_per_scanline as usize;
-
-    for i in 0..image.height {
-      let row_start = (i as usize) * row_size;
+    let row_size = image.bytes_per_scanline;
 
-      let scanline = &pass_bytes[row_start + 1..row_start + row_size];
-      scanlines.push(scanline)
-    }
+    let scanlines: Vec<&[u8]> = pass_bytes
+      .chunks_exact(row_size)
+      .map(|row| &row[1..])
+      .collect();
+    passes.push(scanlines);
   }
-  scanlines
+  passes
 }
 
-/// Map scanline bytes to pixels
+/// Map scanline bytes to pixels, one row of the canvas at a time
 fn map_pixels(
-  bytes: &[&[u8]],
-  images: &Vec<SubImage>,
+  passes: &[Vec<&[u8]>],
+  images: &[SubImage],
   header: &PNGHeader,
   meta: &PNGMetadata,
+  options: &DecodeOptions,
 ) -> Vec<u8> {
   let width = *header.width as usize;
   let height = *header.height as usize;
 
-  let mut canvas: Vec<u8> = vec![0u8; width * height * 4];
-  let mut scanline_iter: std::slice::Iter<'_, &[u8]> = bytes.iter();
+  let channels: usize = options.color.channels();
+  let sample_bytes: usize = get_sample_bytes(header, options);
+  let pixel_size: usize = channels * sample_bytes;
 
-  for image in images {
-    for row_index in 0..(image.height as usize) {
-      let current: &[u8] = scanline_iter.next().expect("Invalid data");
+  // 16-bit samples that are not kept are reduced to their most significant byte
+  let shift: u32 = if header.bit_depth == BitDepth::D16 && sample_bytes == 1 {
+    8
+  } else {
+    0
+  };
+
+  let row_length: usize = width * pixel_size;
+  let mut canvas: Vec<u8> = vec![0u8; height * row_length];
+  if row_length == 0 {
+    return canvas;
+  }
+
+  let map_row = |cy: usize, row: &mut [u8]| {
+    // Find the scanline of each subimage that covers the row
+    for (image, scanlines) in images.iter().zip(passes) {
+      let (y_start, y_step) = (image.y_start as usize, image.y_step as usize);
+      if cy < y_start || !(cy - y_start).is_multiple_of(y_step) {
+        continue;
+      }
+      let current: &[u8] = scanlines[(cy - y_start) / y_step];
 
       for col_index in 0..(image.width as usize) {
         let cx = (image.x_start as usize) + col_index * (image.x_step as usize);
-        let cy = (image.y_start as usize) + row_index * (image.y_step as usize);
 
-        let cpos = (cx + cy * width) * 4;
-        let (r, g, b, a) = read_pixel(current, col_index, header, meta);
+        let cpos = cx * pixel_size;
+        let [r, g, b, a] = read_pixel(current, col_index, header, meta).map(|v| v >> shift);
+        let samples: [u16; 4] = convert_pixel([r, g, b, a], options.color);
 
-        canvas[cpos] = r;
-        canvas[cpos + 1] = g;
-        canvas[cpos + 2] = b;
-        canvas[cpos + 3] = a;
+        for (channel, sample) in samples[..channels].iter().enumerate() {
+          let position = cpos + channel * sample_bytes;
+
+          if sample_bytes == 2 {
+            row[position..position + 2].copy_from_slice(&sample.to_be_bytes());
+          } else {
+            row[position] = *sample as u8;
+          }
+        }
       }
     }
+  };
+
+  #[cfg(feature = "parallel")]
+  if options.thread_count() > 1 {
+    png_parallel::for_each_row(&mut canvas, row_length, options.thread_count(), map_row);
+    return canvas;
+  }
+
+  for (cy, row) in canvas.chunks_exact_mut(row_length).enumerate() {
+    map_row(cy, row);
   }
   canvas
 }
 
-/// Read the pixel value
-fn read_pixel(
-  bytes: &[u8],
-  col_index: usize,
-  header: &PNGHeader,
-  meta: &PNGMetadata,
-) -> (u8, u8, u8, u8) {
+/// Convert RGBA samples to the channels of a [color target](ColorTarget). Luma
+/// is computed using the Rec. 709 coefficients.
+fn convert_pixel([r, g, b, a]: [u16; 4], target: ColorTarget) -> [u16; 4] {
+  let luma = || ((r as u32 * 2126 + g as u32 * 7152 + b as u32 * 722 + 5000) / 10000) as u16;
+
+  match target {
+    ColorTarget::Grey => [luma(), 0, 0, 0],
+    ColorTarget::GreyAlpha => [luma(), a, 0, 0],
+    ColorTarget::Rgb | ColorTarget::Rgba => [r, g, b, a],
+  }
+}
+
+/// Rearrange interleaved pixels so each channel is stored in its own plane
+pub(crate) fn to_planar(pixels: &[u8], channels: usize, sample_bytes: usize) -> Vec<u8> {
+  let pixel_size: usize = channels * sample_bytes;
+  let plane_size: usize = pixels.len() / channels;
+  let mut planes: Vec<u8> = vec![0u8; pixels.len()];
+
+  for (index, pixel) in pixels.chunks_exact(pixel_size).enumerate() {
+    for (channel, sample) in pixel.chunks_exact(sample_bytes).enumerate() {
+      let position = channel * plane_size + index * sample_bytes;
+      planes[position..position + sample_bytes].copy_from_slice(sample);
+    }
+  }
+  planes
+}
+
+/// Read the pixel value as RGBA samples, which are 16-bit values for 16-bit
+/// images and 8-bit values otherwise.
+fn read_pixel(bytes: &[u8], col_index: usize, header: &PNGHeader, meta: &PNGMetadata) -> [u16; 4] {
   let bit_depth = header.bit_depth as usize;
 
   // --- SUB-BYTE PACKING (1, 2, 4 bit depth) ---
@@ -272,22 +415,14 @@ fn read_pixel(
     let raw_val = (bytes[byte_idx] >> bit_shift) & mask;
 
     if header.color_type == ColorType::IndexedColor {
-      let [r, g, b] = if let Some(plte) = &meta.palette {
-        plte.get(raw_val as usize).copied().unwrap_or([0, 0, 0])
-      } else {
-        [0, 0, 0]
-      };
-
-      let a = if let Some(trns) = &meta.transparency_bytes {
-        trns.get(raw_val as usize).copied().unwrap_or(255)
-      } else {
-        255
-      };
-
-      return (r, g, b, a);
+      return read_palette(raw_val, meta);
     }
-    let scaled_val = ((raw_val as u32 * 255) / mask as u32) as u8;
-    return (scaled_val, scaled_val, scaled_val, 255);
+    let a: u16 = meta
+      .transparency
+      .as_ref()
+      .map_or(255, |trns| trns.grey_alpha(raw_val as u16, 255));
+    let scaled_val = (raw_val as u32 * 255 / mask as u32) as u16;
+    return [scaled_val, scaled_val, scaled_val, a];
   }
 
   let channels = get_channels_per_pixels(header) as usize;
@@ -295,58 +430,46 @@ fn read_pixel(
   let pixel_stride = channels * bytes_per_channel;
   let byte_idx = col_index * pixel_stride;
 
-  let r = bytes[byte_idx];
+  let sample = |channel: usize| -> u16 {
+    let start = byte_idx + channel * bytes_per_channel;
+    match bytes_per_channel {
+      2 => u16::from_be_bytes([bytes[start], bytes[start + 1]]),
+      _ => bytes[start] as u16,
+    }
+  };
+  let opaque: u16 = if bytes_per_channel == 2 {
+    u16::MAX
+  } else {
+    255
+  };
+  let transparency: Option<&Transparency> = meta.transparency.as_ref();
 
   match header.color_type {
     ColorType::Greyscale => {
-      let mut a = 255;
-      if let Some(trns) = &meta.transparency_bytes
-        && trns.len() >= 2
-        && r == trns[1]
-      {
-        a = 0;
-      }
-      (r, r, r, a)
+      let grey = sample(0);
+      let a = transparency.map_or(opaque, |trns| trns.grey_alpha(grey, opaque));
+      [grey, grey, grey, a]
     }
     ColorType::GreyscaleAlpha => {
-      let a = bytes[byte_idx + bytes_per_channel];
-      (r, r, r, a)
+      let grey = sample(0);
+      [grey, grey, grey, sample(1)]
     }
     ColorType::Truecolor => {
-      let g = bytes[byte_idx + bytes_per_channel];
-      let b = bytes[byte_idx + 2 * bytes_per_channel];
-      let mut a = 255;
-
-      if let Some(trns) = &meta.transparency_bytes
-        && trns.len() >= 6
-        && r == trns[1]
-        && g == trns[3]
-        && b == trns[5]
-      {
-        a = 0;
-      }
-      (r, g, b, a)
-    }
-    ColorType::TruecolorAlpha => {
-      let g = bytes[byte_idx + bytes_per_channel];
-      let b = bytes[byte_idx + 2 * bytes_per_channel];
-      let a = bytes[byte_idx + 3 * bytes_per_channel];
-      (r, g, b, a)
-    }
-    ColorType::IndexedColor => {
-      let [pr, pg, pb] = if let Some(plte) = &meta.palette {
-        plte.get(r as usize).copied().unwrap_or([0, 0, 0])
-      } else {
-        [0, 0, 0]
-      };
-
-      let pa = if let Some(trns) = &meta.transparency_bytes {
-        trns.get(r as usize).copied().unwrap_or(255)
-      } else {
-        255
-      };
-
-      (pr, pg, pb, pa)
+      let rgb: [u16; 3] = [sample(0), sample(1), sample(2)];
+      let a = transparency.map_or(opaque, |trns| trns.rgb_alpha(rgb, opaque));
+      [rgb[0], rgb[1], rgb[2], a]
     }
+    ColorType::TruecolorAlpha => [sample(0), sample(1), sample(2), sample(3)],
+    ColorType::IndexedColor => read_palette(bytes[byte_idx], meta),
   }
 }
+
+/// Read the RGBA value of a palette entry, which is opaque black when missing
+fn read_palette(index: u8, meta: &PNGMetadata) -> [u16; 4] {
+  let palette: Option<&Palette> = meta.palette.as_ref();
+  let [r, g, b, a] = palette
+    .and_then(|plte| plte.rgba(index, meta.transparency.as_ref()))
+    .unwrap_or([0, 0, 0, 255]);
+
+  [r as u16, g as u16, b as u16, a as u16]
+}
@@ -2,7 +2,7 @@ use crate::define_png_enum;
 
 define_png_enum! {
   /// Filter types for filter method 0
-  #[derive(Debug, PartialEq)]
+  #[derive(Debug, PartialEq, Clone, Copy)]
   pub enum FilterType {
     None = 0,
     Sub = 1,
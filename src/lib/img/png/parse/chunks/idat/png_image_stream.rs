@@ -0,0 +1,18 @@
+/// Compressed image data, split across the data of consecutive `IDAT` chunks
+/// which is kept in place
+#[derive(Debug, Default)]
+pub(crate) struct ImageStream<'d> {
+  /// Data of each chunk
+  pub slices: Vec<&'d [u8]>,
+
+  /// Position in the datastream of each chunk (at its length field)
+  pub offsets: Vec<usize>,
+}
+
+impl<'d> ImageStream<'d> {
+  /// Add the data of a chunk found at the given position
+  pub(crate) fn push(&mut self, data: &'d [u8], offset: usize) {
+    self.slices.push(data);
+    self.offsets.push(offset);
+  }
+}
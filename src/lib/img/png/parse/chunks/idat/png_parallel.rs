@@ -0,0 +1,666 @@
+use crate::lib::{
+  img::png::parse::{
+    chunks::{
+      idat::{
+        handle_idat::{get_allocation, inflate_data, unfilter_rows},
+        png_image_stream::ImageStream,
+        png_subimage::SubImage,
+        png_unfilter::Unfilter,
+      },
+      idot::png_image_division::ImageDivision,
+      ihdr::{png_header::PNGHeader, png_interlace_method::InterlaceMethod},
+    },
+    png_decode_options::DecodeOptions,
+    states::data::png_metadata::PNGMetadata,
+  },
+  util::err::rsm_error::RSMError,
+};
+use adler2::Adler32;
+use miniz_oxide::{
+  DataFormat, MZError, MZFlush, MZStatus,
+  inflate::stream::{InflateState, inflate},
+};
+use std::{
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    mpsc::{Sender, channel},
+  },
+  thread::{self, ScopedJoinHandle},
+};
+
+/// Smallest amount of data handed to a thread, below which splitting the work
+/// costs more than it saves
+const MIN_WORK_BYTES: usize = 64 * 1024;
+
+/// Amount of decompressed data passed at once from the inflating thread to
+/// the unfiltering thread
+const BAND_BYTES: usize = 256 * 1024;
+
+/// Bytes ending an empty stored block, which is how zlib flushes its output
+/// at a byte boundary
+const FLUSH_MARKER: [u8; 4] = [0x00, 0x00, 0xFF, 0xFF];
+
+/// Inflated data of a segment, and the amount of compressed bytes it used
+type Segment = (Vec<u8>, usize);
+
+/// Inflate the image data and unfilter its scanlines on several threads.
+///
+/// The data is inflated in parallel when it holds restart points, which are
+/// given by an `iDOT` chunk or found at zlib flush markers. Segments starting
+/// at a flush marker may still refer to the data before it, so they are
+/// inflated speculatively and checked against the Adler-32 of the stream,
+/// falling back to a single inflating thread otherwise. That thread passes
+/// bands of scanlines to the calling thread, which unfilters them as they
+/// come.
+pub(super) fn inflate_scanlines(
+  stream: &ImageStream<'_>,
+  output: &mut [u8],
+  images: &[SubImage],
+  unfilter: &Unfilter,
+  header: &PNGHeader,
+  meta: &PNGMetadata,
+  options: &DecodeOptions,
+) -> Result<(), RSMError> {
+  let threads: usize = options.thread_count();
+
+  // Segments are inflated to their own buffers before being joined
+  let allocation: u64 = get_allocation(output.len(), header, options) + output.len() as u64;
+  if allocation <= options.limits.max_alloc {
+    let divisions: Option<&[ImageDivision]> = match header.interlace_method {
+      InterlaceMethod::Null => meta.image_divisions.as_deref(),
+      InterlaceMethod::Adam7 => None,
+    };
+    let points: Vec<usize> = restart_points(stream, divisions, threads);
+    if !points.is_empty() && inflate_segments(&stream.slices, &points, output) {
+      return unfilter_passes(output, images, unfilter, threads);
+    }
+  }
+
+  match stream.slices.as_slice() {
+    [_] => {
+      inflate_data(&stream.slices, output)?;
+      unfilter_passes(output, images, unfilter, threads)
+    }
+    slices => inflate_pipelined(slices, output, images, unfilter),
+  }
+}
+
+/// Call a function on every row of an image with its index, handing bands of
+/// rows to several threads
+pub(super) fn for_each_row<F>(image: &mut [u8], row_length: usize, threads: usize, function: F)
+where
+  F: Fn(usize, &mut [u8]) + Sync,
+{
+  let rows: usize = image.len() / row_length;
+  let min_rows: usize = MIN_WORK_BYTES.div_ceil(row_length);
+  let band_rows: usize = rows.div_ceil(threads).max(min_rows);
+
+  let map_band = |band: usize, bytes: &mut [u8]| {
+    for (index, row) in bytes.chunks_exact_mut(row_length).enumerate() {
+      function(band * band_rows + index, row);
+    }
+  };
+
+  if band_rows >= rows {
+    return map_band(0, image);
+  }
+  thread::scope(|scope| {
+    for (band, bytes) in image.chunks_mut(band_rows * row_length).enumerate() {
+      let map_band = &map_band;
+      scope.spawn(move || map_band(band, bytes));
+    }
+  });
+}
+
+/// Unfilter the passes of an image, each pass being independent from the
+/// others
+fn unfilter_passes(
+  output: &mut [u8],
+  images: &[SubImage],
+  unfilter: &Unfilter,
+  threads: usize,
+) -> Result<(), RSMError> {
+  let workers: usize = threads.min(images.len());
+  let mut groups: Vec<Vec<(&SubImage, &mut [u8])>> = (0..workers).map(|_| Vec::new()).collect();
+
+  let mut rest: &mut [u8] = output;
+  for (index, image) in images.iter().enumerate() {
+    let (pass, tail) = rest.split_at_mut(image.buffer_length);
+    groups[index % workers].push((image, pass));
+    rest = tail;
+  }
+
+  let unfilter_group = |group: Vec<(&SubImage, &mut [u8])>| {
+    group.into_iter().try_for_each(|(image, pass)| {
+      let first: Vec<u8> = vec![0u8; image.bytes_per_scanline - 1];
+      unfilter_rows(pass, image.bytes_per_scanline, &first, unfilter)
+    })
+  };
+
+  if workers < 2 {
+    return groups.into_iter().try_for_each(unfilter_group);
+  }
+  thread::scope(|scope| {
+    let handles: Vec<ScopedJoinHandle<'_, Result<(), RSMError>>> = groups
+      .into_iter()
+      .map(|group| scope.spawn(|| unfilter_group(group)))
+      .collect();
+
+    handles.into_iter().try_for_each(|handle| {
+      handle
+        .join()
+        .unwrap_or(Err(RSMError::Other("Unfiltering thread panicked".into())))
+    })
+  })
+}
+
+/// Scanlines of a pass inflated together
+struct Band<'b> {
+  /// Index of the pass
+  image: usize,
+
+  /// Whether the band holds the first scanline of the pass
+  first: bool,
+
+  bytes: &'b mut [u8],
+}
+
+/// Inflate the image data on a thread while unfiltering it on the calling
+/// thread, band by band
+fn inflate_pipelined(
+  slices: &[&[u8]],
+  output: &mut [u8],
+  images: &[SubImage],
+  unfilter: &Unfilter,
+) -> Result<(), RSMError> {
+  let mut bands: Vec<Band<'_>> = Vec::new();
+  let mut rest: &mut [u8] = output;
+
+  for (index, image) in images.iter().enumerate() {
+    let row_size: usize = image.bytes_per_scanline;
+    let (pass, tail) = rest.split_at_mut(image.buffer_length);
+    rest = tail;
+
+    let band_size: usize = (BAND_BYTES / row_size).max(1) * row_size;
+    for (number, bytes) in pass.chunks_mut(band_size).enumerate() {
+      bands.push(Band {
+        image: index,
+        first: number == 0,
+        bytes,
+      });
+    }
+  }
+
+  thread::scope(|scope| {
+    let (sender, receiver) = channel::<Band<'_>>();
+    let inflater: ScopedJoinHandle<'_, Result<(), RSMError>> =
+      scope.spawn(move || inflate_bands(slices, bands, sender));
+
+    // Last unfiltered row, which the first row of the next band refers to
+    let mut previous: Vec<u8> = Vec::new();
+    let mut unfiltered: Result<(), RSMError> = Ok(());
+
+    // Stopping early drops the receiver, which stops the inflating thread
+    for band in receiver {
+      let row_size: usize = images[band.image].bytes_per_scanline;
+      if band.first {
+        previous = vec![0u8; row_size - 1];
+      }
+
+      unfiltered = unfilter_rows(band.bytes, row_size, &previous, unfilter);
+      if unfiltered.is_err() {
+        break;
+      }
+      previous.copy_from_slice(&band.bytes[band.bytes.len() - (row_size - 1)..]);
+    }
+
+    let inflated: Result<(), RSMError> = inflater
+      .join()
+      .unwrap_or(Err(RSMError::Other("Inflating thread panicked".into())));
+    inflated.and(unfiltered)
+  })
+}
+
+/// Fill the bands with the inflated data in order, sending each one once it
+/// is full. Bands after the end of the stream are left empty.
+fn inflate_bands<'b>(
+  slices: &[&[u8]],
+  bands: Vec<Band<'b>>,
+  sender: Sender<Band<'b>>,
+) -> Result<(), RSMError> {
+  let mut state: Box<InflateState> = InflateState::new_boxed(DataFormat::Zlib);
+  let mut slices = slices.iter().copied();
+  let mut input: &[u8] = slices.next().unwrap_or_default();
+  let mut ended: bool = false;
+
+  // Inflate until the output is full or the stream ends, returning the amount
+  // of bytes written
+  let mut fill = |output: &mut [u8], ended: &mut bool| -> Result<usize, RSMError> {
+    let mut filled: usize = 0;
+    while !*ended && filled < output.len() {
+      let result = inflate(&mut state, input, &mut output[filled..], MZFlush::None);
+      input = &input[result.bytes_consumed..];
+      filled += result.bytes_written;
+
+      match result.status {
+        Ok(MZStatus::StreamEnd) => *ended = true,
+        Ok(_) | Err(MZError::Buf) => {}
+        Err(_) => return Err(RSMError::DecompressionError),
+      }
+
+      // The output is not full, so the input is needed to make progress
+      if !*ended && filled < output.len() && input.is_empty() {
+        input = slices.next().ok_or(RSMError::DecompressionError)?;
+      }
+    }
+    Ok(filled)
+  };
+
+  for band in bands {
+    fill(band.bytes, &mut ended)?;
+    if sender.send(band).is_err() {
+      return Ok(());
+    }
+  }
+
+  // Every scanline is filled, so the rest of the stream may only end it
+  let mut extra: [u8; 1] = [0];
+  match fill(&mut extra, &mut ended)? {
+    0 => Ok(()),
+    _ => Err(RSMError::DecompressionError),
+  }
+}
+
+/// Positions in the compressed data where the segments after the first one
+/// start, which are either given by the image divisions when they match the
+/// chunks, or flush markers found close to evenly spaced positions
+fn restart_points(
+  stream: &ImageStream<'_>,
+  divisions: Option<&[ImageDivision]>,
+  threads: usize,
+) -> Vec<usize> {
+  let starts: Vec<usize> = stream
+    .slices
+    .iter()
+    .scan(0, |position, slice| {
+      let start: usize = *position;
+      *position += slice.len();
+      Some(start)
+    })
+    .collect();
+  let length: usize = stream.slices.iter().map(|slice| slice.len()).sum();
+
+  // Segments must follow the zlib header and each other
+  let is_ordered = |points: &[usize]| {
+    let bounds: Vec<usize> = [2]
+      .into_iter()
+      .chain(points.iter().copied())
+      .chain([length])
+      .collect();
+    bounds.windows(2).all(|pair| pair[0] < pair[1])
+  };
+
+  if let Some([_, divisions @ ..]) = divisions {
+    let points: Option<Vec<usize>> = divisions
+      .iter()
+      .map(|division| {
+        let index: Option<usize> = stream.offsets.iter().position(|&o| o == division.offset);
+        index.map(|index| starts[index])
+      })
+      .collect();
+
+    if let Some(points) = points.filter(|points| is_ordered(points)) {
+      return points;
+    }
+  }
+
+  let segments: usize = threads.min(length / MIN_WORK_BYTES);
+  let mut points: Vec<usize> = Vec::new();
+
+  for index in 1..segments {
+    let start: usize = (index * length / segments).max(points.last().map_or(0, |&p| p + 1));
+    let end: usize = (index + 1) * length / segments;
+    points.extend(find_marker(&stream.slices, start, end));
+  }
+  points.retain(|&point| point > 2 && point < length);
+  points
+}
+
+/// Parts of the compressed data between two positions, with the position
+/// of each
+fn parts_between<'d>(slices: &[&'d [u8]], start: usize, end: usize) -> Vec<(usize, &'d [u8])> {
+  let mut position: usize = 0;
+  let mut parts: Vec<(usize, &'d [u8])> = Vec::new();
+
+  for slice in slices {
+    let (slice_start, slice_end) = (position, position + slice.len());
+    position = slice_end;
+    if slice_end > start && slice_start < end {
+      let from: usize = start.max(slice_start);
+      parts.push((
+        from,
+        &slice[from - slice_start..end.min(slice_end) - slice_start],
+      ));
+    }
+  }
+  parts
+}
+
+/// Iterate over the bytes of the compressed data between two positions, with
+/// their position
+fn bytes_between<'d>(
+  slices: &[&'d [u8]],
+  start: usize,
+  end: usize,
+) -> impl Iterator<Item = (usize, u8)> + 'd {
+  parts_between(slices, start, end)
+    .into_iter()
+    .flat_map(|(from, part)| {
+      part
+        .iter()
+        .enumerate()
+        .map(move |(index, &byte)| (from + index, byte))
+    })
+}
+
+/// Split the compressed data between two positions, without copying it
+fn slices_between<'d>(slices: &[&'d [u8]], start: usize, end: usize) -> Vec<&'d [u8]> {
+  parts_between(slices, start, end)
+    .into_iter()
+    .map(|(_, part)| part)
+    .collect()
+}
+
+/// Find the position following the first flush marker between two positions.
+/// Each part of the data is searched as a whole, along with the few bytes
+/// joining it to the previous part, where a marker may be split.
+fn find_marker(slices: &[&[u8]], start: usize, end: usize) -> Option<usize> {
+  let marker_end = |window: &[u8]| window == FLUSH_MARKER;
+  // Last bytes of the previous parts, too few to hold a marker on their own
+  let mut carry: Vec<u8> = Vec::with_capacity(FLUSH_MARKER.len() * 2);
+
+  for (from, part) in parts_between(slices, start, end) {
+    let joint: usize = part.len().min(FLUSH_MARKER.len() - 1);
+    let carried: usize = carry.len();
+    carry.extend_from_slice(&part[..joint]);
+    if let Some(index) = carry.windows(FLUSH_MARKER.len()).position(marker_end) {
+      return Some(from - carried + index + FLUSH_MARKER.len());
+    }
+    if let Some(index) = part.windows(FLUSH_MARKER.len()).position(marker_end) {
+      return Some(from + index + FLUSH_MARKER.len());
+    }
+
+    carry.truncate(carried);
+    carry.extend_from_slice(part);
+    carry.drain(..carry.len().saturating_sub(FLUSH_MARKER.len() - 1));
+  }
+  None
+}
+
+/// Inflate the segments of a zlib stream starting at the given positions in
+/// parallel, and join them in the output. Returns whether the segments are
+/// independent, which is only known once they match the Adler-32 of the
+/// stream; the output is left untouched otherwise. The segments share a budget
+/// of the output size, so that their buffers never hold more than the image.
+fn inflate_segments(slices: &[&[u8]], points: &[usize], output: &mut [u8]) -> bool {
+  // A preset dictionary is never used by PNG
+  let header: Vec<u8> = bytes_between(slices, 0, 2).map(|(_, byte)| byte).collect();
+  let valid_header: bool = matches!(header[..], [cmf, flg]
+    if cmf & 0x0f == 8 && u16::from_be_bytes([cmf, flg]).is_multiple_of(31) && flg & 0x20 == 0);
+  if !valid_header {
+    return false;
+  }
+
+  let length: usize = slices.iter().map(|slice| slice.len()).sum();
+  let budget: AtomicUsize = AtomicUsize::new(output.len());
+  let bounds: Vec<usize> = [2]
+    .into_iter()
+    .chain(points.iter().copied())
+    .chain([length])
+    .collect();
+
+  let segments: Vec<Option<Segment>> = thread::scope(|scope| {
+    let handles: Vec<ScopedJoinHandle<'_, Option<Segment>>> = bounds
+      .windows(2)
+      .map(|bound| {
+        let input: Vec<&[u8]> = slices_between(slices, bound[0], bound[1]);
+        let last: bool = bound[1] == length;
+        let budget: &AtomicUsize = &budget;
+        scope.spawn(move || inflate_segment(&input, last, budget))
+      })
+      .collect();
+
+    handles
+      .into_iter()
+      .map(|handle| handle.join().ok().flatten())
+      .collect()
+  });
+  let Some(segments) = segments.into_iter().collect::<Option<Vec<Segment>>>() else {
+    return false;
+  };
+
+  // The checksum follows the end of the last segment
+  let consumed: usize = segments.last().map_or(0, |(_, consumed)| *consumed);
+  let trailer_start: usize = bounds[bounds.len() - 2] + consumed;
+  let trailer: Vec<u8> = bytes_between(slices, trailer_start, trailer_start + 4)
+    .map(|(_, byte)| byte)
+    .collect();
+  let Ok(trailer) = <[u8; 4]>::try_from(trailer) else {
+    return false;
+  };
+
+  let mut adler: Adler32 = Adler32::new();
+  segments
+    .iter()
+    .for_each(|(bytes, _)| adler.write_slice(bytes));
+  let total: usize = segments.iter().map(|(bytes, _)| bytes.len()).sum();
+  if total != output.len() || adler.checksum() != u32::from_be_bytes(trailer) {
+    return false;
+  }
+
+  let mut written: usize = 0;
+  for (bytes, _) in segments {
+    output[written..written + bytes.len()].copy_from_slice(&bytes);
+    written += bytes.len();
+  }
+  true
+}
+
+/// Inflate a segment of raw Deflate data, which must end the stream when it
+/// is the last one. The inflated data is taken from the shared budget as it
+/// comes. Returns the inflated data and the amount of bytes consumed, or
+/// `None` when the segment is invalid or exceeds the budget.
+fn inflate_segment(input: &[&[u8]], last: bool, budget: &AtomicUsize) -> Option<Segment> {
+  const STEP: usize = 64 * 1024;
+
+  let mut state: Box<InflateState> = InflateState::new_boxed(DataFormat::Raw);
+  let mut buffer: Vec<u8> = vec![0u8; STEP];
+  let mut output: Vec<u8> = Vec::new();
+  let mut consumed: usize = 0;
+
+  for slice in input {
+    let mut slice: &[u8] = slice;
+    loop {
+      let result = inflate(&mut state, slice, &mut buffer, MZFlush::None);
+      slice = &slice[result.bytes_consumed..];
+      consumed += result.bytes_consumed;
+
+      let written: usize = result.bytes_written;
+      let taken: Result<usize, usize> =
+        budget.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |left| {
+          left.checked_sub(written)
+        });
+      if taken.is_err() {
+        return None;
+      }
+      output.reserve_exact(written);
+      output.extend_from_slice(&buffer[..written]);
+
+      match result.status {
+        Ok(MZStatus::StreamEnd) => return last.then_some((output, consumed)),
+        Ok(_) | Err(MZError::Buf) => {}
+        Err(_) => return None,
+      }
+
+      // A full output may hold back more data, even once the input is read
+      if slice.is_empty() && written < STEP {
+        break;
+      }
+    }
+  }
+
+  // The last segment did not reach the end of the stream
+  (!last).then_some((output, consumed))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use miniz_oxide::deflate::{core::CompressorOxide, stream::deflate};
+
+  /// Compress data as a zlib stream, flushing it after each part with the
+  /// given mode
+  fn zlib_parts(parts: &[&[u8]], flush: MZFlush) -> Vec<u8> {
+    let mut compressor: Box<CompressorOxide> = Box::default();
+    compressor.set_format_and_level(DataFormat::Zlib, 6);
+
+    let mut output: Vec<u8> =
+      vec![0u8; parts.iter().map(|part| part.len()).sum::<usize>() * 2 + 1024];
+    let mut written: usize = 0;
+    for (index, part) in parts.iter().enumerate() {
+      let flush: MZFlush = if index + 1 == parts.len() {
+        MZFlush::Finish
+      } else {
+        flush
+      };
+      let result = deflate(&mut compressor, part, &mut output[written..], flush);
+      assert_eq!(result.bytes_consumed, part.len());
+      written += result.bytes_written;
+    }
+    output.truncate(written);
+    output
+  }
+
+  /// Pseudo-random data split in parts, each one starting with the end of the
+  /// previous one so that it can refer to it
+  fn sample_parts() -> Vec<Vec<u8>> {
+    let mut state: u32 = 0x2545_f491;
+    let mut random = move || {
+      state ^= state << 13;
+      state ^= state >> 17;
+      state ^= state << 5;
+      state as u8
+    };
+
+    let mut parts: Vec<Vec<u8>> = Vec::new();
+    for _ in 0..8 {
+      let mut part: Vec<u8> = parts.last().map_or(Vec::new(), |last: &Vec<u8>| {
+        last[last.len() - 1024..].to_vec()
+      });
+      part.extend((0..MIN_WORK_BYTES).map(|_| random()));
+      parts.push(part);
+    }
+    parts
+  }
+
+  #[test]
+  fn test_restart_points() {
+    let parts: Vec<Vec<u8>> = sample_parts();
+    let slices: Vec<&[u8]> = parts.iter().map(Vec::as_slice).collect();
+    let compressed: Vec<u8> = zlib_parts(&slices, MZFlush::Full);
+
+    // The markers are found even when split across chunks
+    let stream: ImageStream<'_> = ImageStream {
+      slices: compressed.chunks(4099).collect(),
+      offsets: Vec::new(),
+    };
+    let points: Vec<usize> = restart_points(&stream, None, 4);
+    assert_eq!(points.len(), 3);
+
+    let data: Vec<u8> = parts.concat();
+    let mut output: Vec<u8> = vec![0u8; data.len()];
+    assert!(inflate_segments(&stream.slices, &points, &mut output));
+    assert_eq!(output, data);
+  }
+
+  #[test]
+  fn test_find_marker() {
+    let mut data: Vec<u8> = vec![0xFF; 40];
+    data[17..21].copy_from_slice(&FLUSH_MARKER);
+    data[30..34].copy_from_slice(&FLUSH_MARKER);
+
+    // Markers are found wherever the chunks split them
+    for size in 1..8 {
+      let slices: Vec<&[u8]> = data.chunks(size).collect();
+      assert_eq!(find_marker(&slices, 0, data.len()), Some(21), "{size}");
+      assert_eq!(find_marker(&slices, 18, data.len()), Some(34), "{size}");
+      assert_eq!(find_marker(&slices, 18, 33), None, "{size}");
+    }
+  }
+
+  #[test]
+  fn test_dependent_segments() {
+    // Segments after a sync flush refer to the data before them
+    let parts: Vec<Vec<u8>> = sample_parts();
+    let slices: Vec<&[u8]> = parts.iter().map(Vec::as_slice).collect();
+    let compressed: Vec<u8> = zlib_parts(&slices, MZFlush::Sync);
+
+    let stream: ImageStream<'_> = ImageStream {
+      slices: vec![&compressed],
+      offsets: Vec::new(),
+    };
+    let points: Vec<usize> = restart_points(&stream, None, 4);
+    assert_eq!(points.len(), 3);
+
+    let mut output: Vec<u8> = vec![0u8; parts.concat().len()];
+    assert!(!inflate_segments(&stream.slices, &points, &mut output));
+    assert!(output.iter().all(|&byte| byte == 0));
+  }
+
+  #[test]
+  fn test_segment_budget() {
+    let parts: Vec<Vec<u8>> = sample_parts();
+    let slices: Vec<&[u8]> = parts.iter().map(Vec::as_slice).collect();
+    let compressed: Vec<u8> = zlib_parts(&slices, MZFlush::Full);
+    let data: Vec<u8> = parts.concat();
+
+    let stream: ImageStream<'_> = ImageStream {
+      slices: vec![&compressed],
+      offsets: Vec::new(),
+    };
+    let points: Vec<usize> = restart_points(&stream, None, 4);
+    assert_eq!(points.len(), 3);
+
+    let mut output: Vec<u8> = vec![0u8; data.len()];
+    assert!(inflate_segments(&stream.slices, &points, &mut output));
+    assert_eq!(output, data);
+
+    // The segments together may not outgrow the output
+    let mut output: Vec<u8> = vec![0u8; data.len() - 1];
+    assert!(!inflate_segments(&stream.slices, &points, &mut output));
+    assert!(output.iter().all(|&byte| byte == 0));
+
+    // A segment takes what it inflates from the budget, and fails once spent
+    let input: Vec<&[u8]> = slices_between(&stream.slices, 2, points[0]);
+    let budget: AtomicUsize = AtomicUsize::new(data.len());
+    let (bytes, _) = inflate_segment(&input, false, &budget).unwrap();
+    assert_eq!(bytes, data[..bytes.len()]);
+    assert_eq!(budget.load(Ordering::Relaxed), data.len() - bytes.len());
+
+    let budget: AtomicUsize = AtomicUsize::new(bytes.len() - 1);
+    assert!(inflate_segment(&input, false, &budget).is_none());
+  }
+
+  #[test]
+  fn test_for_each_row() {
+    let row_length: usize = 1024;
+    let mut image: Vec<u8> = vec![0u8; row_length * 300];
+    for_each_row(&mut image, row_length, 7, |index, row| {
+      row.fill(index as u8)
+    });
+
+    for (index, row) in image.chunks_exact(row_length).enumerate() {
+      assert!(row.iter().all(|&byte| byte == index as u8));
+    }
+  }
+}
@@ -1,10 +1,26 @@
-use std::fmt::{Debug, Formatter, Result};
+use crate::lib::{
+  img::png::parse::chunks::idat::png_pixel_format::{ColorTarget, PixelLayout},
+  util::err::rsm_error::RSMError,
+};
+use std::{
+  borrow::Cow,
+  fmt::{Debug, Formatter, Result},
+};
 
 /// Represents pixel data from the `IDAT` chunk.
 pub struct PixelData {
   pub data: Vec<u8>,
   pub width: u32,
   pub height: u32,
+
+  /// [Color channels](ColorTarget) of each pixel
+  pub color: ColorTarget,
+
+  /// Bit depth of each sample, either 8 or 16
+  pub bit_depth: u8,
+
+  /// [Arrangement](PixelLayout) of the pixels in memory
+  pub layout: PixelLayout,
 }
 
 impl Debug for PixelData {
@@ -12,6 +28,9 @@ impl Debug for PixelData {
     formatter
       .debug_struct("PixelData")
       .field("size", &self.data.len())
+      .field("color", &self.color)
+      .field("bit_depth", &self.bit_depth)
+      .field("layout", &self.layout)
       .finish()
   }
 }
@@ -42,3 +61,173 @@ impl PixelData {
     }
   }
 }
+
+impl PixelData {
+  /// Split the pixels into planes holding units of a given size. Interleaved
+  /// pixels form a single plane of whole pixels, while planar pixels form one
+  /// plane of samples per channel.
+  pub(crate) fn planes(&self) -> (usize, usize) {
+    let sample_bytes: usize = (self.bit_depth / 8) as usize;
+    let channels: usize = self.color.channels();
+
+    match self.layout {
+      PixelLayout::Interleaved => (1, channels * sample_bytes),
+      PixelLayout::Planar => (channels, sample_bytes),
+    }
+  }
+
+  /// Rebuild the pixels with new dimensions, where each pixel of the result is
+  /// copied from the source pixel given by `source(x, y)`.
+  pub(crate) fn remap<F>(&mut self, width: u32, height: u32, source: F)
+  where
+    F: Fn(usize, usize) -> (usize, usize),
+  {
+    self.remap_filled(width, height, |x, y| Some(source(x, y)), &[]);
+  }
+
+  /// Rebuild the pixels with new dimensions, where each pixel of the result is
+  /// copied from the source pixel given by `source(x, y)`, or set to the
+  /// samples of an interleaved `fill` pixel when there is none.
+  pub(crate) fn remap_filled<F>(&mut self, width: u32, height: u32, source: F, fill: &[u8])
+  where
+    F: Fn(usize, usize) -> Option<(usize, usize)>,
+  {
+    let (planes, unit) = self.planes();
+    let source_width: usize = self.width as usize;
+    let source_plane: usize = source_width * self.height as usize * unit;
+    let plane: usize = width as usize * height as usize * unit;
+
+    let mut data: Vec<u8> = vec![0u8; plane * planes];
+    for index in 0..planes {
+      let from: &[u8] = &self.data[index * source_plane..(index + 1) * source_plane];
+      let to: &mut [u8] = &mut data[index * plane..(index + 1) * plane];
+
+      for (y, row) in to.chunks_exact_mut(width as usize * unit).enumerate() {
+        for (x, pixel) in row.chunks_exact_mut(unit).enumerate() {
+          match source(x, y) {
+            Some((sx, sy)) => {
+              let start: usize = (sx + sy * source_width) * unit;
+              pixel.copy_from_slice(&from[start..start + unit]);
+            }
+            None => pixel.copy_from_slice(&fill[index * unit..(index + 1) * unit]),
+          }
+        }
+      }
+    }
+
+    self.data = data;
+    self.width = width;
+    self.height = height;
+  }
+
+  /// Copy the pixels within a rectangle, which must lie within the pixels
+  pub(crate) fn region(&self, x: usize, y: usize, width: usize, height: usize) -> PixelData {
+    let (planes, unit) = self.planes();
+    let plane: usize = self.width as usize * self.height as usize * unit;
+
+    let mut data: Vec<u8> = Vec::with_capacity(width * height * unit * planes);
+    for index in 0..planes {
+      for row in y..y + height {
+        let start: usize = index * plane + (row * self.width as usize + x) * unit;
+        data.extend_from_slice(&self.data[start..start + width * unit]);
+      }
+    }
+    PixelData {
+      data,
+      width: width as u32,
+      height: height as u32,
+      color: self.color,
+      bit_depth: self.bit_depth,
+      layout: self.layout,
+    }
+  }
+
+  /// Overwrite the pixels within a rectangle starting at `(x, y)` with pixels
+  /// of the same format, which must lie within these ones
+  pub(crate) fn paste(&mut self, region: &PixelData, x: usize, y: usize) {
+    let (planes, unit) = self.planes();
+    let plane: usize = self.width as usize * self.height as usize * unit;
+    let row_size: usize = region.width as usize * unit;
+    if row_size == 0 {
+      return;
+    }
+
+    for (index, rows) in region
+      .data
+      .chunks_exact(row_size * region.height as usize)
+      .take(planes)
+      .enumerate()
+    {
+      for (row, bytes) in rows.chunks_exact(row_size).enumerate() {
+        let start: usize = index * plane + ((y + row) * self.width as usize + x) * unit;
+        self.data[start..start + row_size].copy_from_slice(bytes);
+      }
+    }
+  }
+
+  /// Check the data holds every sample of the pixels
+  pub(crate) fn validate(&self) -> std::result::Result<(), RSMError> {
+    if !matches!(self.bit_depth, 8 | 16) {
+      return Err(RSMError::InvalidContent);
+    }
+    let pixel_size: usize = self.color.channels() * (self.bit_depth / 8) as usize;
+    match self.data.len() == self.width as usize * self.height as usize * pixel_size {
+      true => Ok(()),
+      false => Err(RSMError::InvalidLength),
+    }
+  }
+}
+
+impl PixelData {
+  /// Obtain the pixels with interleaved channels, whatever their layout
+  pub fn interleaved(&self) -> Cow<'_, [u8]> {
+    if self.layout == PixelLayout::Interleaved {
+      return Cow::Borrowed(&self.data);
+    }
+
+    let (channels, sample_bytes) = self.planes();
+    let plane: usize = self.data.len() / channels;
+    let mut data: Vec<u8> = vec![0u8; self.data.len()];
+
+    for (index, pixel) in data.chunks_exact_mut(channels * sample_bytes).enumerate() {
+      for (channel, sample) in pixel.chunks_exact_mut(sample_bytes).enumerate() {
+        let start: usize = channel * plane + index * sample_bytes;
+        sample.copy_from_slice(&self.data[start..start + sample_bytes]);
+      }
+    }
+    Cow::Owned(data)
+  }
+}
+
+impl PixelData {
+  /// Create interleaved 8-bit RGBA pixels from their colors
+  pub fn from_rgba8(width: u32, height: u32, colors: &[[u8; 4]]) -> Self {
+    Self {
+      data: colors.concat(),
+      width,
+      height,
+      color: ColorTarget::Rgba,
+      bit_depth: 8,
+      layout: PixelLayout::Interleaved,
+    }
+  }
+
+  /// Obtain the color of each pixel as 8-bit RGBA samples, keeping the most
+  /// significant byte of 16-bit samples
+  pub fn to_rgba8(&self) -> Vec<[u8; 4]> {
+    let sample_bytes: usize = (self.bit_depth / 8) as usize;
+    self
+      .interleaved()
+      .chunks_exact(self.color.channels() * sample_bytes)
+      .map(|pixel| {
+        let sample = |channel: usize| pixel[channel * sample_bytes];
+        match self.color {
+          ColorTarget::Grey => [sample(0), sample(0), sample(0), 255],
+          ColorTarget::GreyAlpha => [sample(0), sample(0), sample(0), sample(1)],
+          ColorTarget::Rgb => [sample(0), sample(1), sample(2), 255],
+          ColorTarget::Rgba => [sample(0), sample(1), sample(2), sample(3)],
+        }
+      })
+      .collect()
+  }
+}
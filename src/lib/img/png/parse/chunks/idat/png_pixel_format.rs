@@ -0,0 +1,57 @@
+/// Color channels of decoded pixels, independently of the color type of the
+/// image.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum ColorTarget {
+  /// Luma only
+  Grey,
+
+  /// Luma followed by alpha
+  GreyAlpha,
+
+  /// Red, green and blue, without alpha
+  Rgb,
+
+  /// Red, green, blue and alpha
+  #[default]
+  Rgba,
+}
+
+impl ColorTarget {
+  /// Amount of channels per pixel
+  pub fn channels(&self) -> usize {
+    match self {
+      Self::Grey => 1,
+      Self::GreyAlpha => 2,
+      Self::Rgb => 3,
+      Self::Rgba => 4,
+    }
+  }
+
+  /// Determine if the last channel of a pixel is alpha
+  pub fn has_alpha(&self) -> bool {
+    matches!(self, Self::GreyAlpha | Self::Rgba)
+  }
+}
+
+/// Handling of 16-bit samples while decoding. Images with a lower bit depth
+/// are always decoded to 8-bit samples.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum BitDepthHandling {
+  /// Keep the most significant byte of 16-bit samples
+  #[default]
+  Strip,
+
+  /// Keep 16-bit samples, stored in big-endian order
+  Keep,
+}
+
+/// Arrangement of the channels of decoded pixels in memory
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum PixelLayout {
+  /// The channels of a pixel follow each other (e.g. `RGBARGBA`)
+  #[default]
+  Interleaved,
+
+  /// Each channel is stored in its own plane (e.g. `RRGGBBAA`)
+  Planar,
+}
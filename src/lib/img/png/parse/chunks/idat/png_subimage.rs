@@ -1,7 +1,7 @@
 pub struct SubImage {
   pub width: u32,
   pub height: u32,
-  pub bytes_per_scanline: u32,
+  pub bytes_per_scanline: usize,
   pub buffer_offset: usize,
   pub buffer_length: usize,
   pub x_step: u32,
@@ -1,3 +1,5 @@
+use crate::lib::img::png::parse::chunks::idat::png_filters::FilterType;
+
 /// Undo the **sub** filter
 pub(crate) fn unfilter_sub(current: &mut [u8], bpp: usize) {
   for i in bpp..current.len() {
@@ -34,7 +36,7 @@ pub(crate) fn unfilter_paeth(current: &mut [u8], previous: &[u8], bpp: usize) {
 }
 
 /// Paeth predictor function
-fn paeth_predictor(a: u8, b: u8, c: u8) -> u8 {
+pub(crate) fn paeth_predictor(a: u8, b: u8, c: u8) -> u8 {
   let p = a as i32 + b as i32 - c as i32;
   let pa = (p - a as i32).abs();
   let pb = (p - b as i32).abs();
@@ -48,3 +50,176 @@ fn paeth_predictor(a: u8, b: u8, c: u8) -> u8 {
     c
   }
 }
+
+/// Kernels undoing the filters of scanlines with a given number of bytes per
+/// pixel, chosen once per image. Pixels of 3, 4, 6 and 8 bytes use SIMD
+/// instructions when the processor supports them, and other pixels use the
+/// portable implementations.
+#[derive(Debug, Clone, Copy)]
+pub struct Unfilter {
+  bpp: usize,
+  sub: fn(&mut [u8], usize),
+  up: fn(&mut [u8], &[u8]),
+  average: fn(&mut [u8], &[u8], usize),
+  paeth: fn(&mut [u8], &[u8], usize),
+}
+
+impl Unfilter {
+  /// Select the fastest kernels available for the pixel size
+  pub fn new(bpp: usize) -> Self {
+    #[cfg(target_arch = "x86_64")]
+    {
+      use crate::lib::img::png::parse::chunks::idat::png_unfilter_x86 as simd;
+
+      let mut unfilter: Self = Self::portable(bpp);
+      unfilter.up = match std::arch::is_x86_feature_detected!("avx2") {
+        // SAFETY: AVX2 is detected by `is_x86_feature_detected!` just above
+        true => |current, previous| unsafe { simd::unfilter_up_avx2(current, previous) },
+        // SAFETY: SSE2 is part of the x86-64 baseline, so every x86-64
+        // processor supports it
+        false => |current, previous| unsafe { simd::unfilter_up_sse2(current, previous) },
+      };
+      // SAFETY: the pixel kernels of the x86 module only use SSE2, which is
+      // part of the x86-64 baseline
+      unfilter.with_pixel_kernels(simd_kernels!(simd, bpp))
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    {
+      use crate::lib::img::png::parse::chunks::idat::png_unfilter_neon as simd;
+
+      let mut unfilter: Self = Self::portable(bpp);
+      if std::arch::is_aarch64_feature_detected!("neon") {
+        // SAFETY: NEON is detected by `is_aarch64_feature_detected!` just
+        // above, for both the up kernel and the pixel kernels
+        unfilter.up = |current, previous| unsafe { simd::unfilter_up(current, previous) };
+        unfilter = unfilter.with_pixel_kernels(simd_kernels!(simd, bpp));
+      }
+      unfilter
+    }
+
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    Self::portable(bpp)
+  }
+
+  /// Select the scalar kernels, which work on every processor
+  pub fn portable(bpp: usize) -> Self {
+    Self {
+      bpp,
+      sub: unfilter_sub,
+      up: unfilter_up,
+      average: unfilter_average,
+      paeth: unfilter_paeth,
+    }
+  }
+
+  #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+  fn with_pixel_kernels(mut self, kernels: Option<PixelKernels>) -> Self {
+    if let Some((sub, average, paeth)) = kernels {
+      (self.sub, self.average, self.paeth) = (sub, average, paeth);
+    }
+    self
+  }
+
+  /// Undo the filter of a scanline, given the unfiltered previous scanline
+  pub fn apply(&self, method: FilterType, current: &mut [u8], previous: &[u8]) {
+    // The pixel kernels only handle whole pixels
+    let unfilter: Self = match current.len().is_multiple_of(self.bpp) {
+      true => *self,
+      false => Self::portable(self.bpp),
+    };
+
+    match method {
+      FilterType::None => {}
+      FilterType::Sub => (unfilter.sub)(current, self.bpp),
+      FilterType::Up => (unfilter.up)(current, previous),
+      FilterType::Average => (unfilter.average)(current, previous, self.bpp),
+      FilterType::Paeth => (unfilter.paeth)(current, previous, self.bpp),
+    }
+  }
+}
+
+/// Sub, average and paeth kernels for a pixel size
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+type PixelKernels = (
+  fn(&mut [u8], usize),
+  fn(&mut [u8], &[u8], usize),
+  fn(&mut [u8], &[u8], usize),
+);
+
+/// Select the SIMD kernels of a module specialized for a pixel size. Callers
+/// must check the processor supports the instructions of the module.
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+macro_rules! simd_kernels {
+  ($module: ident, $bpp: expr) => {
+    match $bpp {
+      3 => Some(simd_kernels!($module, 3usize,)),
+      4 => Some(simd_kernels!($module, 4usize,)),
+      6 => Some(simd_kernels!($module, 6usize,)),
+      8 => Some(simd_kernels!($module, 8usize,)),
+      _ => None,
+    }
+  };
+  ($module: ident, $bpp: expr,) => {
+    // SAFETY: the kernels only require the target features of the module,
+    // which every caller checks before selecting them: SSE2 is part of the
+    // x86-64 baseline, and NEON is detected with `is_aarch64_feature_detected!`
+    (
+      (|current, _| unsafe { $module::unfilter_sub::<$bpp>(current) }) as fn(&mut [u8], usize),
+      (|current, previous, _| unsafe { $module::unfilter_average::<$bpp>(current, previous) })
+        as fn(&mut [u8], &[u8], usize),
+      (|current, previous, _| unsafe { $module::unfilter_paeth::<$bpp>(current, previous) })
+        as fn(&mut [u8], &[u8], usize),
+    )
+  };
+}
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+use simd_kernels;
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use proptest::{collection::vec, prelude::Strategy, prop_assert_eq, proptest};
+
+  /// Two scanlines of whole pixels, with the number of bytes per pixel
+  fn scanlines() -> impl Strategy<Value = (usize, Vec<u8>, Vec<u8>)> {
+    (1usize..=8, 0usize..64).prop_flat_map(|(bpp, pixels)| {
+      (
+        proptest::strategy::Just(bpp),
+        vec(0u8..=255, bpp * pixels),
+        vec(0u8..=255, bpp * pixels),
+      )
+    })
+  }
+
+  proptest! {
+    /// Test the selected kernels match the portable ones
+    #[test]
+    fn test_simd_matches_portable(
+      (bpp, current, previous) in scanlines(),
+      filter in 0u8..=4,
+    ) {
+      let filter: FilterType = FilterType::try_from(filter).unwrap();
+
+      let mut expected: Vec<u8> = current.clone();
+      Unfilter::portable(bpp).apply(filter, &mut expected, &previous);
+
+      let mut actual: Vec<u8> = current;
+      Unfilter::new(bpp).apply(filter, &mut actual, &previous);
+      prop_assert_eq!(actual, expected);
+    }
+
+    /// Test partial pixels fall back to the portable kernels
+    #[test]
+    fn test_partial_pixels(current in vec(0u8..=255, 0..40), previous in vec(0u8..=255, 40)) {
+      for filter in [FilterType::Sub, FilterType::Average, FilterType::Paeth] {
+        let mut expected: Vec<u8> = current.clone();
+        Unfilter::portable(4).apply(filter, &mut expected, &previous);
+
+        let mut actual: Vec<u8> = current.clone();
+        Unfilter::new(4).apply(filter, &mut actual, &previous);
+        prop_assert_eq!(actual, expected);
+      }
+    }
+  }
+}
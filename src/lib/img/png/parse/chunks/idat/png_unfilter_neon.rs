@@ -0,0 +1,86 @@
+use std::arch::aarch64::{
+  uint8x8_t, uint16x8_t, vabd_u8, vabdq_u16, vadd_u8, vaddl_u8, vaddq_u8, vbsl_u8, vceqq_u16,
+  vcreate_u8, vget_lane_u64, vhadd_u8, vld1q_u8, vminq_u16, vmovl_u8, vmovn_u16,
+  vreinterpret_u64_u8, vshll_n_u8, vst1q_u8,
+};
+
+/// Load a pixel of `BPP` bytes (at most 8) in the low bytes of a register
+#[inline]
+#[target_feature(enable = "neon")]
+fn load<const BPP: usize>(bytes: &[u8]) -> uint8x8_t {
+  let mut buffer: [u8; 8] = [0; 8];
+  buffer[..BPP].copy_from_slice(&bytes[..BPP]);
+  vcreate_u8(u64::from_le_bytes(buffer))
+}
+
+/// Store the low `BPP` bytes of a register
+#[inline]
+#[target_feature(enable = "neon")]
+fn store<const BPP: usize>(value: uint8x8_t, bytes: &mut [u8]) {
+  let buffer: [u8; 8] = vget_lane_u64::<0>(vreinterpret_u64_u8(value)).to_le_bytes();
+  bytes[..BPP].copy_from_slice(&buffer[..BPP]);
+}
+
+#[target_feature(enable = "neon")]
+pub(super) fn unfilter_sub<const BPP: usize>(current: &mut [u8]) {
+  let mut left: uint8x8_t = vcreate_u8(0);
+  for pixel in current.chunks_exact_mut(BPP) {
+    left = vadd_u8(load::<BPP>(pixel), left);
+    store::<BPP>(left, pixel);
+  }
+}
+
+#[target_feature(enable = "neon")]
+pub(super) fn unfilter_up(current: &mut [u8], previous: &[u8]) {
+  let mut current = current.chunks_exact_mut(16);
+  let mut previous = previous.chunks_exact(16);
+
+  for (row, above) in (&mut current).zip(&mut previous) {
+    // SAFETY: both chunks hold exactly 16 bytes
+    unsafe {
+      let sum = vaddq_u8(vld1q_u8(row.as_ptr()), vld1q_u8(above.as_ptr()));
+      vst1q_u8(row.as_mut_ptr(), sum);
+    }
+  }
+  super::png_unfilter::unfilter_up(current.into_remainder(), previous.remainder());
+}
+
+#[target_feature(enable = "neon")]
+pub(super) fn unfilter_average<const BPP: usize>(current: &mut [u8], previous: &[u8]) {
+  let mut left: uint8x8_t = vcreate_u8(0);
+  for (pixel, above) in current
+    .chunks_exact_mut(BPP)
+    .zip(previous.chunks_exact(BPP))
+  {
+    left = vadd_u8(load::<BPP>(pixel), vhadd_u8(left, load::<BPP>(above)));
+    store::<BPP>(left, pixel);
+  }
+}
+
+#[target_feature(enable = "neon")]
+pub(super) fn unfilter_paeth<const BPP: usize>(current: &mut [u8], previous: &[u8]) {
+  let mut a: uint8x8_t = vcreate_u8(0);
+  let mut c: uint8x8_t = vcreate_u8(0);
+
+  for (pixel, above) in current
+    .chunks_exact_mut(BPP)
+    .zip(previous.chunks_exact(BPP))
+  {
+    let b: uint8x8_t = load::<BPP>(above);
+
+    // With p = a + b - c: |p - a| = |b - c|, |p - b| = |a - c|, and
+    // |p - c| = |(a + b) - 2c|, which needs 16 bits
+    let pa: uint16x8_t = vmovl_u8(vabd_u8(b, c));
+    let pb: uint16x8_t = vmovl_u8(vabd_u8(a, c));
+    let pc: uint16x8_t = vabdq_u16(vaddl_u8(a, b), vshll_n_u8::<1>(c));
+
+    let smallest: uint16x8_t = vminq_u16(pc, vminq_u16(pa, pb));
+    let use_a: uint8x8_t = vmovn_u16(vceqq_u16(pa, smallest));
+    let use_b: uint8x8_t = vmovn_u16(vceqq_u16(pb, smallest));
+    let predictor: uint8x8_t = vbsl_u8(use_a, a, vbsl_u8(use_b, b, c));
+
+    a = vadd_u8(load::<BPP>(pixel), predictor);
+    store::<BPP>(a, pixel);
+    c = b;
+  }
+}
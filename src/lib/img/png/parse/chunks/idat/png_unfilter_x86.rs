@@ -0,0 +1,140 @@
+use std::arch::x86_64::{
+  __m128i, __m256i, _mm_add_epi8, _mm_add_epi16, _mm_and_si128, _mm_andnot_si128, _mm_avg_epu8,
+  _mm_cmpeq_epi16, _mm_cvtsi64_si128, _mm_cvtsi128_si64, _mm_loadu_si128, _mm_max_epi16,
+  _mm_min_epi16, _mm_or_si128, _mm_packus_epi16, _mm_set1_epi8, _mm_setzero_si128,
+  _mm_storeu_si128, _mm_sub_epi8, _mm_sub_epi16, _mm_unpacklo_epi8, _mm_xor_si128, _mm256_add_epi8,
+  _mm256_loadu_si256, _mm256_storeu_si256,
+};
+
+/// Load a pixel of `BPP` bytes (at most 8) in the low bytes of a register
+#[inline]
+#[target_feature(enable = "sse2")]
+fn load<const BPP: usize>(bytes: &[u8]) -> __m128i {
+  let mut buffer: [u8; 8] = [0; 8];
+  buffer[..BPP].copy_from_slice(&bytes[..BPP]);
+  _mm_cvtsi64_si128(i64::from_le_bytes(buffer))
+}
+
+/// Store the low `BPP` bytes of a register
+#[inline]
+#[target_feature(enable = "sse2")]
+fn store<const BPP: usize>(value: __m128i, bytes: &mut [u8]) {
+  let buffer: [u8; 8] = _mm_cvtsi128_si64(value).to_le_bytes();
+  bytes[..BPP].copy_from_slice(&buffer[..BPP]);
+}
+
+#[target_feature(enable = "sse2")]
+pub(super) fn unfilter_sub<const BPP: usize>(current: &mut [u8]) {
+  let mut left: __m128i = _mm_setzero_si128();
+  for pixel in current.chunks_exact_mut(BPP) {
+    left = _mm_add_epi8(load::<BPP>(pixel), left);
+    store::<BPP>(left, pixel);
+  }
+}
+
+#[target_feature(enable = "sse2")]
+pub(super) fn unfilter_up_sse2(current: &mut [u8], previous: &[u8]) {
+  let mut current = current.chunks_exact_mut(16);
+  let mut previous = previous.chunks_exact(16);
+
+  for (row, above) in (&mut current).zip(&mut previous) {
+    // SAFETY: both chunks hold exactly 16 bytes, and unaligned accesses are
+    // allowed
+    unsafe {
+      let sum: __m128i = _mm_add_epi8(
+        _mm_loadu_si128(row.as_ptr().cast()),
+        _mm_loadu_si128(above.as_ptr().cast()),
+      );
+      _mm_storeu_si128(row.as_mut_ptr().cast(), sum);
+    }
+  }
+  super::png_unfilter::unfilter_up(current.into_remainder(), previous.remainder());
+}
+
+#[target_feature(enable = "avx2")]
+pub(super) fn unfilter_up_avx2(current: &mut [u8], previous: &[u8]) {
+  let mut current = current.chunks_exact_mut(32);
+  let mut previous = previous.chunks_exact(32);
+
+  for (row, above) in (&mut current).zip(&mut previous) {
+    // SAFETY: both chunks hold exactly 32 bytes, and unaligned accesses are
+    // allowed
+    unsafe {
+      let sum: __m256i = _mm256_add_epi8(
+        _mm256_loadu_si256(row.as_ptr().cast()),
+        _mm256_loadu_si256(above.as_ptr().cast()),
+      );
+      _mm256_storeu_si256(row.as_mut_ptr().cast(), sum);
+    }
+  }
+  unfilter_up_sse2(current.into_remainder(), previous.remainder());
+}
+
+#[target_feature(enable = "sse2")]
+pub(super) fn unfilter_average<const BPP: usize>(current: &mut [u8], previous: &[u8]) {
+  let ones: __m128i = _mm_set1_epi8(1);
+  let mut left: __m128i = _mm_setzero_si128();
+
+  for (pixel, above) in current
+    .chunks_exact_mut(BPP)
+    .zip(previous.chunks_exact(BPP))
+  {
+    let above: __m128i = load::<BPP>(above);
+
+    // `_mm_avg_epu8` rounds up, so remove the carry of odd sums
+    let odd: __m128i = _mm_and_si128(_mm_xor_si128(left, above), ones);
+    let average: __m128i = _mm_sub_epi8(_mm_avg_epu8(left, above), odd);
+
+    left = _mm_add_epi8(load::<BPP>(pixel), average);
+    store::<BPP>(left, pixel);
+  }
+}
+
+/// Select `then` where the mask is set, and `otherwise` elsewhere
+#[inline]
+#[target_feature(enable = "sse2")]
+fn select(mask: __m128i, then: __m128i, otherwise: __m128i) -> __m128i {
+  _mm_or_si128(_mm_and_si128(mask, then), _mm_andnot_si128(mask, otherwise))
+}
+
+#[inline]
+#[target_feature(enable = "sse2")]
+fn abs_epi16(value: __m128i) -> __m128i {
+  _mm_max_epi16(value, _mm_sub_epi16(_mm_setzero_si128(), value))
+}
+
+#[target_feature(enable = "sse2")]
+pub(super) fn unfilter_paeth<const BPP: usize>(current: &mut [u8], previous: &[u8]) {
+  let zero: __m128i = _mm_setzero_si128();
+
+  // Left and upper left samples, widened to 16 bits
+  let mut a: __m128i = zero;
+  let mut c: __m128i = zero;
+
+  for (pixel, above) in current
+    .chunks_exact_mut(BPP)
+    .zip(previous.chunks_exact(BPP))
+  {
+    let b: __m128i = _mm_unpacklo_epi8(load::<BPP>(above), zero);
+
+    // With p = a + b - c: |p - a| = |b - c|, |p - b| = |a - c|, and
+    // |p - c| = |(b - c) + (a - c)|
+    let pa: __m128i = _mm_sub_epi16(b, c);
+    let pb: __m128i = _mm_sub_epi16(a, c);
+    let pc: __m128i = abs_epi16(_mm_add_epi16(pa, pb));
+    let (pa, pb) = (abs_epi16(pa), abs_epi16(pb));
+
+    let smallest: __m128i = _mm_min_epi16(pc, _mm_min_epi16(pa, pb));
+    let predictor: __m128i = select(
+      _mm_cmpeq_epi16(pa, smallest),
+      a,
+      select(_mm_cmpeq_epi16(pb, smallest), b, c),
+    );
+
+    let value: __m128i = _mm_add_epi8(load::<BPP>(pixel), _mm_packus_epi16(predictor, zero));
+    store::<BPP>(value, pixel);
+
+    a = _mm_unpacklo_epi8(value, zero);
+    c = b;
+  }
+}
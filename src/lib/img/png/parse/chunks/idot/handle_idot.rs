@@ -0,0 +1,113 @@
+use crate::lib::{
+  img::png::parse::{chunks::idot::png_image_division::ImageDivision, values::png_int::PNGInt},
+  util::err::rsm_error::RSMError,
+};
+
+/// Handle the `iDOT` chunk written by Apple, found at the given position in
+/// the datastream of an image of the given height. The chunk is undocumented:
+/// it holds the number of divisions, followed by the first row, the number of
+/// rows and the offset of the first `IDAT` chunk (from the start of the `iDOT`
+/// chunk) of each one.
+pub(crate) fn handle_idot(
+  data: &[u8],
+  chunk_offset: usize,
+  height: u32,
+) -> Result<Vec<ImageDivision>, RSMError> {
+  let (count, entries) = data
+    .split_first_chunk::<4>()
+    .ok_or(RSMError::InvalidLength)?;
+  let count: PNGInt = count[..].try_into()?;
+  if *count == 0 || entries.len() != *count as usize * 12 {
+    return Err(RSMError::InvalidLength);
+  }
+
+  let mut divisions: Vec<ImageDivision> = Vec::with_capacity(*count as usize);
+  let mut next_row: u32 = 0;
+
+  for entry in entries.chunks_exact(12) {
+    let first_row: PNGInt = entry[0..4].try_into()?;
+    let rows: PNGInt = entry[4..8].try_into()?;
+    let offset: PNGInt = entry[8..12].try_into()?;
+
+    // Divisions must cover the image from top to bottom
+    if *first_row != next_row {
+      return Err(RSMError::InvalidContent);
+    }
+    next_row = next_row
+      .checked_add(*rows)
+      .ok_or(RSMError::InvalidContent)?;
+
+    divisions.push(ImageDivision {
+      first_row: *first_row,
+      rows: *rows,
+      offset: chunk_offset + *offset as usize,
+    });
+  }
+
+  match next_row == height {
+    true => Ok(divisions),
+    false => Err(RSMError::InvalidContent),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Encode the data of an `iDOT` chunk from its divisions
+  fn idot(divisions: &[[u32; 3]]) -> Vec<u8> {
+    let mut data: Vec<u8> = (divisions.len() as u32).to_be_bytes().to_vec();
+    data.extend(
+      divisions
+        .iter()
+        .flatten()
+        .flat_map(|value| value.to_be_bytes()),
+    );
+    data
+  }
+
+  #[test]
+  fn test_divisions() {
+    let data: Vec<u8> = idot(&[[0, 50, 40], [50, 51, 16424]]);
+    assert_eq!(
+      handle_idot(&data, 33, 101).unwrap(),
+      vec![
+        ImageDivision {
+          first_row: 0,
+          rows: 50,
+          offset: 73,
+        },
+        ImageDivision {
+          first_row: 50,
+          rows: 51,
+          offset: 16457,
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn test_invalid_divisions() {
+    let gap: Vec<u8> = idot(&[[0, 50, 40], [51, 50, 16424]]);
+    assert!(matches!(
+      handle_idot(&gap, 0, 101),
+      Err(RSMError::InvalidContent)
+    ));
+
+    let short: Vec<u8> = idot(&[[0, 50, 40]]);
+    assert!(matches!(
+      handle_idot(&short, 0, 101),
+      Err(RSMError::InvalidContent)
+    ));
+
+    let truncated: Vec<u8> = idot(&[[0, 101, 40]])[..12].to_vec();
+    assert!(matches!(
+      handle_idot(&truncated, 0, 101),
+      Err(RSMError::InvalidLength)
+    ));
+    assert!(matches!(
+      handle_idot(&idot(&[]), 0, 101),
+      Err(RSMError::InvalidLength)
+    ));
+  }
+}
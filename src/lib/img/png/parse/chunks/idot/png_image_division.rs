@@ -0,0 +1,14 @@
+/// Band of rows whose image data is compressed independently of the rows
+/// above it, as listed in Apple's `iDOT` chunk
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ImageDivision {
+  /// Index of the first row of the band
+  pub first_row: u32,
+
+  /// Number of rows in the band
+  pub rows: u32,
+
+  /// Position in the datastream of the `IDAT` chunk where the compressed
+  /// data of the band starts (at its length field)
+  pub offset: usize,
+}
@@ -10,7 +10,10 @@ pub mod ihdr {
 }
 
 /// `PLTE` - Palette chunk
-pub mod handle_plte;
+pub mod plte {
+  pub mod handle_plte;
+  pub mod png_palette;
+}
 
 /// `acTL` - Animation control chunk
 pub mod actl {
@@ -24,7 +27,9 @@ pub mod handle_bkgd;
 /// `caBX` - Content Credentials
 pub mod cabx {
   pub mod handle_cabx;
+  pub mod png_attribution_assertion;
   pub mod png_attribution_manifest;
+  pub mod png_content_credentials;
 }
 
 /// `cHRM` - Primary chromaticities and white point
@@ -49,6 +54,8 @@ pub mod clli {
 pub mod exif {
   pub mod handle_exif;
   pub mod png_exif;
+  pub mod png_exif_date_time;
+  pub mod png_exif_field;
 }
 
 /// `fcTL` - Frame Control chunk
@@ -69,13 +76,37 @@ pub mod iccp {
   pub mod png_icc_profile;
 }
 
+/// `iDOT` - Apple image divisions chunk
+pub mod idot {
+  pub mod handle_idot;
+  pub mod png_image_division;
+}
+
 /// `IDAT` - Image data chunk
 pub mod idat {
   pub mod handle_idat;
   pub mod png_filters;
+  pub(crate) mod png_image_stream;
+
+  /// Decoding on several threads: restart points of the compressed data are
+  /// inflated in parallel, unfiltering follows inflation band by band, and
+  /// Adam7 passes and rows of the canvas are shared between threads
+  #[cfg(feature = "parallel")]
+  pub(crate) mod png_parallel;
   pub mod png_pixel_data;
+  pub mod png_pixel_format;
   pub mod png_subimage;
   pub mod png_unfilter;
+
+  /// SSE2 and AVX2 unfiltering. The sub, average and paeth filters depend on
+  /// the previous pixel, so they process one pixel at a time with every
+  /// channel in one register. Only the up filter is wide enough for AVX2.
+  #[cfg(target_arch = "x86_64")]
+  pub(crate) mod png_unfilter_x86;
+
+  /// NEON unfiltering, following the SSE2 implementation
+  #[cfg(target_arch = "aarch64")]
+  pub(crate) mod png_unfilter_neon;
 }
 
 /// `mDCV` - Mastering display color volume chunk
@@ -88,6 +119,7 @@ pub mod mdcv {
 pub mod phys {
   pub mod handle_phys;
   pub mod png_physical_dimensions;
+  pub mod png_physical_unit;
 }
 
 /// `sBIT` - Significant bits chunk
@@ -105,17 +137,33 @@ pub mod time {
   pub mod png_time;
 }
 
-/// `tEXt` - Textual data chunk
+/// `tEXt` - Textual data chunk, `iTXt` - International textual data chunk
 pub mod text {
+  pub mod handle_itxt;
   pub mod handle_text;
+  pub mod png_creation_time;
+  pub mod png_keyword;
   pub mod png_text;
+  pub mod png_text_metadata;
 }
 
 /// `tRNS` - Transparency chunk
-pub mod handle_trns;
+pub mod trns {
+  pub mod handle_trns;
+  pub mod png_transparency;
+}
 
 /// `zTXt` - Compressed textual data chunk
 pub mod handle_ztxt;
 
+/// `iTXt` with the `XML:com.adobe.xmp` keyword - Extensible Metadata Platform
+pub mod xmp {
+  pub mod handle_xmp;
+  pub mod png_xmp;
+  pub mod png_xmp_reader;
+  pub mod png_xmp_value;
+  pub mod png_xmp_writer;
+}
+
 /// Utility modules for chunks
 pub mod utils;
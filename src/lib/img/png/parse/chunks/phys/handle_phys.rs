@@ -6,17 +6,13 @@ use crate::lib::{
 };
 
 /// Handle `pHYs` (Physical pixel dimensions) chunk.
-pub(crate) fn handle_phys(data: [u8; 9]) -> Result<Option<PhysicalDimensions>, RSMError> {
+pub(crate) fn handle_phys(data: [u8; 9]) -> Result<PhysicalDimensions, RSMError> {
   let x: PNGInt = data[0..4].try_into()?;
   let y: PNGInt = data[4..8].try_into()?;
 
-  let unit: u8 = data[8];
-  if unit > 1 {
-    return Ok(None);
-  }
-  Ok(Some(PhysicalDimensions {
+  Ok(PhysicalDimensions {
     pp_x: *x,
     pp_y: *y,
-    is_meter: unit == 1,
-  }))
+    unit: data[8].try_into()?,
+  })
 }
@@ -1,6 +1,9 @@
+use crate::lib::{
+  img::png::parse::chunks::phys::png_physical_unit::PhysicalUnit, util::err::rsm_error::RSMError,
+};
+
 /// Physical pixel dimensions (`pHYs`)
-#[allow(unused)]
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub struct PhysicalDimensions {
   /// Pixels per unit on the X axis
   pub pp_x: u32,
@@ -8,6 +11,183 @@ pub struct PhysicalDimensions {
   /// Pixels per unit on the Y axis
   pub pp_y: u32,
 
-  // Determines if the physical dimensions unit is the meter
-  pub is_meter: bool,
+  /// Unit of the dimensions
+  pub unit: PhysicalUnit,
+}
+
+impl PhysicalDimensions {
+  /// Meters in an inch
+  pub const METERS_PER_INCH: f64 = 0.0254;
+
+  /// Create dimensions from a resolution in dots (pixels) per inch. Values are
+  /// rounded to the nearest pixel per meter, so 300 DPI is stored as 11811.
+  pub fn from_dpi(x: f64, y: f64) -> Result<Self, RSMError> {
+    Self::from_pixels_per_meter(x / Self::METERS_PER_INCH, y / Self::METERS_PER_INCH)
+  }
+
+  /// Create dimensions from a resolution in dots (pixels) per centimeter
+  pub fn from_dpcm(x: f64, y: f64) -> Result<Self, RSMError> {
+    Self::from_pixels_per_meter(x * 100.0, y * 100.0)
+  }
+
+  /// Create dimensions only describing the aspect ratio of pixels, as their
+  /// width divided by their height
+  pub fn from_aspect_ratio(ratio: f64) -> Result<Self, RSMError> {
+    if !ratio.is_finite() || ratio <= 0.0 {
+      return Err(RSMError::OutOfBounds);
+    }
+
+    // Store the smaller count as a million, which keeps six decimals of the ratio
+    const BASE: f64 = 1_000_000.0;
+    let (pp_x, pp_y) = match ratio >= 1.0 {
+      true => (scale(BASE)?, scale(BASE * ratio)?),
+      false => (scale(BASE / ratio)?, scale(BASE)?),
+    };
+    Ok(Self {
+      pp_x,
+      pp_y,
+      unit: PhysicalUnit::Unknown,
+    })
+  }
+
+  fn from_pixels_per_meter(x: f64, y: f64) -> Result<Self, RSMError> {
+    Ok(Self {
+      pp_x: scale(x)?,
+      pp_y: scale(y)?,
+      unit: PhysicalUnit::Meter,
+    })
+  }
+
+  /// Resolution in dots (pixels) per inch on the X and Y axes, which is `None`
+  /// when the unit is unknown
+  pub fn dpi(&self) -> Option<(f64, f64)> {
+    self
+      .pixels_per_meter()
+      .map(|(x, y)| (x * Self::METERS_PER_INCH, y * Self::METERS_PER_INCH))
+  }
+
+  /// Resolution in dots (pixels) per centimeter on the X and Y axes, which is
+  /// `None` when the unit is unknown
+  pub fn dpcm(&self) -> Option<(f64, f64)> {
+    self.pixels_per_meter().map(|(x, y)| (x / 100.0, y / 100.0))
+  }
+
+  /// Set the resolution in dots (pixels) per inch
+  pub fn set_dpi(&mut self, x: f64, y: f64) -> Result<(), RSMError> {
+    *self = Self::from_dpi(x, y)?;
+    Ok(())
+  }
+
+  /// Set the resolution in dots (pixels) per centimeter
+  pub fn set_dpcm(&mut self, x: f64, y: f64) -> Result<(), RSMError> {
+    *self = Self::from_dpcm(x, y)?;
+    Ok(())
+  }
+
+  fn pixels_per_meter(&self) -> Option<(f64, f64)> {
+    match self.unit {
+      PhysicalUnit::Meter => Some((self.pp_x as f64, self.pp_y as f64)),
+      PhysicalUnit::Unknown => None,
+    }
+  }
+
+  /// Aspect ratio of pixels, as their width divided by their height. A ratio
+  /// above 1 means pixels are wider than they are tall. `None` when a count is
+  /// zero.
+  pub fn aspect_ratio(&self) -> Option<f64> {
+    match (self.pp_x, self.pp_y) {
+      (0, _) | (_, 0) => None,
+      (x, y) => Some(y as f64 / x as f64),
+    }
+  }
+
+  /// Determine if pixels are square
+  pub fn is_square(&self) -> bool {
+    self.pp_x == self.pp_y
+  }
+
+  /// Physical size of an image, in millimeters, which is `None` when the unit
+  /// is unknown or a count is zero
+  pub fn size_mm(&self, width: u32, height: u32) -> Option<(f64, f64)> {
+    let (x, y) = self
+      .pixels_per_meter()
+      .filter(|&(x, y)| x > 0.0 && y > 0.0)?;
+    Some((width as f64 / x * 1000.0, height as f64 / y * 1000.0))
+  }
+
+  /// Physical size of an image, in inches, which is `None` when the unit is
+  /// unknown or a count is zero
+  pub fn size_inches(&self, width: u32, height: u32) -> Option<(f64, f64)> {
+    let inches = |mm: f64| mm / 1000.0 / Self::METERS_PER_INCH;
+    self
+      .size_mm(width, height)
+      .map(|(width, height)| (inches(width), inches(height)))
+  }
+
+  /// Encode the dimensions as the data of a `pHYs` chunk
+  pub fn to_bytes(&self) -> [u8; 9] {
+    let mut data: [u8; 9] = [0; 9];
+    data[0..4].copy_from_slice(&self.pp_x.to_be_bytes());
+    data[4..8].copy_from_slice(&self.pp_y.to_be_bytes());
+    data[8] = self.unit as u8;
+    data
+  }
+}
+
+/// Round a number of pixels per unit, which must fit in a PNG four-byte
+/// unsigned integer
+fn scale(value: f64) -> Result<u32, RSMError> {
+  let rounded: f64 = value.round();
+  match rounded.is_finite() && rounded >= 1.0 && rounded <= i32::MAX as f64 {
+    true => Ok(rounded as u32),
+    false => Err(RSMError::OutOfBounds),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_dpi() {
+    let dimensions: PhysicalDimensions = PhysicalDimensions::from_dpi(300.0, 72.0).unwrap();
+    assert_eq!((dimensions.pp_x, dimensions.pp_y), (11811, 2835));
+
+    let (x, y) = dimensions.dpi().unwrap();
+    assert_eq!((x.round(), y.round()), (300.0, 72.0));
+
+    let (x, y) = dimensions.dpcm().unwrap();
+    assert_eq!((x, y), (118.11, 28.35));
+    assert!(PhysicalDimensions::from_dpi(0.0, 72.0).is_err());
+    assert!(PhysicalDimensions::from_dpi(f64::NAN, 72.0).is_err());
+  }
+
+  #[test]
+  fn test_aspect_ratio() {
+    let wide: PhysicalDimensions = PhysicalDimensions::from_aspect_ratio(2.0).unwrap();
+    assert_eq!(wide.unit, PhysicalUnit::Unknown);
+    assert_eq!(wide.aspect_ratio(), Some(2.0));
+    assert!(wide.dpi().is_none());
+    assert!(wide.size_mm(10, 10).is_none());
+
+    let tall: PhysicalDimensions = PhysicalDimensions::from_dpcm(100.0, 50.0).unwrap();
+    assert_eq!(tall.aspect_ratio(), Some(0.5));
+    assert!(!tall.is_square());
+  }
+
+  #[test]
+  fn test_physical_size() {
+    let dimensions: PhysicalDimensions = PhysicalDimensions::from_dpcm(10.0, 20.0).unwrap();
+    assert_eq!(dimensions.size_mm(100, 100), Some((100.0, 50.0)));
+
+    let dimensions: PhysicalDimensions = PhysicalDimensions::from_dpi(300.0, 300.0).unwrap();
+    let (width, height) = dimensions.size_inches(2550, 3300).unwrap();
+    assert!((width - 8.5).abs() < 1e-3 && (height - 11.0).abs() < 1e-3);
+  }
+
+  #[test]
+  fn test_to_bytes() {
+    let dimensions: PhysicalDimensions = PhysicalDimensions::from_dpi(300.0, 300.0).unwrap();
+    assert_eq!(dimensions.to_bytes(), [0, 0, 46, 35, 0, 0, 46, 35, 1]);
+  }
 }
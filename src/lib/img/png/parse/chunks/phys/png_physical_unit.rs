@@ -0,0 +1,11 @@
+use crate::define_png_enum;
+
+define_png_enum! {
+  /// Defines the unit of the `pHYs` pixel dimensions. With an unknown unit,
+  /// only the aspect ratio of pixels is known.
+  #[derive(Debug, PartialEq, Clone, Copy)]
+  pub enum PhysicalUnit {
+    Unknown = 0,
+    Meter = 1
+  }
+}
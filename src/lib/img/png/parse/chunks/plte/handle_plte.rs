@@ -1,15 +1,17 @@
-use crate::lib::util::err::rsm_error::RSMError;
+use crate::lib::{
+  img::png::parse::chunks::plte::png_palette::Palette, util::err::rsm_error::RSMError,
+};
 
 /// Handle `PLTE` (Palette) chunk
-pub(crate) fn handle_plte(data: &[u8]) -> Result<Vec<[u8; 3]>, RSMError> {
-  if !data.len().is_multiple_of(3) || data.len() > 768 || data.is_empty() {
+pub(crate) fn handle_plte(data: &[u8]) -> Result<Palette, RSMError> {
+  if !data.len().is_multiple_of(3) {
     return Err(RSMError::InvalidLength);
   }
-  let palette: Vec<[u8; 3]> = data
+  let colors: Vec<[u8; 3]> = data
     .chunks_exact(3)
     .map(|triple| [triple[0], triple[1], triple[2]])
     .collect();
-  Ok(palette)
+  Palette::new(colors)
 }
 
 #[cfg(test)]
@@ -37,7 +39,7 @@ mod tests {
   proptest! {
     #[test]
     fn test_plte_invalid_lengths(data in filter_above_768()) {
-      let res: Result<Vec<[u8; 3]>, RSMError> = handle_plte(&data);
+      let res: Result<Palette, RSMError> = handle_plte(&data);
       assert!(res.is_err());
     }
   }
@@ -0,0 +1,95 @@
+use crate::lib::{
+  img::png::parse::chunks::trns::png_transparency::Transparency, util::err::rsm_error::RSMError,
+};
+
+/// Palette from the `PLTE` chunk, holding from 1 to 256 RGB colors
+#[derive(Debug, PartialEq, Clone)]
+pub struct Palette {
+  colors: Vec<[u8; 3]>,
+}
+
+impl Palette {
+  /// Maximum number of entries in a palette
+  pub const MAX_ENTRIES: usize = 256;
+
+  /// Create a palette, checking it holds from 1 to 256 colors
+  pub fn new(colors: Vec<[u8; 3]>) -> Result<Self, RSMError> {
+    if colors.is_empty() || colors.len() > Self::MAX_ENTRIES {
+      return Err(RSMError::InvalidLength);
+    }
+    Ok(Self { colors })
+  }
+
+  pub fn colors(&self) -> &[[u8; 3]] {
+    &self.colors
+  }
+
+  pub fn len(&self) -> usize {
+    self.colors.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.colors.is_empty()
+  }
+
+  /// Check alpha values from a `tRNS` chunk can apply to the palette, which
+  /// requires at most one value per entry
+  pub fn validate_alpha(&self, alpha: &[u8]) -> Result<(), RSMError> {
+    match alpha.len() <= self.len() {
+      true => Ok(()),
+      false => Err(RSMError::InvalidLength),
+    }
+  }
+
+  /// Obtain the RGBA value of an entry. Entries without an alpha value in the
+  /// transparency are opaque.
+  pub fn rgba(&self, index: u8, transparency: Option<&Transparency>) -> Option<[u8; 4]> {
+    let [r, g, b] = *self.colors.get(index as usize)?;
+    let a: u8 = match transparency {
+      Some(Transparency::PaletteAlpha(alpha)) => alpha.get(index as usize).copied().unwrap_or(255),
+      _ => 255,
+    };
+    Some([r, g, b, a])
+  }
+
+  /// Obtain the RGBA values of every entry
+  pub fn to_rgba(&self, transparency: Option<&Transparency>) -> Vec<[u8; 4]> {
+    (0..self.len())
+      .filter_map(|index| self.rgba(index as u8, transparency))
+      .collect()
+  }
+
+  /// Encode the palette as the data of a `PLTE` chunk
+  pub fn to_bytes(&self) -> Vec<u8> {
+    self.colors.concat()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_palette_lengths() {
+    assert!(Palette::new(Vec::new()).is_err());
+    assert!(Palette::new(vec![[0; 3]; 257]).is_err());
+
+    let palette: Palette = Palette::new(vec![[0; 3]; 2]).unwrap();
+    assert!(palette.validate_alpha(&[0, 0]).is_ok());
+    assert!(palette.validate_alpha(&[0, 0, 0]).is_err());
+  }
+
+  #[test]
+  fn test_rgba_entries() {
+    let palette: Palette = Palette::new(vec![[255, 0, 0], [0, 255, 0], [0, 0, 255]]).unwrap();
+    let transparency: Transparency = Transparency::PaletteAlpha(vec![0, 128]);
+
+    assert_eq!(
+      palette.to_rgba(Some(&transparency)),
+      [[255, 0, 0, 0], [0, 255, 0, 128], [0, 0, 255, 255]]
+    );
+    assert_eq!(palette.rgba(2, None), Some([0, 0, 255, 255]));
+    assert_eq!(palette.rgba(3, None), None);
+    assert_eq!(palette.to_bytes(), [255, 0, 0, 0, 255, 0, 0, 0, 255]);
+  }
+}
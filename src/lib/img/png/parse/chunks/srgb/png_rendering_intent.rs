@@ -2,7 +2,7 @@ use crate::define_png_enum;
 
 define_png_enum! {
   /// Defines the `sRGB` rendering intent
-  #[derive(Debug, PartialEq)]
+  #[derive(Debug, PartialEq, Clone, Copy)]
   pub enum RenderingIntent {
     Perceptual = 0,
     RelativeColorimetric = 1,
@@ -0,0 +1,56 @@
+use crate::lib::{
+  img::png::parse::{
+    chunks::{
+      ihdr::png_compression_method::CompressionMethod,
+      text::{png_keyword::Keyword, png_text::Text},
+      utils::{inflate, read_text},
+    },
+    png_limits::Limits,
+  },
+  util::err::rsm_error::RSMError,
+};
+
+/// Handle `iTXt` (International textual data) chunk
+pub(in super::super::super) fn handle_itxt(data: &[u8], limits: &Limits) -> Result<Text, RSMError> {
+  let mut parts = data.splitn(2, |&n| n == 0);
+
+  let keyword: Keyword = Keyword::from_bytes(parts.next().unwrap())?;
+
+  let rest: &[u8] = parts.next().ok_or(RSMError::InvalidContent)?;
+  let [flag, method, rest @ ..] = rest else {
+    return Err(RSMError::NotEnoughContent);
+  };
+
+  let mut parts = rest.splitn(3, |&n| n == 0);
+  let language: &[u8] = parts.next().unwrap();
+  let translated_keyword: &[u8] = parts.next().ok_or(RSMError::InvalidContent)?;
+  let text: &[u8] = parts.next().ok_or(RSMError::InvalidContent)?;
+
+  let compressed: bool = match flag {
+    0 => false,
+    1 => true,
+    _ => return Err(RSMError::InvalidContent),
+  };
+
+  let text: String = if compressed {
+    let method: CompressionMethod = (*method).try_into()?;
+    if method != CompressionMethod::Deflate {
+      return Err(RSMError::InvalidContent);
+    }
+    let buffer: Vec<u8> = inflate(text, limits.max_metadata_size)?;
+    String::from_utf8(buffer).map_err(|_| RSMError::InvalidContent)?
+  } else {
+    String::from_utf8(text.to_vec()).map_err(|_| RSMError::InvalidContent)?
+  };
+
+  let text: Text = Text::InternationalText {
+    keyword,
+    language: read_text(language)?,
+    translated_keyword: String::from_utf8(translated_keyword.to_vec())
+      .map_err(|_| RSMError::InvalidContent)?,
+    text,
+    compressed,
+  };
+  text.validate()?;
+  Ok(text)
+}
@@ -1,5 +1,8 @@
 use crate::lib::{
-  img::png::parse::chunks::{text::png_text::Text, utils::read_text},
+  img::png::parse::chunks::{
+    text::{png_keyword::Keyword, png_text::Text},
+    utils::read_text,
+  },
   util::err::rsm_error::RSMError,
 };
 
@@ -7,19 +10,10 @@ use crate::lib::{
 pub(in super::super::super) fn handle_text(data: &[u8]) -> Result<Text, RSMError> {
   let mut parts = data.splitn(2, |&n| n == 0);
 
-  let keyword: &[u8] = parts.next().unwrap();
-  if keyword.len() > 79 {
-    return Err(RSMError::InvalidLength);
-  }
-  let keyword_str: String = read_text(keyword)?;
+  let keyword: Keyword = Keyword::from_bytes(parts.next().unwrap())?;
+  let text: &[u8] = parts.next().ok_or(RSMError::InvalidContent)?;
 
-  if let Some(text) = parts.next() {
-    if text.contains(&0) {
-      return Err(RSMError::InvalidContent);
-    }
-    let text_content: String = read_text(text)?;
-    Ok(Text::Text(keyword_str, text_content))
-  } else {
-    Err(RSMError::InvalidContent)
-  }
+  let text: Text = Text::Text(keyword, read_text(text)?);
+  text.validate()?;
+  Ok(text)
 }
@@ -0,0 +1,303 @@
+use crate::lib::util::err::rsm_error::RSMError;
+use std::fmt::{Display, Formatter, Result};
+
+const DAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+const MONTHS: [&str; 12] = [
+  "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Time of original image creation from the `Creation Time` text keyword,
+/// written as a RFC 1123 or ISO 8601 date
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct CreationTime {
+  pub year: u16,
+  pub month: u8,
+  pub day: u8,
+  pub hour: u8,
+  pub minute: u8,
+  pub second: u8,
+
+  /// Offset from UTC, in minutes. Unknown for ISO 8601 dates without a time
+  /// zone, which are local to where they were recorded.
+  pub offset: Option<i16>,
+}
+
+impl CreationTime {
+  /// Day of the week, from 0 (Sunday) to 6 (Saturday), if the time exists
+  pub fn weekday(&self) -> Option<u8> {
+    const SHIFTS: [u32; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+    if !self.is_valid() {
+      return None;
+    }
+    // Weekdays repeat every 400 years, which keeps the year positive
+    let year: u32 = self.year as u32 + 400 - (self.month < 3) as u32;
+    let days: u32 = year + year / 4 - year / 100 + year / 400;
+    Some(((days + SHIFTS[self.month as usize - 1] + self.day as u32) % 7) as u8)
+  }
+
+  /// Parse a RFC 1123 date (e.g. `Mon, 19 Oct 2026 14:30:00 GMT`)
+  fn from_rfc_1123(text: &str) -> Option<Self> {
+    let text: &str = text.split_once(',').map_or(text, |(_, date)| date);
+    let parts: Vec<&str> = text.split_whitespace().collect();
+    let [day, month, year, time, rest @ ..] = parts.as_slice() else {
+      return None;
+    };
+
+    let month: usize = MONTHS.iter().position(|m| m.eq_ignore_ascii_case(month))?;
+    let year: u16 = match (year.len(), year.parse::<u16>().ok()?) {
+      (2, short @ 0..50) => 2000 + short,
+      (2, short) => 1900 + short,
+      (_, full) => full,
+    };
+
+    let mut time = time.split(':');
+    let hour: u8 = time.next()?.parse().ok()?;
+    let minute: u8 = time.next()?.parse().ok()?;
+    let second: u8 = time.next().map_or(Some(0), |s| s.parse().ok())?;
+
+    let offset: i16 = match rest {
+      [] => 0,
+      [zone] => match zone.to_ascii_uppercase().as_str() {
+        "GMT" | "UT" | "UTC" | "Z" => 0,
+        "EDT" => -4 * 60,
+        "EST" | "CDT" => -5 * 60,
+        "CST" | "MDT" => -6 * 60,
+        "MST" | "PDT" => -7 * 60,
+        "PST" => -8 * 60,
+        zone => parse_offset(zone)?,
+      },
+      _ => return None,
+    };
+
+    Some(Self {
+      year,
+      month: month as u8 + 1,
+      day: day.parse().ok()?,
+      hour,
+      minute,
+      second,
+      offset: Some(offset),
+    })
+  }
+
+  /// Parse an ISO 8601 date (e.g. `2026-10-19T14:30:00+02:00` or `2026-10-19`)
+  fn from_iso_8601(text: &str) -> Option<Self> {
+    let date: &str = text.get(..10)?;
+    let time: &str = text.get(10..)?;
+
+    let mut parts = date.split('-');
+    let year: u16 = parts.next().filter(|y| y.len() == 4)?.parse().ok()?;
+    let month: u8 = parts.next().filter(|m| m.len() == 2)?.parse().ok()?;
+    let day: u8 = parts.next().filter(|d| d.len() == 2)?.parse().ok()?;
+
+    let mut creation_time: Self = Self {
+      year,
+      month,
+      day,
+      hour: 0,
+      minute: 0,
+      second: 0,
+      offset: None,
+    };
+    if time.is_empty() {
+      return Some(creation_time);
+    }
+
+    let time: &str = time.strip_prefix(['T', 't', ' '])?;
+    let split: usize = time.find(['Z', 'z', '+', '-']).unwrap_or(time.len());
+    let (time, zone) = time.split_at(split);
+
+    let mut parts = time.split(':');
+    creation_time.hour = parts.next().filter(|h| h.len() == 2)?.parse().ok()?;
+    creation_time.minute = parts.next().filter(|m| m.len() == 2)?.parse().ok()?;
+    if let Some(second) = parts.next() {
+      let second: &str = second.split_once(['.', ',']).map_or(second, |(s, _)| s);
+      creation_time.second = second.parse().ok()?;
+    }
+    if parts.next().is_some() {
+      return None;
+    }
+
+    creation_time.offset = match zone {
+      "" => None,
+      "Z" | "z" => Some(0),
+      zone => Some(parse_offset(&zone.replace(':', ""))?),
+    };
+    Some(creation_time)
+  }
+
+  fn is_valid(&self) -> bool {
+    let leap: bool = self.year.is_multiple_of(4)
+      && (!self.year.is_multiple_of(100) || self.year.is_multiple_of(400));
+    let days: u8 = match self.month {
+      2 if leap => 29,
+      2 => 28,
+      4 | 6 | 9 | 11 => 30,
+      _ => 31,
+    };
+
+    (1..=12).contains(&self.month)
+      && (1..=days).contains(&self.day)
+      && self.hour < 24
+      && self.minute < 60
+      && self.second <= 60
+      && self.offset.is_none_or(|offset| offset.abs() < 24 * 60)
+  }
+}
+
+/// Parse a time zone offset written as `+hhmm`, `-hhmm` or `+hh`
+fn parse_offset(zone: &str) -> Option<i16> {
+  let sign: i16 = match zone.as_bytes().first()? {
+    b'+' => 1,
+    b'-' => -1,
+    _ => return None,
+  };
+  let digits: &str = &zone[1..];
+  if !matches!(digits.len(), 2 | 4) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+    return None;
+  }
+
+  let hours: i16 = digits[..2].parse().ok()?;
+  let minutes: i16 = digits.get(2..).map_or(Some(0), |m| m.parse().ok())?;
+  if minutes >= 60 {
+    return None;
+  }
+  Some(sign * (hours * 60 + minutes))
+}
+
+impl TryFrom<&str> for CreationTime {
+  type Error = RSMError;
+
+  fn try_from(text: &str) -> std::result::Result<Self, Self::Error> {
+    let text: &str = text.trim();
+    Self::from_iso_8601(text)
+      .or_else(|| Self::from_rfc_1123(text))
+      .filter(Self::is_valid)
+      .ok_or(RSMError::InvalidContent)
+  }
+}
+
+/// Write the time as a RFC 1123 date, or as an ISO 8601 date when its time
+/// zone is unknown or it does not exist
+impl Display for CreationTime {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    let (Some(offset), Some(weekday)) = (self.offset, self.weekday()) else {
+      write!(
+        f,
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        self.year, self.month, self.day, self.hour, self.minute, self.second
+      )?;
+      return match self.offset {
+        None => Ok(()),
+        Some(0) => write!(f, "Z"),
+        Some(offset) => {
+          let sign: char = if offset < 0 { '-' } else { '+' };
+          write!(f, "{sign}{:02}:{:02}", offset.abs() / 60, offset.abs() % 60)
+        }
+      };
+    };
+
+    write!(
+      f,
+      "{}, {:02} {} {:04} {:02}:{:02}:{:02} ",
+      DAYS[weekday as usize],
+      self.day,
+      MONTHS[self.month as usize - 1],
+      self.year,
+      self.hour,
+      self.minute,
+      self.second
+    )?;
+    match offset {
+      0 => write!(f, "GMT"),
+      offset => {
+        let sign: char = if offset < 0 { '-' } else { '+' };
+        write!(f, "{sign}{:02}{:02}", offset.abs() / 60, offset.abs() % 60)
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn time(date: (u16, u8, u8), time: (u8, u8, u8), offset: Option<i16>) -> CreationTime {
+    CreationTime {
+      year: date.0,
+      month: date.1,
+      day: date.2,
+      hour: time.0,
+      minute: time.1,
+      second: time.2,
+      offset,
+    }
+  }
+
+  #[test]
+  fn test_rfc_1123() {
+    let expected: CreationTime = time((2026, 10, 19), (14, 30, 5), Some(0));
+    for text in [
+      "Mon, 19 Oct 2026 14:30:05 GMT",
+      "19 oct 2026 14:30:05 UT",
+      "Mon, 19 Oct 26 14:30:05 +0000",
+    ] {
+      assert_eq!(CreationTime::try_from(text).unwrap(), expected);
+    }
+
+    let pacific: CreationTime = CreationTime::try_from("Sun, 29 Feb 2004 08:00 PST").unwrap();
+    assert_eq!(pacific, time((2004, 2, 29), (8, 0, 0), Some(-480)));
+    assert_eq!(pacific.to_string(), "Sun, 29 Feb 2004 08:00:00 -0800");
+  }
+
+  #[test]
+  fn test_iso_8601() {
+    assert_eq!(
+      CreationTime::try_from("2026-10-19T14:30:05.250+02:00").unwrap(),
+      time((2026, 10, 19), (14, 30, 5), Some(120))
+    );
+    assert_eq!(
+      CreationTime::try_from("2026-10-19 14:30Z").unwrap(),
+      time((2026, 10, 19), (14, 30, 0), Some(0))
+    );
+
+    let local: CreationTime = CreationTime::try_from("2026-10-19").unwrap();
+    assert_eq!(local, time((2026, 10, 19), (0, 0, 0), None));
+    assert_eq!(local.to_string(), "2026-10-19T00:00:00");
+  }
+
+  #[test]
+  fn test_invalid_times() {
+    for text in [
+      "",
+      "yesterday",
+      "2026-02-29",
+      "2026-13-01",
+      "2026-10-19T24:00",
+      "2026-10-19T10:00+2",
+      "2026-10-19T10:00+02:60",
+      "Mon, 19 Oct 2026 10:00 -0075",
+      "Mon, 31 Apr 2026 10:00 GMT",
+      "Mon, 19 Oct 2026 10:00 GMT extra",
+    ] {
+      assert!(CreationTime::try_from(text).is_err(), "{text}");
+    }
+  }
+
+  #[test]
+  fn test_display_round_trip() {
+    let expected: CreationTime = time((2000, 1, 1), (23, 59, 60), Some(-330));
+    let text: String = expected.to_string();
+    assert_eq!(text, "Sat, 01 Jan 2000 23:59:60 -0530");
+    assert_eq!(CreationTime::try_from(text.as_str()).unwrap(), expected);
+  }
+
+  #[test]
+  fn test_nonexistent_time() {
+    // Times built by hand may not exist, and have no weekday
+    let invalid: CreationTime = time((2026, 13, 40), (10, 0, 0), Some(90));
+    assert_eq!(invalid.weekday(), None);
+    assert_eq!(invalid.to_string(), "2026-13-40T10:00:00+01:30");
+    assert_eq!(time((2026, 10, 19), (0, 0, 0), None).weekday(), Some(1));
+  }
+}
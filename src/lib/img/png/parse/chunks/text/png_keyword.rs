@@ -0,0 +1,168 @@
+use crate::lib::{img::png::parse::chunks::utils::write_text, util::err::rsm_error::RSMError};
+use std::{
+  fmt::{Display, Formatter, Result},
+  ops::Deref,
+};
+
+/// Keyword of a text chunk, holding from 1 to 79 printable Latin-1 characters
+/// without leading, trailing or consecutive spaces
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Keyword(String);
+
+impl Keyword {
+  /// Maximum length of a keyword, in bytes
+  pub const MAX_LENGTH: usize = 79;
+
+  /// Short title or caption of the image
+  pub const TITLE: &str = "Title";
+  /// Name of the author of the image
+  pub const AUTHOR: &str = "Author";
+  /// Description of the image, possibly long
+  pub const DESCRIPTION: &str = "Description";
+  /// Copyright notice
+  pub const COPYRIGHT: &str = "Copyright";
+  /// Time of original image creation
+  pub const CREATION_TIME: &str = "Creation Time";
+  /// Software used to create the image
+  pub const SOFTWARE: &str = "Software";
+  /// Legal disclaimer
+  pub const DISCLAIMER: &str = "Disclaimer";
+  /// Warning of the nature of the content
+  pub const WARNING: &str = "Warning";
+  /// Device used to create the image
+  pub const SOURCE: &str = "Source";
+  /// Miscellaneous comment
+  pub const COMMENT: &str = "Comment";
+
+  /// Keywords registered by the PNG specification
+  pub const REGISTERED: [&str; 10] = [
+    Self::TITLE,
+    Self::AUTHOR,
+    Self::DESCRIPTION,
+    Self::COPYRIGHT,
+    Self::CREATION_TIME,
+    Self::SOFTWARE,
+    Self::DISCLAIMER,
+    Self::WARNING,
+    Self::SOURCE,
+    Self::COMMENT,
+  ];
+
+  /// Create a keyword, checking it follows the rules of the PNG specification
+  pub fn new(keyword: &str) -> std::result::Result<Self, RSMError> {
+    let length: usize = keyword.chars().count();
+    if length == 0 || length > Self::MAX_LENGTH {
+      return Err(RSMError::InvalidLength);
+    }
+
+    let printable = |c: char| matches!(c as u32, 0x20..=0x7e | 0xa1..=0xff);
+    if !keyword.chars().all(printable)
+      || keyword.starts_with(' ')
+      || keyword.ends_with(' ')
+      || keyword.contains("  ")
+    {
+      return Err(RSMError::InvalidContent);
+    }
+    Ok(Self(keyword.to_string()))
+  }
+
+  /// Read a keyword from Latin-1 bytes
+  pub fn from_bytes(bytes: &[u8]) -> std::result::Result<Self, RSMError> {
+    let keyword: String = bytes.iter().map(|&b| b as char).collect();
+    Self::new(&keyword)
+  }
+
+  /// Encode the keyword as Latin-1 bytes
+  pub fn to_bytes(&self) -> Vec<u8> {
+    write_text(&self.0).expect("keywords only hold Latin-1 characters")
+  }
+
+  pub fn as_str(&self) -> &str {
+    &self.0
+  }
+}
+
+impl TryFrom<&str> for Keyword {
+  type Error = RSMError;
+
+  fn try_from(keyword: &str) -> std::result::Result<Self, Self::Error> {
+    Self::new(keyword)
+  }
+}
+
+impl Deref for Keyword {
+  type Target = str;
+
+  fn deref(&self) -> &str {
+    &self.0
+  }
+}
+
+impl PartialEq<str> for Keyword {
+  fn eq(&self, other: &str) -> bool {
+    self.0 == other
+  }
+}
+
+impl PartialEq<&str> for Keyword {
+  fn eq(&self, other: &&str) -> bool {
+    self.0 == *other
+  }
+}
+
+impl Display for Keyword {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    write!(f, "{}", self.0)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use proptest::{prop_assert, proptest};
+
+  #[test]
+  fn test_valid_keywords() {
+    for keyword in Keyword::REGISTERED
+      .iter()
+      .chain(&["Légende", "a", &"k".repeat(79)])
+    {
+      let parsed: Keyword = Keyword::new(keyword).unwrap();
+      assert_eq!(Keyword::from_bytes(&parsed.to_bytes()).unwrap(), parsed);
+    }
+  }
+
+  #[test]
+  fn test_invalid_keywords() {
+    for keyword in ["", &"k".repeat(80)] {
+      assert!(matches!(
+        Keyword::new(keyword),
+        Err(RSMError::InvalidLength)
+      ));
+    }
+    for keyword in [
+      " Title",
+      "Title ",
+      "Creation  Time",
+      "Tab\tulation",
+      "Line\nbreak",
+      "Non\u{a0}breaking",
+      "Ω",
+    ] {
+      assert!(matches!(
+        Keyword::new(keyword),
+        Err(RSMError::InvalidContent)
+      ));
+    }
+  }
+
+  proptest! {
+    /// Test keywords read from bytes are written back to the same bytes
+    #[test]
+    fn test_keyword_bytes(bytes in proptest::collection::vec(0u8..=255, 0..100)) {
+      if let Ok(keyword) = Keyword::from_bytes(&bytes) {
+        prop_assert!(keyword.to_bytes() == bytes);
+      }
+    }
+  }
+}
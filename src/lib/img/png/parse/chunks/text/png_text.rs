@@ -1,9 +1,216 @@
+use crate::lib::{
+  img::png::{
+    chunk::png_chunk_type::ChunkType,
+    parse::chunks::{
+      text::png_keyword::Keyword,
+      utils::{deflate, write_text},
+    },
+  },
+  util::err::rsm_error::RSMError,
+};
+
 /// Represents PNG text and its different forms
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Text {
   /// Text obtained from the `tEXt` (Textual data) chunk
-  Text(String, String),
+  Text(Keyword, String),
 
   /// Text obtained from the `zTXt` (Compressed textual data) chunk
-  CompressedText(String, String),
+  CompressedText(Keyword, String),
+
+  /// Text obtained from the `iTXt` (International textual data) chunk
+  InternationalText {
+    keyword: Keyword,
+
+    /// Language of the text, as a RFC 1766 language tag
+    language: String,
+
+    /// Translation of the keyword in the language of the text
+    translated_keyword: String,
+    text: String,
+
+    /// Determines if the text was compressed
+    compressed: bool,
+  },
+}
+
+impl Text {
+  /// Length of text, in bytes, above which it is compressed when choosing the
+  /// chunk to store it
+  pub const COMPRESSION_THRESHOLD: usize = 1024;
+
+  /// Create text stored in a `tEXt` chunk, which only holds Latin-1 characters
+  pub fn new(keyword: &str, text: &str) -> Result<Self, RSMError> {
+    let text: Self = Self::Text(Keyword::new(keyword)?, text.to_string());
+    text.validate()?;
+    Ok(text)
+  }
+
+  /// Create text stored in a `zTXt` chunk, which only holds Latin-1 characters
+  pub fn compressed(keyword: &str, text: &str) -> Result<Self, RSMError> {
+    let text: Self = Self::CompressedText(Keyword::new(keyword)?, text.to_string());
+    text.validate()?;
+    Ok(text)
+  }
+
+  /// Create text stored in an `iTXt` chunk, which holds any Unicode character
+  pub fn international(
+    keyword: &str,
+    language: &str,
+    translated_keyword: &str,
+    text: &str,
+    compressed: bool,
+  ) -> Result<Self, RSMError> {
+    let text: Self = Self::InternationalText {
+      keyword: Keyword::new(keyword)?,
+      language: language.to_string(),
+      translated_keyword: translated_keyword.to_string(),
+      text: text.to_string(),
+      compressed,
+    };
+    text.validate()?;
+    Ok(text)
+  }
+
+  /// Create text stored in the most suitable chunk: `tEXt` for short Latin-1
+  /// text, `zTXt` for longer Latin-1 text, and `iTXt` for any other text
+  pub fn best_fit(keyword: &str, text: &str) -> Result<Self, RSMError> {
+    let latin_1: bool = text.chars().all(|c| c as u32 <= 0xff);
+    let compressed: bool = text.len() > Self::COMPRESSION_THRESHOLD;
+
+    match (latin_1, compressed) {
+      (true, false) => Self::new(keyword, text),
+      (true, true) => Self::compressed(keyword, text),
+      (false, compressed) => Self::international(keyword, "", "", text, compressed),
+    }
+  }
+
+  pub fn keyword(&self) -> &Keyword {
+    match self {
+      Self::Text(keyword, _) | Self::CompressedText(keyword, _) => keyword,
+      Self::InternationalText { keyword, .. } => keyword,
+    }
+  }
+
+  pub fn text(&self) -> &str {
+    match self {
+      Self::Text(_, text) | Self::CompressedText(_, text) => text,
+      Self::InternationalText { text, .. } => text,
+    }
+  }
+
+  /// Check the text follows the rules of the PNG specification: no control
+  /// characters other than line feeds, only Latin-1 characters in `tEXt` and
+  /// `zTXt` chunks, and a language tag made of ASCII letters, digits and
+  /// hyphens.
+  pub fn validate(&self) -> Result<(), RSMError> {
+    let control = |c: char| c.is_control() && c != '\n';
+
+    match self {
+      Self::Text(_, text) | Self::CompressedText(_, text) => {
+        if text.chars().any(|c| control(c) || c as u32 > 0xff) {
+          return Err(RSMError::InvalidContent);
+        }
+      }
+      Self::InternationalText {
+        language,
+        translated_keyword,
+        text,
+        ..
+      } => {
+        let tag = |c: char| c.is_ascii_alphanumeric() || c == '-';
+        if !language.chars().all(tag)
+          || translated_keyword.chars().any(control)
+          || text.chars().any(control)
+        {
+          return Err(RSMError::InvalidContent);
+        }
+      }
+    }
+    Ok(())
+  }
+
+  /// Encode the text as the type and data of its chunk, compressing at a level
+  /// from 0 (none) to 12 (smallest)
+  pub(crate) fn to_chunk(&self, level: u8) -> Result<(ChunkType, Vec<u8>), RSMError> {
+    self.validate()?;
+    let mut data: Vec<u8> = self.keyword().to_bytes();
+
+    Ok(match self {
+      Self::Text(_, text) => {
+        data.push(0);
+        data.extend(write_text(text)?);
+        (ChunkType::tEXt, data)
+      }
+
+      Self::CompressedText(_, text) => {
+        data.extend_from_slice(&[0, 0]);
+        data.extend(deflate(&write_text(text)?, level)?);
+        (ChunkType::zTXt, data)
+      }
+
+      Self::InternationalText {
+        language,
+        translated_keyword,
+        text,
+        compressed,
+        ..
+      } => {
+        data.extend_from_slice(&[0, *compressed as u8, 0]);
+        data.extend_from_slice(language.as_bytes());
+        data.push(0);
+        data.extend_from_slice(translated_keyword.as_bytes());
+        data.push(0);
+
+        match compressed {
+          true => data.extend(deflate(text.as_bytes(), level)?),
+          false => data.extend_from_slice(text.as_bytes()),
+        }
+        (ChunkType::iTXt, data)
+      }
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_text_validation() {
+    assert!(Text::new("Comment", "line\nbreak").is_ok());
+    assert!(Text::new("Comment", "carriage\r\nreturn").is_err());
+    assert!(Text::new("Comment", "Ω").is_err());
+    assert!(Text::compressed("Comment", "nul\0").is_err());
+    assert!(Text::international("Comment", "fr-CA", "Commentaire", "Ω", false).is_ok());
+    assert!(Text::international("Comment", "fr_CA", "Commentaire", "Ω", false).is_err());
+    assert!(Text::international("Comment", "", "", "tab\t", true).is_err());
+  }
+
+  #[test]
+  fn test_best_fit() {
+    let long: String = "é".repeat(Text::COMPRESSION_THRESHOLD);
+    assert!(matches!(
+      Text::best_fit("Title", "café"),
+      Ok(Text::Text(..))
+    ));
+    assert!(matches!(
+      Text::best_fit("Title", &long),
+      Ok(Text::CompressedText(..))
+    ));
+    assert!(matches!(
+      Text::best_fit("Title", "Ωmega"),
+      Ok(Text::InternationalText {
+        compressed: false,
+        ..
+      })
+    ));
+    assert!(Text::best_fit("Title", "bell\x07").is_err());
+  }
+
+  #[test]
+  fn test_unrepresentable_text() {
+    let text: Text = Text::Text(Keyword::new("Comment").unwrap(), "€".into());
+    assert!(matches!(text.to_chunk(6), Err(RSMError::InvalidContent)));
+  }
 }
@@ -0,0 +1,168 @@
+use crate::lib::{
+  img::png::parse::{
+    chunks::text::{png_creation_time::CreationTime, png_keyword::Keyword, png_text::Text},
+    states::data::png_metadata::PNGMetadata,
+  },
+  util::err::rsm_error::RSMError,
+};
+
+/// Define the getter and setter of text stored under a registered keyword
+macro_rules! define_text_accessors {
+  ($($(#[$doc:meta])* $getter:ident, $setter:ident => $keyword:ident;)*) => {
+    impl PNGMetadata {
+      $(
+        $(#[$doc])*
+        pub fn $getter(&self) -> Option<&str> {
+          self.text(Keyword::$keyword)
+        }
+
+        #[doc = concat!("Set the text returned by [`Self::", stringify!($getter), "`]")]
+        pub fn $setter(&mut self, text: &str) -> Result<(), RSMError> {
+          self.set_text(Keyword::$keyword, text)
+        }
+      )*
+    }
+  };
+}
+
+define_text_accessors! {
+  /// Short title or caption of the image (`Title`)
+  title, set_title => TITLE;
+  /// Name of the author of the image (`Author`)
+  author, set_author => AUTHOR;
+  /// Description of the image (`Description`)
+  description, set_description => DESCRIPTION;
+  /// Copyright notice (`Copyright`)
+  copyright, set_copyright => COPYRIGHT;
+  /// Software used to create the image (`Software`)
+  software, set_software => SOFTWARE;
+  /// Legal disclaimer (`Disclaimer`)
+  disclaimer, set_disclaimer => DISCLAIMER;
+  /// Warning of the nature of the content (`Warning`)
+  warning, set_warning => WARNING;
+  /// Device used to create the image (`Source`)
+  source, set_source => SOURCE;
+  /// Miscellaneous comment (`Comment`)
+  comment, set_comment => COMMENT;
+}
+
+impl PNGMetadata {
+  /// Obtain the entries stored under a keyword, whether they come from `tEXt`,
+  /// `zTXt` or `iTXt` chunks, in the order they were found
+  pub fn texts<'m>(&'m self, keyword: &str) -> impl Iterator<Item = &'m Text> {
+    self
+      .text_entries
+      .iter()
+      .flatten()
+      .filter(move |text| text.keyword() == keyword)
+  }
+
+  /// Obtain the first text stored under a keyword
+  pub fn text(&self, keyword: &str) -> Option<&str> {
+    self.texts(keyword).next().map(Text::text)
+  }
+
+  /// Obtain the text stored under a keyword in a language, falling back to the
+  /// first text stored under the keyword when no `iTXt` chunk matches the
+  /// language tag
+  pub fn text_in(&self, keyword: &str, language: &str) -> Option<&str> {
+    self
+      .texts(keyword)
+      .find(|text| {
+        matches!(text, Text::InternationalText { language: tag, .. }
+          if tag.eq_ignore_ascii_case(language))
+      })
+      .map(Text::text)
+      .or_else(|| self.text(keyword))
+  }
+
+  /// Replace the entries stored under a keyword with a text, stored in the
+  /// chunk best suited to it (see [`Text::best_fit`])
+  pub fn set_text(&mut self, keyword: &str, text: &str) -> Result<(), RSMError> {
+    let text: Text = Text::best_fit(keyword, text)?;
+    self.remove_text(keyword);
+    self.text_entries.get_or_insert_default().push(text);
+    Ok(())
+  }
+
+  /// Remove the entries stored under a keyword, returning them
+  pub fn remove_text(&mut self, keyword: &str) -> Vec<Text> {
+    let Some(entries) = &mut self.text_entries else {
+      return Vec::new();
+    };
+
+    let (removed, kept): (Vec<Text>, Vec<Text>) = entries
+      .drain(..)
+      .partition(|text| text.keyword() == keyword);
+    self.text_entries = (!kept.is_empty()).then_some(kept);
+    removed
+  }
+
+  /// Time of original image creation (`Creation Time`), which is `None` when
+  /// missing and an error when not written as a RFC 1123 or ISO 8601 date
+  pub fn creation_time(&self) -> Option<Result<CreationTime, RSMError>> {
+    self
+      .text(Keyword::CREATION_TIME)
+      .map(CreationTime::try_from)
+  }
+
+  /// Set the time of original image creation, written as a RFC 1123 date
+  pub fn set_creation_time(&mut self, time: &CreationTime) {
+    self
+      .set_text(Keyword::CREATION_TIME, &time.to_string())
+      .expect("dates only hold ASCII characters")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_merged_sources() {
+    let mut meta: PNGMetadata = PNGMetadata {
+      text_entries: Some(vec![
+        Text::compressed("Title", "Sunset").unwrap(),
+        Text::international("Title", "fr", "Titre", "Coucher de soleil", false).unwrap(),
+        Text::new("Author", "Jane Doe").unwrap(),
+      ]),
+      ..Default::default()
+    };
+
+    assert_eq!(meta.title(), Some("Sunset"));
+    assert_eq!(meta.text_in("Title", "FR"), Some("Coucher de soleil"));
+    assert_eq!(meta.text_in("Title", "de"), Some("Sunset"));
+    assert_eq!(meta.author(), Some("Jane Doe"));
+    assert_eq!(meta.texts("Title").count(), 2);
+
+    meta.set_title("Ωmega").unwrap();
+    assert_eq!(meta.title(), Some("Ωmega"));
+    assert_eq!(meta.texts("Title").count(), 1);
+
+    assert_eq!(meta.remove_text("Author").len(), 1);
+    assert_eq!(meta.remove_text("Title").len(), 1);
+    assert!(meta.text_entries.is_none());
+  }
+
+  #[test]
+  fn test_creation_time() {
+    let mut meta: PNGMetadata = PNGMetadata::default();
+    assert!(meta.creation_time().is_none());
+
+    meta.set_comment("rsm").unwrap();
+    meta
+      .set_text("Creation Time", "2026-10-19T14:30:00Z")
+      .unwrap();
+    let time: CreationTime = meta.creation_time().unwrap().unwrap();
+
+    meta.set_creation_time(&time);
+    assert_eq!(
+      meta.text("Creation Time"),
+      Some("Mon, 19 Oct 2026 14:30:00 GMT")
+    );
+    assert_eq!(meta.creation_time().unwrap().unwrap(), time);
+
+    meta.set_text("Creation Time", "last week").unwrap();
+    assert!(meta.creation_time().unwrap().is_err());
+  }
+}
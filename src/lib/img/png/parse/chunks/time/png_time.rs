@@ -1,5 +1,13 @@
-/// Last-modification time from the `tIME` chunk
-#[derive(Debug, PartialEq)]
+use crate::lib::util::err::rsm_error::RSMError;
+use std::{
+  fmt::{Display, Formatter, Result},
+  time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Last-modification time from the `tIME` chunk, in UTC
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub struct ModificationTime {
   pub year: u16,
   pub month: u8,
@@ -8,3 +16,253 @@ pub struct ModificationTime {
   pub minute: u8,
   pub second: u8,
 }
+
+impl ModificationTime {
+  /// Create a time, checking it exists
+  pub fn new(
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+  ) -> std::result::Result<Self, RSMError> {
+    let time: Self = Self {
+      year,
+      month,
+      day,
+      hour,
+      minute,
+      second,
+    };
+    time.validate()?;
+    Ok(time)
+  }
+
+  /// Current time, which is the Unix epoch if the system clock is set outside
+  /// the years 0 to 65535
+  pub fn now() -> Self {
+    Self::try_from(SystemTime::now()).unwrap_or(Self::UNIX_EPOCH)
+  }
+
+  /// Start of the Unix epoch (1970-01-01T00:00:00Z)
+  pub const UNIX_EPOCH: Self = Self {
+    year: 1970,
+    month: 1,
+    day: 1,
+    hour: 0,
+    minute: 0,
+    second: 0,
+  };
+
+  /// Check the time exists: the day must be in its month, and leap seconds
+  /// (60) may only occur in the last minute of a day
+  pub fn validate(&self) -> std::result::Result<(), RSMError> {
+    let valid: bool = (1..=12).contains(&self.month)
+      && (1..=days_in_month(self.year as i64, self.month)).contains(&self.day)
+      && self.hour < 24
+      && self.minute < 60
+      && (self.second < 60 || (self.second == 60 && self.hour == 23 && self.minute == 59));
+
+    match valid {
+      true => Ok(()),
+      false => Err(RSMError::InvalidContent),
+    }
+  }
+
+  /// Create a time from the number of seconds since the Unix epoch
+  pub fn from_unix_timestamp(timestamp: i64) -> std::result::Result<Self, RSMError> {
+    let days: i64 = timestamp.div_euclid(SECONDS_PER_DAY);
+    let seconds: i64 = timestamp.rem_euclid(SECONDS_PER_DAY);
+    let (year, month, day) = civil_from_days(days);
+
+    Ok(Self {
+      year: u16::try_from(year).map_err(|_| RSMError::OutOfBounds)?,
+      month,
+      day,
+      hour: (seconds / 3600) as u8,
+      minute: (seconds % 3600 / 60) as u8,
+      second: (seconds % 60) as u8,
+    })
+  }
+
+  /// Number of seconds since the Unix epoch. Unix time has no leap seconds,
+  /// so a leap second is counted as the first second of the next day.
+  pub fn to_unix_timestamp(&self) -> i64 {
+    let days: i64 = days_from_civil(self.year as i64, self.month, self.day);
+    days * SECONDS_PER_DAY + self.hour as i64 * 3600 + self.minute as i64 * 60 + self.second as i64
+  }
+
+  /// Convert to a system time, if the platform can represent it
+  pub fn to_system_time(&self) -> Option<SystemTime> {
+    let timestamp: i64 = self.to_unix_timestamp();
+    let offset: Duration = Duration::from_secs(timestamp.unsigned_abs());
+    match timestamp >= 0 {
+      true => UNIX_EPOCH.checked_add(offset),
+      false => UNIX_EPOCH.checked_sub(offset),
+    }
+  }
+
+  /// Encode the time as the data of a `tIME` chunk
+  pub fn to_bytes(&self) -> [u8; 7] {
+    let [high, low] = self.year.to_be_bytes();
+    [
+      high,
+      low,
+      self.month,
+      self.day,
+      self.hour,
+      self.minute,
+      self.second,
+    ]
+  }
+}
+
+impl TryFrom<SystemTime> for ModificationTime {
+  type Error = RSMError;
+
+  /// Convert a system time, truncated to the second. Times outside the years
+  /// 0 to 65535 are out of bounds.
+  fn try_from(time: SystemTime) -> std::result::Result<Self, Self::Error> {
+    let timestamp: i64 = match time.duration_since(UNIX_EPOCH) {
+      Ok(after) => i64::try_from(after.as_secs()).map_err(|_| RSMError::OutOfBounds)?,
+      Err(before) => {
+        let before: Duration = before.duration();
+        let seconds: i64 = i64::try_from(before.as_secs()).map_err(|_| RSMError::OutOfBounds)?;
+        -seconds - (before.subsec_nanos() > 0) as i64
+      }
+    };
+    Self::from_unix_timestamp(timestamp)
+  }
+}
+
+impl TryFrom<ModificationTime> for SystemTime {
+  type Error = RSMError;
+
+  /// Convert a time, which is out of bounds if the platform cannot represent
+  /// it
+  fn try_from(time: ModificationTime) -> std::result::Result<Self, Self::Error> {
+    time.to_system_time().ok_or(RSMError::OutOfBounds)
+  }
+}
+
+/// Write the time as a RFC 3339 date (e.g. `2026-10-19T14:30:05Z`)
+impl Display for ModificationTime {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    write!(
+      f,
+      "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+      self.year, self.month, self.day, self.hour, self.minute, self.second
+    )
+  }
+}
+
+fn days_in_month(year: i64, month: u8) -> u8 {
+  let leap: bool = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+  match month {
+    2 if leap => 29,
+    2 => 28,
+    4 | 6 | 9 | 11 => 30,
+    _ => 31,
+  }
+}
+
+/// Number of days since the Unix epoch of a date in the proleptic Gregorian
+/// calendar
+fn days_from_civil(year: i64, month: u8, day: u8) -> i64 {
+  let year: i64 = year - (month <= 2) as i64;
+  let era: i64 = year.div_euclid(400);
+  let year_of_era: i64 = year - era * 400;
+  let month: i64 = (month as i64 + 9) % 12;
+  let day_of_year: i64 = (153 * month + 2) / 5 + day as i64 - 1;
+  let day_of_era: i64 = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+  era * 146_097 + day_of_era - 719_468
+}
+
+/// Date in the proleptic Gregorian calendar of a number of days since the Unix
+/// epoch
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+  let days: i64 = days + 719_468;
+  let era: i64 = days.div_euclid(146_097);
+  let day_of_era: i64 = days - era * 146_097;
+  let year_of_era: i64 =
+    (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+  let day_of_year: i64 = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  let month: i64 = (5 * day_of_year + 2) / 153;
+  let day: u8 = (day_of_year - (153 * month + 2) / 5 + 1) as u8;
+  let month: u8 = if month < 10 { month + 3 } else { month - 9 } as u8;
+  (year_of_era + era * 400 + (month <= 2) as i64, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use proptest::{prop_assert_eq, proptest};
+
+  #[test]
+  fn test_validation() {
+    assert!(ModificationTime::new(2024, 2, 29, 0, 0, 0).is_ok());
+    assert!(ModificationTime::new(2023, 2, 29, 0, 0, 0).is_err());
+    assert!(ModificationTime::new(1900, 2, 29, 0, 0, 0).is_err());
+    assert!(ModificationTime::new(2000, 2, 29, 0, 0, 0).is_ok());
+    assert!(ModificationTime::new(2026, 4, 31, 0, 0, 0).is_err());
+    assert!(ModificationTime::new(2016, 12, 31, 23, 59, 60).is_ok());
+    assert!(ModificationTime::new(2016, 12, 31, 12, 0, 60).is_err());
+    assert!(ModificationTime::new(2026, 13, 1, 0, 0, 0).is_err());
+  }
+
+  #[test]
+  fn test_unix_timestamps() {
+    let time: ModificationTime = ModificationTime::new(2026, 10, 19, 14, 30, 5).unwrap();
+    assert_eq!(time.to_unix_timestamp(), 1_792_420_205);
+    assert_eq!(
+      ModificationTime::from_unix_timestamp(1_792_420_205).unwrap(),
+      time
+    );
+    assert_eq!(
+      ModificationTime::from_unix_timestamp(0).unwrap(),
+      ModificationTime::UNIX_EPOCH
+    );
+    assert_eq!(
+      ModificationTime::from_unix_timestamp(-1).unwrap(),
+      ModificationTime::new(1969, 12, 31, 23, 59, 59).unwrap()
+    );
+    assert!(ModificationTime::from_unix_timestamp(i64::MAX / 2).is_err());
+
+    let leap: ModificationTime = ModificationTime::new(2016, 12, 31, 23, 59, 60).unwrap();
+    assert_eq!(
+      ModificationTime::from_unix_timestamp(leap.to_unix_timestamp()).unwrap(),
+      ModificationTime::new(2017, 1, 1, 0, 0, 0).unwrap()
+    );
+  }
+
+  #[test]
+  fn test_system_time() {
+    let time: ModificationTime = ModificationTime::new(1960, 1, 1, 0, 0, 0).unwrap();
+    let system: SystemTime = time.to_system_time().unwrap();
+    assert_eq!(ModificationTime::try_from(system).unwrap(), time);
+    assert_eq!(SystemTime::try_from(time).unwrap(), system);
+
+    let truncated: SystemTime = system - Duration::from_millis(1);
+    assert_eq!(
+      ModificationTime::try_from(truncated).unwrap(),
+      ModificationTime::new(1959, 12, 31, 23, 59, 59).unwrap()
+    );
+  }
+
+  #[test]
+  fn test_rfc_3339() {
+    let time: ModificationTime = ModificationTime::new(987, 6, 5, 4, 3, 2).unwrap();
+    assert_eq!(time.to_string(), "0987-06-05T04:03:02Z");
+    assert_eq!(time.to_bytes(), [3, 219, 6, 5, 4, 3, 2]);
+  }
+
+  proptest! {
+    #[test]
+    fn test_timestamp_round_trip(timestamp in -62_167_219_200i64..=2_005_949_145_599) {
+      let time: ModificationTime = ModificationTime::from_unix_timestamp(timestamp).unwrap();
+      prop_assert_eq!(time.validate().is_ok(), true);
+      prop_assert_eq!(time.to_unix_timestamp(), timestamp);
+    }
+  }
+}
@@ -0,0 +1,72 @@
+use crate::lib::{
+  img::png::parse::chunks::{
+    ihdr::png_color_type::ColorType, plte::png_palette::Palette,
+    trns::png_transparency::Transparency,
+  },
+  util::err::rsm_error::RSMError,
+};
+
+/// Handle `tRNS` (Transparency) Chunk, which must follow the `PLTE` chunk of
+/// indexed-color images
+pub(crate) fn handle_trns(
+  data: &[u8],
+  color_type: ColorType,
+  palette: Option<&Palette>,
+) -> Result<Transparency, RSMError> {
+  let sample = |index: usize| u16::from_be_bytes([data[index * 2], data[index * 2 + 1]]);
+
+  match color_type {
+    // Color type: 0
+    ColorType::Greyscale if data.len() == 2 => Ok(Transparency::GrayKey(sample(0))),
+
+    // Color type: 2
+    ColorType::Truecolor if data.len() == 6 => {
+      Ok(Transparency::RgbKey(sample(0), sample(1), sample(2)))
+    }
+
+    // Color type: 3
+    ColorType::IndexedColor => {
+      palette
+        .ok_or(RSMError::InvalidContent)?
+        .validate_alpha(data)?;
+      Ok(Transparency::PaletteAlpha(data.to_vec()))
+    }
+
+    ColorType::Greyscale | ColorType::Truecolor => Err(RSMError::InvalidLength),
+    _ => Err(RSMError::InvalidContent),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_keys() {
+    let grey = handle_trns(&[0x01, 0x02], ColorType::Greyscale, None);
+    assert!(matches!(grey, Ok(Transparency::GrayKey(0x0102))));
+
+    let rgb = handle_trns(&[0, 1, 0, 2, 0, 3], ColorType::Truecolor, None);
+    assert!(matches!(rgb, Ok(Transparency::RgbKey(1, 2, 3))));
+
+    let short = handle_trns(&[0, 1], ColorType::Truecolor, None);
+    assert!(matches!(short, Err(RSMError::InvalidLength)));
+
+    let alpha = handle_trns(&[0, 1], ColorType::TruecolorAlpha, None);
+    assert!(matches!(alpha, Err(RSMError::InvalidContent)));
+  }
+
+  #[test]
+  fn test_palette_alpha() {
+    let palette: Palette = Palette::new(vec![[0; 3]; 2]).unwrap();
+
+    let alpha = handle_trns(&[0, 128], ColorType::IndexedColor, Some(&palette));
+    assert_eq!(alpha.unwrap(), Transparency::PaletteAlpha(vec![0, 128]));
+
+    let long = handle_trns(&[0, 128, 255], ColorType::IndexedColor, Some(&palette));
+    assert!(matches!(long, Err(RSMError::InvalidLength)));
+
+    let missing = handle_trns(&[0], ColorType::IndexedColor, None);
+    assert!(matches!(missing, Err(RSMError::InvalidContent)));
+  }
+}
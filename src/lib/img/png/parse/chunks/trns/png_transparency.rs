@@ -0,0 +1,78 @@
+use crate::lib::img::png::parse::chunks::ihdr::png_color_type::ColorType;
+
+/// Transparency from the `tRNS` chunk
+#[derive(Debug, PartialEq, Clone)]
+pub enum Transparency {
+  /// Grey sample value of pixels which are fully transparent
+  GrayKey(u16),
+
+  /// RGB sample values of pixels which are fully transparent
+  RgbKey(u16, u16, u16),
+
+  /// Alpha values of the first palette entries. Later entries are opaque.
+  PaletteAlpha(Vec<u8>),
+}
+
+impl Transparency {
+  /// Determine if the transparency applies to images of a color type
+  pub fn matches(&self, color_type: ColorType) -> bool {
+    matches!(
+      (self, color_type),
+      (Self::GrayKey(_), ColorType::Greyscale)
+        | (Self::RgbKey(..), ColorType::Truecolor)
+        | (Self::PaletteAlpha(_), ColorType::IndexedColor)
+    )
+  }
+
+  /// Obtain the alpha of a grey sample: 0 if it matches the key, or the
+  /// opaque value otherwise
+  pub fn grey_alpha(&self, grey: u16, opaque: u16) -> u16 {
+    match self {
+      Self::GrayKey(key) if *key == grey => 0,
+      _ => opaque,
+    }
+  }
+
+  /// Obtain the alpha of RGB samples: 0 if they match the key, or the opaque
+  /// value otherwise
+  pub fn rgb_alpha(&self, [r, g, b]: [u16; 3], opaque: u16) -> u16 {
+    match self {
+      Self::RgbKey(key_r, key_g, key_b) if [*key_r, *key_g, *key_b] == [r, g, b] => 0,
+      _ => opaque,
+    }
+  }
+
+  /// Encode the transparency as the data of a `tRNS` chunk
+  pub fn to_bytes(&self) -> Vec<u8> {
+    match self {
+      Self::GrayKey(grey) => grey.to_be_bytes().to_vec(),
+      Self::RgbKey(r, g, b) => [r, g, b].iter().flat_map(|s| s.to_be_bytes()).collect(),
+      Self::PaletteAlpha(alpha) => alpha.clone(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_keys() {
+    let grey: Transparency = Transparency::GrayKey(0x0102);
+    assert_eq!(grey.grey_alpha(0x0102, 0xffff), 0);
+    assert_eq!(grey.grey_alpha(0x0002, 0xffff), 0xffff);
+    assert_eq!(grey.rgb_alpha([0x0102; 3], 0xffff), 0xffff);
+
+    let rgb: Transparency = Transparency::RgbKey(1, 0x0200, 3);
+    assert_eq!(rgb.rgb_alpha([1, 0x0200, 3], 255), 0);
+    assert_eq!(rgb.rgb_alpha([1, 0, 3], 255), 255);
+    assert_eq!(rgb.to_bytes(), [0, 1, 2, 0, 0, 3]);
+  }
+
+  #[test]
+  fn test_matches() {
+    assert!(Transparency::GrayKey(0).matches(ColorType::Greyscale));
+    assert!(!Transparency::GrayKey(0).matches(ColorType::Truecolor));
+    assert!(Transparency::PaletteAlpha(Vec::new()).matches(ColorType::IndexedColor));
+  }
+}
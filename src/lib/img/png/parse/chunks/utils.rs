@@ -1,6 +1,20 @@
 use crate::lib::util::err::rsm_error::RSMError;
+use libdeflater::{CompressionLvl, Compressor, DecompressionError, Decompressor};
+use miniz_oxide::inflate::{
+  TINFLStatus,
+  core::{
+    DecompressorOxide, decompress,
+    inflate_flags::{
+      TINFL_FLAG_COMPUTE_ADLER32, TINFL_FLAG_HAS_MORE_INPUT, TINFL_FLAG_PARSE_ZLIB_HEADER,
+      TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF,
+    },
+  },
+};
 use std::ops::Range;
 
+/// Initial size of the buffer used to inflate data of unknown size
+const INFLATE_INITIAL_SIZE: usize = 1024;
+
 pub(crate) fn get_bytes(range: Range<usize>, data: &[u8]) -> Result<&[u8], RSMError> {
   let Some(bytes) = data.get(range) else {
     return Err(RSMError::NotEnoughContent);
@@ -13,6 +27,95 @@ pub(crate) fn read_text(bytes: &[u8]) -> Result<String, RSMError> {
   Ok(bytes.iter().map(|&b| b as char).collect())
 }
 
+/// Write text as bytes (Latin-1), failing on characters that Latin-1 cannot
+/// represent
+pub(crate) fn write_text(text: &str) -> Result<Vec<u8>, RSMError> {
+  text
+    .chars()
+    .map(|c| u8::try_from(c).map_err(|_| RSMError::InvalidContent))
+    .collect()
+}
+
+/// Decompress zlib data of unknown decompressed size, growing the output buffer
+/// as needed, but never beyond `limit` bytes. The returned buffer holds exactly
+/// the decompressed bytes.
+pub(crate) fn inflate(data: &[u8], limit: usize) -> Result<Vec<u8>, RSMError> {
+  let mut decompressor: Decompressor = Decompressor::new();
+  let mut size: usize = data
+    .len()
+    .saturating_mul(4)
+    .max(INFLATE_INITIAL_SIZE)
+    .min(limit);
+
+  loop {
+    let mut buffer: Vec<u8> = vec![0u8; size];
+
+    match decompressor.zlib_decompress(data, &mut buffer) {
+      Ok(written) => {
+        buffer.truncate(written);
+        return Ok(buffer);
+      }
+      Err(DecompressionError::InsufficientSpace) if size < limit => {
+        size = size.saturating_mul(2).min(limit);
+      }
+      Err(DecompressionError::InsufficientSpace) => return Err(RSMError::LimitExceeded),
+      Err(DecompressionError::BadData) => return Err(RSMError::DecompressionError),
+    }
+  }
+}
+
+/// Decompress a zlib stream split across slices (e.g. the data of consecutive
+/// `IDAT` chunks) into the output, without joining the slices. Returns the
+/// number of bytes written, failing if the output is too small.
+pub(crate) fn inflate_slices(slices: &[&[u8]], output: &mut [u8]) -> Result<usize, RSMError> {
+  let mut decompressor: Box<DecompressorOxide> = Box::default();
+  let mut written: usize = 0;
+
+  for (index, slice) in slices.iter().enumerate() {
+    let mut flags: u32 = TINFL_FLAG_PARSE_ZLIB_HEADER
+      | TINFL_FLAG_COMPUTE_ADLER32
+      | TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF;
+    if index + 1 < slices.len() {
+      flags |= TINFL_FLAG_HAS_MORE_INPUT;
+    }
+
+    let mut input: &[u8] = slice;
+    loop {
+      let (status, consumed, produced) =
+        decompress(&mut decompressor, input, output, written, flags);
+      input = &input[consumed..];
+      written += produced;
+
+      // The output being full is only an error once no progress can be made,
+      // as the end of the stream may not produce any byte
+      match status {
+        TINFLStatus::Done => return Ok(written),
+        TINFLStatus::NeedsMoreInput | TINFLStatus::HasMoreOutput if input.is_empty() => break,
+        TINFLStatus::HasMoreOutput if consumed == 0 && produced == 0 => {
+          return Err(RSMError::DecompressionError);
+        }
+        TINFLStatus::NeedsMoreInput | TINFLStatus::HasMoreOutput => continue,
+        _ => return Err(RSMError::DecompressionError),
+      }
+    }
+  }
+  Err(RSMError::DecompressionError)
+}
+
+/// Compress data using zlib, at a level from 0 (none) to 12 (smallest)
+pub(crate) fn deflate(data: &[u8], level: u8) -> Result<Vec<u8>, RSMError> {
+  let level: CompressionLvl =
+    CompressionLvl::new(level as i32).map_err(|_| RSMError::OutOfBounds)?;
+  let mut compressor: Compressor = Compressor::new(level);
+
+  let mut buffer: Vec<u8> = vec![0u8; compressor.zlib_compress_bound(data.len())];
+  let written: usize = compressor
+    .zlib_compress(data, &mut buffer)
+    .map_err(|_| RSMError::OutOfBounds)?;
+  buffer.truncate(written);
+  Ok(buffer)
+}
+
 #[macro_export]
 macro_rules! define_png_enum {
   (
@@ -62,3 +165,80 @@ macro_rules! define_png_enum {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use proptest::{collection::vec, prelude::any, prop_assert_eq, proptest};
+
+  fn compress(data: &[u8]) -> Vec<u8> {
+    deflate(data, 6).unwrap()
+  }
+
+  #[test]
+  fn test_inflate_slices() {
+    let data: Vec<u8> = (0..100_000u64).map(|n| (n * n % 251) as u8).collect();
+    let compressed: Vec<u8> = compress(&data);
+    let mut output: Vec<u8> = vec![0; data.len()];
+
+    for size in [1, 7, 4096, compressed.len()] {
+      let slices: Vec<&[u8]> = compressed.chunks(size).collect();
+      output.fill(0);
+      assert_eq!(inflate_slices(&slices, &mut output).unwrap(), data.len());
+      assert_eq!(output, data);
+    }
+
+    let mut short: Vec<u8> = vec![0; data.len() - 1];
+    assert!(inflate_slices(&[&compressed], &mut short).is_err());
+
+    let truncated: &[u8] = &compressed[..compressed.len() / 2];
+    assert!(inflate_slices(&[truncated, &[]], &mut output).is_err());
+
+    let mut corrupt: Vec<u8> = compressed.clone();
+    *corrupt.last_mut().unwrap() ^= 1;
+    assert!(inflate_slices(&[&corrupt], &mut output).is_err());
+  }
+
+  #[test]
+  fn test_inflate_compressible() {
+    let data: Vec<u8> = vec![b'a'; 1 << 20];
+    assert_eq!(inflate(&compress(&data), usize::MAX).unwrap(), data);
+  }
+
+  #[test]
+  fn test_inflate_limit() {
+    let data: Vec<u8> = vec![0u8; 1 << 16];
+    let compressed: Vec<u8> = compress(&data);
+
+    assert!(matches!(
+      inflate(&compressed, (1 << 16) - 1),
+      Err(RSMError::LimitExceeded)
+    ));
+    assert_eq!(inflate(&compressed, 1 << 16).unwrap(), data);
+  }
+
+  #[test]
+  fn test_inflate_invalid() {
+    assert!(matches!(
+      inflate(&[0x78, 0x9c, 0xff], usize::MAX),
+      Err(RSMError::DecompressionError)
+    ));
+  }
+
+  #[test]
+  fn test_deflate_level() {
+    assert!(matches!(deflate(b"rsm", 13), Err(RSMError::OutOfBounds)));
+    assert_eq!(
+      inflate(&deflate(b"rsm", 0).unwrap(), usize::MAX).unwrap(),
+      b"rsm"
+    );
+  }
+
+  proptest! {
+    /// Test inflated data holds exactly the decompressed bytes
+    #[test]
+    fn test_inflate_exact(data in vec(any::<u8>(), 0..4096)) {
+      prop_assert_eq!(inflate(&compress(&data), usize::MAX).unwrap(), data);
+    }
+  }
+}
@@ -0,0 +1,15 @@
+use crate::lib::{
+  img::png::parse::chunks::{text::png_text::Text, xmp::png_xmp::PNGXmpData},
+  util::err::rsm_error::RSMError,
+};
+
+/// Handle the text of an `iTXt` chunk, returning the XMP data it holds when its
+/// keyword is `XML:com.adobe.xmp`.
+pub(crate) fn handle_xmp(text: &Text) -> Result<Option<PNGXmpData>, RSMError> {
+  match text {
+    Text::InternationalText { keyword, text, .. } if keyword == PNGXmpData::KEYWORD => {
+      PNGXmpData::from_packet(text).map(Some)
+    }
+    _ => Ok(None),
+  }
+}
@@ -0,0 +1,310 @@
+use crate::lib::{
+  img::png::parse::chunks::xmp::{
+    png_xmp_reader::XmpReader,
+    png_xmp_value::{XmpName, XmpNamespace, XmpProperty, XmpValue},
+    png_xmp_writer::XmpWriter,
+  },
+  util::err::rsm_error::RSMError,
+};
+
+/// Represents Extensible Metadata Platform (XMP) data obtained from an `iTXt`
+/// chunk with the `XML:com.adobe.xmp` keyword
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct PNGXmpData {
+  /// Properties describing the image
+  pub properties: Vec<XmpProperty>,
+
+  /// Prefixes declared for namespaces (prefix, namespace URI), which are
+  /// preferred when encoding.
+  pub prefixes: Vec<(String, String)>,
+}
+
+impl PNGXmpData {
+  /// Keyword of the `iTXt` chunk holding XMP data
+  pub const KEYWORD: &str = "XML:com.adobe.xmp";
+
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Parse an XMP packet
+  pub fn from_packet(packet: &str) -> Result<Self, RSMError> {
+    let mut reader: XmpReader<'_> = XmpReader::new(packet);
+    let properties: Vec<XmpProperty> = reader.read()?;
+
+    Ok(Self {
+      properties,
+      prefixes: reader.prefixes,
+    })
+  }
+
+  /// Encode the data as an XMP packet
+  pub fn to_packet(&self) -> String {
+    XmpWriter::new(&self.properties, &self.prefixes).write(&self.properties)
+  }
+
+  /// Encode the data of the `iTXt` chunk holding the XMP packet, which is left
+  /// uncompressed so that it can be found without decoding PNG chunks.
+  pub fn to_itxt(&self) -> Vec<u8> {
+    let mut data: Vec<u8> = Vec::new();
+    data.extend_from_slice(Self::KEYWORD.as_bytes());
+    data.extend_from_slice(&[0, 0, 0, 0, 0]);
+    data.extend_from_slice(self.to_packet().as_bytes());
+    data
+  }
+
+  /// Obtain the value of a property
+  pub fn get(&self, namespace: &str, name: &str) -> Option<&XmpValue> {
+    self
+      .properties
+      .iter()
+      .find(|property| property.name.namespace == namespace && property.name.name == name)
+      .map(|property| &property.value)
+  }
+
+  /// Set the value of a property
+  pub fn set(&mut self, namespace: &str, name: &str, value: XmpValue) {
+    match self
+      .properties
+      .iter_mut()
+      .find(|property| property.name.namespace == namespace && property.name.name == name)
+    {
+      Some(property) => property.value = value,
+      None => self.properties.push(XmpProperty {
+        name: XmpName::new(namespace, name),
+        value,
+      }),
+    }
+  }
+
+  /// Remove a property, returning its value
+  pub fn remove(&mut self, namespace: &str, name: &str) -> Option<XmpValue> {
+    let index: usize = self
+      .properties
+      .iter()
+      .position(|property| property.name.namespace == namespace && property.name.name == name)?;
+    Some(self.properties.remove(index).value)
+  }
+
+  /// Obtain the properties of a namespace (e.g. every `photoshop:` property)
+  pub fn properties_in<'x>(&'x self, namespace: &'x str) -> impl Iterator<Item = &'x XmpProperty> {
+    self
+      .properties
+      .iter()
+      .filter(move |property| property.name.namespace == namespace)
+  }
+
+  /// Declare the prefix to use for a namespace when encoding
+  pub fn register_namespace(&mut self, prefix: &str, namespace: &str) {
+    self
+      .prefixes
+      .retain(|(p, uri)| p != prefix && uri != namespace);
+    self
+      .prefixes
+      .push((prefix.to_string(), namespace.to_string()));
+  }
+
+  /// Set the text of a property holding alternatives in different languages,
+  /// replacing the alternative in the given language.
+  pub fn set_text_in(&mut self, namespace: &str, name: &str, language: &str, text: &str) {
+    let mut alternatives: Vec<(String, String)> = match self.remove(namespace, name) {
+      Some(XmpValue::LangAlt(alternatives)) => alternatives,
+      _ => Vec::new(),
+    };
+
+    match alternatives
+      .iter_mut()
+      .find(|(tag, _)| tag.eq_ignore_ascii_case(language))
+    {
+      Some((_, existing)) => *existing = text.to_string(),
+      None if language == XmpValue::DEFAULT_LANGUAGE => {
+        alternatives.insert(0, (language.to_string(), text.to_string()))
+      }
+      None => alternatives.push((language.to_string(), text.to_string())),
+    }
+    self.set(namespace, name, XmpValue::LangAlt(alternatives));
+  }
+
+  /// Title of the image (`dc:title`)
+  pub fn title(&self) -> Option<&str> {
+    self.get(XmpNamespace::DC, "title")?.as_str()
+  }
+
+  /// Set the default title of the image
+  pub fn set_title(&mut self, title: &str) {
+    self.set_text_in(XmpNamespace::DC, "title", XmpValue::DEFAULT_LANGUAGE, title)
+  }
+
+  /// Description of the image (`dc:description`)
+  pub fn description(&self) -> Option<&str> {
+    self.get(XmpNamespace::DC, "description")?.as_str()
+  }
+
+  /// Set the default description of the image
+  pub fn set_description(&mut self, description: &str) {
+    let language: &str = XmpValue::DEFAULT_LANGUAGE;
+    self.set_text_in(XmpNamespace::DC, "description", language, description)
+  }
+
+  /// Creators of the image, in order of precedence (`dc:creator`)
+  pub fn creators(&self) -> Vec<&str> {
+    let Some(creator) = self.get(XmpNamespace::DC, "creator") else {
+      return Vec::new();
+    };
+
+    match creator.as_array() {
+      Some(items) => items.iter().filter_map(XmpValue::as_str).collect(),
+      None => creator.as_str().into_iter().collect(),
+    }
+  }
+
+  /// Set the creators of the image
+  pub fn set_creators(&mut self, creators: &[&str]) {
+    let creators: Vec<XmpValue> = creators
+      .iter()
+      .map(|creator| XmpValue::Text(creator.to_string()))
+      .collect();
+    self.set(XmpNamespace::DC, "creator", XmpValue::Seq(creators));
+  }
+
+  /// Rights statement of the image (`dc:rights`)
+  pub fn rights(&self) -> Option<&str> {
+    self.get(XmpNamespace::DC, "rights")?.as_str()
+  }
+
+  /// Set the default rights statement of the image
+  pub fn set_rights(&mut self, rights: &str) {
+    self.set_text_in(
+      XmpNamespace::DC,
+      "rights",
+      XmpValue::DEFAULT_LANGUAGE,
+      rights,
+    )
+  }
+
+  /// Date at which the image was created, as an ISO 8601 date
+  /// (`xmp:CreateDate`)
+  pub fn create_date(&self) -> Option<&str> {
+    self.get(XmpNamespace::XMP, "CreateDate")?.as_str()
+  }
+
+  /// Set the date at which the image was created, as an ISO 8601 date
+  pub fn set_create_date(&mut self, date: &str) {
+    let value: XmpValue = XmpValue::Text(date.to_string());
+    self.set(XmpNamespace::XMP, "CreateDate", value)
+  }
+
+  /// Tool used to create the image (`xmp:CreatorTool`)
+  pub fn creator_tool(&self) -> Option<&str> {
+    self.get(XmpNamespace::XMP, "CreatorTool")?.as_str()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const PACKET: &str = r#"<?xpacket begin="" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/">
+  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    <rdf:Description rdf:about=""
+        xmlns:xmp="http://ns.adobe.com/xap/1.0/"
+        xmlns:photoshop="http://ns.adobe.com/photoshop/1.0/"
+        xmp:CreateDate="2024-02-29T13:45:30+01:00"
+        photoshop:Credit="Agency">
+      <photoshop:City>Montr&#233;al</photoshop:City>
+    </rdf:Description>
+    <rdf:Description rdf:about=""
+        xmlns:dc="http://purl.org/dc/elements/1.1/"
+        xmlns:acme="http://example.com/acme/">
+      <dc:title>
+        <rdf:Alt>
+          <rdf:li xml:lang="x-default">Sunset &amp; sea</rdf:li>
+          <rdf:li xml:lang="fr-CA">Coucher de soleil</rdf:li>
+        </rdf:Alt>
+      </dc:title>
+      <dc:creator>
+        <rdf:Seq>
+          <rdf:li>Jane Doe</rdf:li>
+          <rdf:li>John Doe</rdf:li>
+        </rdf:Seq>
+      </dc:creator>
+      <acme:License rdf:parseType="Resource">
+        <acme:Id>42</acme:Id>
+      </acme:License>
+      <acme:Site rdf:resource="https://example.com"/>
+    </rdf:Description>
+  </rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>"#;
+
+  #[test]
+  fn test_xmp_read() {
+    let xmp: PNGXmpData = PNGXmpData::from_packet(PACKET).unwrap();
+
+    assert_eq!(xmp.title(), Some("Sunset & sea"));
+    assert_eq!(
+      xmp.get(XmpNamespace::DC, "title").unwrap().text_in("fr-CA"),
+      Some("Coucher de soleil")
+    );
+    assert_eq!(xmp.creators(), ["Jane Doe", "John Doe"]);
+    assert_eq!(xmp.create_date(), Some("2024-02-29T13:45:30+01:00"));
+    assert_eq!(xmp.properties_in(XmpNamespace::PHOTOSHOP).count(), 2);
+    assert_eq!(
+      xmp.get(XmpNamespace::PHOTOSHOP, "City").unwrap().as_str(),
+      Some("Montréal")
+    );
+
+    let license: &[XmpProperty] = xmp
+      .get("http://example.com/acme/", "License")
+      .unwrap()
+      .as_struct()
+      .unwrap();
+    assert_eq!(license[0].value.as_str(), Some("42"));
+    assert_eq!(
+      xmp
+        .get("http://example.com/acme/", "Site")
+        .unwrap()
+        .as_str(),
+      Some("https://example.com")
+    );
+  }
+
+  #[test]
+  fn test_xmp_round_trip() {
+    let mut xmp: PNGXmpData = PNGXmpData::from_packet(PACKET).unwrap();
+    xmp.set_title("Sunset <edited>");
+    xmp.set_rights("© 2024 Jane Doe");
+    xmp.set(
+      XmpNamespace::DC,
+      "subject",
+      XmpValue::Bag(vec![XmpValue::Text("sea".into())]),
+    );
+
+    let packet: String = xmp.to_packet();
+    assert!(packet.contains("xmlns:acme=\"http://example.com/acme/\""));
+
+    let decoded: PNGXmpData = PNGXmpData::from_packet(&packet).unwrap();
+    assert_eq!(decoded.properties, xmp.properties);
+    assert_eq!(decoded.title(), Some("Sunset <edited>"));
+    assert_eq!(decoded.rights(), Some("© 2024 Jane Doe"));
+  }
+
+  #[test]
+  fn test_xmp_prefixes() {
+    let mut xmp: PNGXmpData = PNGXmpData::new();
+    xmp.set("http://example.com/a/", "A", XmpValue::Text("a".into()));
+    xmp.set("http://example.com/b/", "B", XmpValue::Text("b".into()));
+    xmp.register_namespace("b", "http://example.com/b/");
+
+    let packet: String = xmp.to_packet();
+    assert!(packet.contains("<ns1:A>a</ns1:A>"));
+    assert!(packet.contains("<b:B>b</b:B>"));
+  }
+
+  #[test]
+  fn test_xmp_invalid() {
+    assert!(PNGXmpData::from_packet("<x:xmpmeta xmlns:x=\"adobe:ns:meta/\">").is_err());
+    assert!(PNGXmpData::from_packet("<root/>").is_err());
+  }
+}
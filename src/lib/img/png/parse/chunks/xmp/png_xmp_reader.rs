@@ -0,0 +1,271 @@
+use crate::lib::{
+  img::png::parse::chunks::xmp::png_xmp_value::{XmpName, XmpNamespace, XmpProperty, XmpValue},
+  util::err::rsm_error::RSMError,
+};
+use quick_xml::{
+  NsReader,
+  escape::resolve_predefined_entity,
+  events::{BytesStart, Event},
+  name::{PrefixDeclaration, ResolveResult},
+};
+
+/// Element of an XML document, with its namespace resolved
+#[derive(Debug, Default)]
+struct Element {
+  name: XmpName,
+  attributes: Vec<(XmpName, String)>,
+  children: Vec<Element>,
+  text: String,
+}
+
+impl Element {
+  fn is(&self, namespace: &str, name: &str) -> bool {
+    self.name.namespace == namespace && self.name.name == name
+  }
+
+  fn attribute(&self, namespace: &str, name: &str) -> Option<&str> {
+    self
+      .attributes
+      .iter()
+      .find(|(key, _)| key.namespace == namespace && key.name == name)
+      .map(|(_, value)| value.as_str())
+  }
+
+  /// Attributes that are properties, rather than RDF or XML syntax
+  fn property_attributes(&self) -> impl Iterator<Item = XmpProperty> + '_ {
+    self
+      .attributes
+      .iter()
+      .filter(|(key, _)| key.namespace != XmpNamespace::RDF && key.namespace != XmpNamespace::XML)
+      .map(|(name, value)| XmpProperty {
+        name: name.clone(),
+        value: XmpValue::Text(value.clone()),
+      })
+  }
+}
+
+impl Default for XmpName {
+  fn default() -> Self {
+    Self::new("", "")
+  }
+}
+
+/// Reader of the RDF/XML serialization of XMP data
+pub(crate) struct XmpReader<'x> {
+  reader: NsReader<&'x [u8]>,
+
+  /// Prefixes declared in the packet, with their namespace
+  pub(crate) prefixes: Vec<(String, String)>,
+}
+
+impl<'x> XmpReader<'x> {
+  pub(crate) fn new(packet: &'x str) -> Self {
+    Self {
+      reader: NsReader::from_str(packet),
+      prefixes: Vec::new(),
+    }
+  }
+
+  /// Read the properties described by the packet
+  pub(crate) fn read(&mut self) -> Result<Vec<XmpProperty>, RSMError> {
+    let root: Element = self.read_document()?;
+    let rdf: &Element = Self::find_rdf(&root).ok_or(RSMError::InvalidContent)?;
+
+    let mut properties: Vec<XmpProperty> = Vec::new();
+    for description in &rdf.children {
+      if !description.is(XmpNamespace::RDF, "Description") {
+        return Err(RSMError::InvalidContent);
+      }
+      properties.extend(Self::read_fields(description)?);
+    }
+    Ok(properties)
+  }
+
+  /// Find the `rdf:RDF` element, which may be wrapped in an `x:xmpmeta` element
+  fn find_rdf(element: &Element) -> Option<&Element> {
+    if element.is(XmpNamespace::RDF, "RDF") {
+      return Some(element);
+    }
+    element.children.iter().find_map(Self::find_rdf)
+  }
+
+  /// Read the properties of a node, given as attributes or child elements
+  fn read_fields(node: &Element) -> Result<Vec<XmpProperty>, RSMError> {
+    let mut fields: Vec<XmpProperty> = node.property_attributes().collect();
+    for child in &node.children {
+      fields.push(XmpProperty {
+        name: child.name.clone(),
+        value: Self::read_value(child)?,
+      });
+    }
+    Ok(fields)
+  }
+
+  /// Read the value of a property element or an array item
+  fn read_value(element: &Element) -> Result<XmpValue, RSMError> {
+    if let Some(resource) = element.attribute(XmpNamespace::RDF, "resource") {
+      return Ok(XmpValue::Text(resource.to_string()));
+    }
+    if element.attribute(XmpNamespace::RDF, "parseType") == Some("Resource") {
+      return Ok(XmpValue::Struct(Self::read_fields(element)?));
+    }
+
+    let Some(child) = element.children.first() else {
+      let mut fields = element.property_attributes().peekable();
+      return Ok(match fields.peek() {
+        Some(_) => XmpValue::Struct(fields.collect()),
+        None => XmpValue::Text(element.text.clone()),
+      });
+    };
+
+    if element.children.len() != 1 || child.name.namespace != XmpNamespace::RDF {
+      return Err(RSMError::InvalidContent);
+    }
+
+    match child.name.name.as_str() {
+      "Description" => Ok(XmpValue::Struct(Self::read_fields(child)?)),
+      "Alt" if Self::is_lang_alt(child) => Ok(XmpValue::LangAlt(
+        child
+          .children
+          .iter()
+          .map(|item| {
+            let language: &str = item
+              .attribute(XmpNamespace::XML, "lang")
+              .unwrap_or_default();
+            (language.to_string(), item.text.clone())
+          })
+          .collect(),
+      )),
+      "Bag" => Ok(XmpValue::Bag(Self::read_items(child)?)),
+      "Seq" => Ok(XmpValue::Seq(Self::read_items(child)?)),
+      "Alt" => Ok(XmpValue::Alt(Self::read_items(child)?)),
+      _ => Err(RSMError::InvalidContent),
+    }
+  }
+
+  /// Determine if an `rdf:Alt` array holds texts in different languages
+  fn is_lang_alt(array: &Element) -> bool {
+    !array.children.is_empty()
+      && array
+        .children
+        .iter()
+        .all(|item| item.children.is_empty() && item.attribute(XmpNamespace::XML, "lang").is_some())
+  }
+
+  /// Read the `rdf:li` items of an array
+  fn read_items(array: &Element) -> Result<Vec<XmpValue>, RSMError> {
+    array
+      .children
+      .iter()
+      .map(|item| match item.is(XmpNamespace::RDF, "li") {
+        true => Self::read_value(item),
+        false => Err(RSMError::InvalidContent),
+      })
+      .collect()
+  }
+
+  /// Read the document as a tree of elements
+  fn read_document(&mut self) -> Result<Element, RSMError> {
+    let mut stack: Vec<Element> = vec![Element::default()];
+
+    loop {
+      let event: Event<'_> = self
+        .reader
+        .read_event()
+        .map_err(|_| RSMError::InvalidContent)?;
+      let current: &mut Element = stack.last_mut().ok_or(RSMError::InvalidContent)?;
+
+      match event {
+        Event::Start(start) => {
+          let element: Element = self.read_element(&start)?;
+          stack.push(element);
+        }
+        Event::Empty(start) => {
+          let element: Element = self.read_element(&start)?;
+          current.children.push(element);
+        }
+        Event::End(_) => {
+          let element: Element = stack.pop().ok_or(RSMError::InvalidContent)?;
+          stack
+            .last_mut()
+            .ok_or(RSMError::InvalidContent)?
+            .children
+            .push(element);
+        }
+        Event::Text(text) => {
+          let text = text.xml_content().map_err(|_| RSMError::InvalidContent)?;
+          current.text.push_str(&text);
+        }
+        Event::CData(data) => {
+          let data = data.decode().map_err(|_| RSMError::InvalidContent)?;
+          current.text.push_str(&data);
+        }
+        Event::GeneralRef(reference) => {
+          let character = reference
+            .resolve_char_ref()
+            .map_err(|_| RSMError::InvalidContent)?;
+          let name = reference.decode().map_err(|_| RSMError::InvalidContent)?;
+
+          match character {
+            Some(character) => current.text.push(character),
+            None => current
+              .text
+              .push_str(resolve_predefined_entity(&name).ok_or(RSMError::InvalidContent)?),
+          }
+        }
+        Event::Eof => break,
+        _ => {}
+      }
+    }
+
+    match <[Element; 1]>::try_from(stack) {
+      Ok([root]) => Ok(root),
+      Err(_) => Err(RSMError::InvalidContent),
+    }
+  }
+
+  /// Read the name and attributes of an element, resolving their namespaces
+  fn read_element(&mut self, start: &BytesStart<'_>) -> Result<Element, RSMError> {
+    let mut attributes: Vec<(XmpName, String)> = Vec::new();
+
+    for attribute in start.attributes() {
+      let attribute = attribute.map_err(|_| RSMError::InvalidContent)?;
+      let value: String = attribute
+        .unescape_value()
+        .map_err(|_| RSMError::InvalidContent)?
+        .into_owned();
+
+      match attribute.key.as_namespace_binding() {
+        Some(PrefixDeclaration::Named(prefix)) => {
+          let prefix: String = String::from_utf8_lossy(prefix).into_owned();
+          if !self.prefixes.contains(&(prefix.clone(), value.clone())) {
+            self.prefixes.push((prefix, value));
+          }
+        }
+        Some(PrefixDeclaration::Default) => {}
+        None => {
+          let (namespace, name) = self.reader.resolver().resolve_attribute(attribute.key);
+          attributes.push((Self::name(namespace, name.as_ref()), value));
+        }
+      }
+    }
+
+    let (namespace, name) = self.reader.resolver().resolve_element(start.name());
+    Ok(Element {
+      name: Self::name(namespace, name.as_ref()),
+      attributes,
+      ..Default::default()
+    })
+  }
+
+  fn name(namespace: ResolveResult<'_>, name: &[u8]) -> XmpName {
+    let namespace: String = match namespace {
+      ResolveResult::Bound(namespace) => String::from_utf8_lossy(namespace.as_ref()).into_owned(),
+      _ => String::new(),
+    };
+    XmpName {
+      namespace,
+      name: String::from_utf8_lossy(name).into_owned(),
+    }
+  }
+}
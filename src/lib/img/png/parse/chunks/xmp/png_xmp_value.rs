@@ -0,0 +1,159 @@
+/// Namespace URIs of common XMP schemas
+pub struct XmpNamespace;
+
+impl XmpNamespace {
+  /// Dublin Core, describing titles, creators, descriptions and rights
+  pub const DC: &str = "http://purl.org/dc/elements/1.1/";
+
+  /// XMP basic schema, describing dates and the tool used to create a resource
+  pub const XMP: &str = "http://ns.adobe.com/xap/1.0/";
+
+  /// XMP rights management schema
+  pub const XMP_RIGHTS: &str = "http://ns.adobe.com/xap/1.0/rights/";
+
+  /// XMP media management schema
+  pub const XMP_MM: &str = "http://ns.adobe.com/xap/1.0/mm/";
+
+  /// Photoshop schema
+  pub const PHOTOSHOP: &str = "http://ns.adobe.com/photoshop/1.0/";
+
+  /// IPTC core schema
+  pub const IPTC_CORE: &str = "http://iptc.org/std/Iptc4xmpCore/1.0/xmlns/";
+
+  /// EXIF schema
+  pub const EXIF: &str = "http://ns.adobe.com/exif/1.0/";
+
+  /// TIFF schema
+  pub const TIFF: &str = "http://ns.adobe.com/tiff/1.0/";
+
+  /// RDF syntax, used to structure the packet
+  pub const RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+
+  /// XML namespace, defining the `xml:lang` attribute
+  pub const XML: &str = "http://www.w3.org/XML/1998/namespace";
+
+  /// Conventional prefix of a namespace
+  pub(crate) fn default_prefix(namespace: &str) -> Option<&'static str> {
+    let prefix: &str = match namespace {
+      Self::DC => "dc",
+      Self::XMP => "xmp",
+      Self::XMP_RIGHTS => "xmpRights",
+      Self::XMP_MM => "xmpMM",
+      Self::PHOTOSHOP => "photoshop",
+      Self::IPTC_CORE => "Iptc4xmpCore",
+      Self::EXIF => "exif",
+      Self::TIFF => "tiff",
+      Self::RDF => "rdf",
+      Self::XML => "xml",
+      _ => return None,
+    };
+    Some(prefix)
+  }
+}
+
+/// Name of an XMP property, qualified by the URI of its namespace
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct XmpName {
+  pub namespace: String,
+  pub name: String,
+}
+
+impl XmpName {
+  pub fn new(namespace: &str, name: &str) -> Self {
+    Self {
+      namespace: namespace.to_string(),
+      name: name.to_string(),
+    }
+  }
+}
+
+/// A property of XMP data
+#[derive(Debug, PartialEq, Clone)]
+pub struct XmpProperty {
+  pub name: XmpName,
+  pub value: XmpValue,
+}
+
+/// Value of an XMP property
+#[derive(Debug, PartialEq, Clone)]
+pub enum XmpValue {
+  Text(String),
+
+  /// Unordered array (`rdf:Bag`)
+  Bag(Vec<XmpValue>),
+
+  /// Ordered array (`rdf:Seq`)
+  Seq(Vec<XmpValue>),
+
+  /// Array of alternatives (`rdf:Alt`)
+  Alt(Vec<XmpValue>),
+
+  /// Alternatives of a text in different languages, as pairs of a RFC 3066
+  /// language tag and the text. The default text uses the `x-default` tag.
+  LangAlt(Vec<(String, String)>),
+
+  /// Structure holding properties of its own
+  Struct(Vec<XmpProperty>),
+}
+
+impl XmpValue {
+  /// Language tag of the default alternative of a text
+  pub const DEFAULT_LANGUAGE: &str = "x-default";
+
+  /// Obtain the text of a simple value, or the default text of alternatives
+  pub fn as_str(&self) -> Option<&str> {
+    match self {
+      Self::Text(text) => Some(text),
+      Self::LangAlt(_) => self.text_in(Self::DEFAULT_LANGUAGE),
+      _ => None,
+    }
+  }
+
+  /// Obtain the text in a given language, falling back to the default text,
+  /// then to the first alternative.
+  pub fn text_in(&self, language: &str) -> Option<&str> {
+    let Self::LangAlt(alternatives) = self else {
+      return self.as_str();
+    };
+
+    let find = |language: &str| {
+      alternatives
+        .iter()
+        .find(|(tag, _)| tag.eq_ignore_ascii_case(language))
+    };
+    find(language)
+      .or_else(|| find(Self::DEFAULT_LANGUAGE))
+      .or(alternatives.first())
+      .map(|(_, text)| text.as_str())
+  }
+
+  /// Obtain the items of an array
+  pub fn as_array(&self) -> Option<&[XmpValue]> {
+    match self {
+      Self::Bag(items) | Self::Seq(items) | Self::Alt(items) => Some(items),
+      _ => None,
+    }
+  }
+
+  /// Obtain the fields of a structure
+  pub fn as_struct(&self) -> Option<&[XmpProperty]> {
+    match self {
+      Self::Struct(fields) => Some(fields),
+      _ => None,
+    }
+  }
+
+  /// Visit the namespaces of the fields nested in the value
+  pub(crate) fn namespaces(&self, visit: &mut impl FnMut(&str)) {
+    match self {
+      Self::Bag(items) | Self::Seq(items) | Self::Alt(items) => {
+        items.iter().for_each(|item| item.namespaces(visit))
+      }
+      Self::Struct(fields) => fields.iter().for_each(|field| {
+        visit(&field.name.namespace);
+        field.value.namespaces(visit);
+      }),
+      Self::Text(_) | Self::LangAlt(_) => {}
+    }
+  }
+}
@@ -0,0 +1,156 @@
+use crate::lib::img::png::parse::chunks::xmp::png_xmp_value::{
+  XmpName, XmpNamespace, XmpProperty, XmpValue,
+};
+use quick_xml::escape::escape;
+use std::fmt::Write;
+
+/// Writer of the RDF/XML serialization of XMP data
+pub(crate) struct XmpWriter {
+  /// Prefixes of the namespaces used by the properties
+  prefixes: Vec<(String, String)>,
+  output: String,
+}
+
+impl XmpWriter {
+  /// Identifier of XMP packets, as given by the XMP specification
+  const PACKET_ID: &str = "W5M0MpCehiHzreSzNTczkc9d";
+
+  /// Create a writer, preferring the given prefixes for namespaces
+  pub(crate) fn new(properties: &[XmpProperty], preferred: &[(String, String)]) -> Self {
+    let mut writer: XmpWriter = Self {
+      prefixes: Vec::new(),
+      output: String::new(),
+    };
+
+    let mut namespaces: Vec<String> = Vec::new();
+    let mut visit = |namespace: &str| {
+      if !namespaces.iter().any(|n| n == namespace) {
+        namespaces.push(namespace.to_string());
+      }
+    };
+    for property in properties {
+      visit(&property.name.namespace);
+      property.value.namespaces(&mut visit);
+    }
+
+    for namespace in namespaces {
+      writer.add_prefix(&namespace, preferred);
+    }
+    writer
+  }
+
+  /// Assign a prefix to a namespace, which is the preferred one if it is not
+  /// already taken, then the conventional one, then a generated one.
+  fn add_prefix(&mut self, namespace: &str, preferred: &[(String, String)]) {
+    let taken = |prefix: &str, prefixes: &[(String, String)]| {
+      prefix == "x" || prefix == "rdf" || prefixes.iter().any(|(p, _)| p == prefix)
+    };
+
+    let candidates = preferred
+      .iter()
+      .filter(|(_, uri)| uri == namespace)
+      .map(|(prefix, _)| prefix.clone())
+      .chain(XmpNamespace::default_prefix(namespace).map(str::to_string))
+      .chain((1..).map(|n| format!("ns{n}")));
+
+    for prefix in candidates {
+      if !taken(&prefix, &self.prefixes) {
+        self.prefixes.push((prefix, namespace.to_string()));
+        return;
+      }
+    }
+  }
+
+  fn qualified_name(&self, name: &XmpName) -> String {
+    let prefix: &str = self
+      .prefixes
+      .iter()
+      .find(|(_, uri)| *uri == name.namespace)
+      .map(|(prefix, _)| prefix.as_str())
+      .unwrap_or_default();
+    format!("{prefix}:{}", name.name)
+  }
+
+  /// Write the properties as a complete packet
+  pub(crate) fn write(mut self, properties: &[XmpProperty]) -> String {
+    let _ = writeln!(
+      self.output,
+      "<?xpacket begin=\"\u{feff}\" id=\"{}\"?>",
+      Self::PACKET_ID
+    );
+    let _ = writeln!(self.output, "<x:xmpmeta xmlns:x=\"adobe:ns:meta/\">");
+    let _ = writeln!(
+      self.output,
+      " <rdf:RDF xmlns:rdf=\"{}\">",
+      XmpNamespace::RDF
+    );
+    let _ = write!(self.output, "  <rdf:Description rdf:about=\"\"");
+
+    for (prefix, namespace) in &self.prefixes {
+      let _ = write!(
+        self.output,
+        "\n    xmlns:{prefix}=\"{}\"",
+        escape(namespace.as_str())
+      );
+    }
+    let _ = writeln!(self.output, ">");
+
+    for property in properties {
+      let name: String = self.qualified_name(&property.name);
+      self.write_value(&name, &property.value, 3);
+    }
+
+    let _ = writeln!(self.output, "  </rdf:Description>");
+    let _ = writeln!(self.output, " </rdf:RDF>");
+    let _ = writeln!(self.output, "</x:xmpmeta>");
+    let _ = write!(self.output, "<?xpacket end=\"w\"?>");
+    self.output
+  }
+
+  /// Write a property element or an array item holding a value
+  fn write_value(&mut self, tag: &str, value: &XmpValue, depth: usize) {
+    let indent: String = " ".repeat(depth);
+
+    let (array, items) = match value {
+      XmpValue::Text(text) => {
+        let _ = writeln!(
+          self.output,
+          "{indent}<{tag}>{}</{tag}>",
+          escape(text.as_str())
+        );
+        return;
+      }
+      XmpValue::Struct(fields) => {
+        let _ = writeln!(self.output, "{indent}<{tag} rdf:parseType=\"Resource\">");
+        for field in fields {
+          let name: String = self.qualified_name(&field.name);
+          self.write_value(&name, &field.value, depth + 1);
+        }
+        let _ = writeln!(self.output, "{indent}</{tag}>");
+        return;
+      }
+      XmpValue::LangAlt(alternatives) => {
+        let _ = writeln!(self.output, "{indent}<{tag}>\n{indent} <rdf:Alt>");
+        for (language, text) in alternatives {
+          let _ = writeln!(
+            self.output,
+            "{indent}  <rdf:li xml:lang=\"{}\">{}</rdf:li>",
+            escape(language.as_str()),
+            escape(text.as_str())
+          );
+        }
+        let _ = writeln!(self.output, "{indent} </rdf:Alt>\n{indent}</{tag}>");
+        return;
+      }
+      XmpValue::Bag(items) => ("rdf:Bag", items),
+      XmpValue::Seq(items) => ("rdf:Seq", items),
+      XmpValue::Alt(items) => ("rdf:Alt", items),
+    };
+
+    let _ = writeln!(self.output, "{indent}<{tag}>\n{indent} <{array}>");
+    for item in items {
+      self.write_value("rdf:li", item, depth + 2);
+    }
+    let _ = writeln!(self.output, "{indent} </{array}>\n{indent}</{tag}>");
+  }
+}
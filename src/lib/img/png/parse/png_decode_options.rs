@@ -0,0 +1,164 @@
+use crate::lib::img::png::{
+  chunk::png_chunk_type::ChunkType,
+  parse::{
+    chunks::idat::png_pixel_format::{BitDepthHandling, ColorTarget, PixelLayout},
+    png_limits::Limits,
+  },
+};
+
+/// Policy applied to the CRC of the chunks that are read
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum CrcPolicy {
+  /// Do not verify the CRC of chunks
+  Ignore,
+
+  /// Verify the CRC of chunks. A mismatch in a critical chunk fails decoding,
+  /// and is handled like any other error in an ancillary chunk otherwise.
+  #[default]
+  Verify,
+}
+
+/// Options used to configure how a PNG image is decoded.
+#[derive(Debug, Clone)]
+pub struct DecodeOptions {
+  /// Escalate errors found in ancillary chunks instead of collecting them as
+  /// [warnings](crate::lib::img::png::parse::png_decode_warning::DecodeWarning).
+  pub(crate) strict: bool,
+
+  /// Resource limits enforced while decoding
+  pub(crate) limits: Limits,
+
+  /// Policy applied to the CRC of chunks
+  pub(crate) crc: CrcPolicy,
+
+  /// Color channels of the decoded pixels
+  pub(crate) color: ColorTarget,
+
+  /// Handling of 16-bit samples
+  pub(crate) bit_depth: BitDepthHandling,
+
+  /// Arrangement of the decoded pixels in memory
+  pub(crate) layout: PixelLayout,
+
+  /// Ancillary chunks to parse, or all of them when absent
+  pub(crate) ancillary_chunks: Option<Vec<ChunkType>>,
+
+  /// Keep the data of chunks that are not parsed into the metadata
+  pub(crate) keep_unknown_chunks: bool,
+
+  /// Transform the pixels according to the EXIF orientation
+  pub(crate) apply_orientation: bool,
+
+  /// Number of threads decoding the image data, where 0 uses every available
+  /// core
+  #[cfg(feature = "parallel")]
+  pub(crate) threads: usize,
+}
+
+#[cfg_attr(not(feature = "parallel"), allow(clippy::derivable_impls))]
+impl Default for DecodeOptions {
+  fn default() -> Self {
+    Self {
+      strict: false,
+      limits: Limits::default(),
+      crc: CrcPolicy::default(),
+      color: ColorTarget::default(),
+      bit_depth: BitDepthHandling::default(),
+      layout: PixelLayout::default(),
+      ancillary_chunks: None,
+      keep_unknown_chunks: false,
+      apply_orientation: false,
+      #[cfg(feature = "parallel")]
+      threads: 1,
+    }
+  }
+}
+
+impl DecodeOptions {
+  /// Create the default decoding options
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Set whether ancillary chunks that fail to parse should fail decoding
+  pub fn strict(mut self, strict: bool) -> Self {
+    self.strict = strict;
+    self
+  }
+
+  /// Set the [resource limits](Limits) enforced while decoding
+  pub fn limits(mut self, limits: Limits) -> Self {
+    self.limits = limits;
+    self
+  }
+
+  /// Set the [policy](CrcPolicy) applied to the CRC of chunks
+  pub fn crc(mut self, policy: CrcPolicy) -> Self {
+    self.crc = policy;
+    self
+  }
+
+  /// Set the [color channels](ColorTarget) the pixels are converted to
+  pub fn color(mut self, target: ColorTarget) -> Self {
+    self.color = target;
+    self
+  }
+
+  /// Set the [handling](BitDepthHandling) of 16-bit samples
+  pub fn bit_depth(mut self, handling: BitDepthHandling) -> Self {
+    self.bit_depth = handling;
+    self
+  }
+
+  /// Set the [arrangement](PixelLayout) of the decoded pixels in memory
+  pub fn layout(mut self, layout: PixelLayout) -> Self {
+    self.layout = layout;
+    self
+  }
+
+  /// Only parse the given ancillary chunks, skipping every other one
+  pub fn ancillary_chunks(mut self, chunks: impl IntoIterator<Item = ChunkType>) -> Self {
+    self.ancillary_chunks = Some(chunks.into_iter().collect());
+    self
+  }
+
+  /// Set whether the data of chunks that are not parsed should be kept in the
+  /// metadata
+  pub fn keep_unknown_chunks(mut self, keep: bool) -> Self {
+    self.keep_unknown_chunks = keep;
+    self
+  }
+
+  /// Set whether the pixels should be transformed to display upright according
+  /// to the orientation stored in the EXIF data, which is then reset.
+  pub fn apply_orientation(mut self, apply: bool) -> Self {
+    self.apply_orientation = apply;
+    self
+  }
+
+  /// Set the number of threads decoding the image data, where 1 (the default)
+  /// decodes on the calling thread and 0 uses every available core, like
+  /// [encoding](crate::lib::img::png::encode::png_encode_options::EncodeOptions::threads)
+  #[cfg(feature = "parallel")]
+  pub fn threads(mut self, threads: usize) -> Self {
+    self.threads = threads;
+    self
+  }
+
+  /// Number of threads decoding the image data
+  #[cfg(feature = "parallel")]
+  pub(crate) fn thread_count(&self) -> usize {
+    match self.threads {
+      0 => std::thread::available_parallelism().map_or(1, |threads| threads.get()),
+      threads => threads,
+    }
+  }
+
+  /// Determine if a chunk should be parsed
+  pub(crate) fn parses(&self, r#type: ChunkType) -> bool {
+    match &self.ancillary_chunks {
+      Some(chunks) if !r#type.is_critical() => chunks.contains(&r#type),
+      _ => true,
+    }
+  }
+}
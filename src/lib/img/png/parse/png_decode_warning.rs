@@ -0,0 +1,23 @@
+use crate::lib::{img::png::chunk::png_chunk_type::ChunkType, util::err::rsm_error::RSMError};
+use std::fmt::{Display, Formatter, Result};
+
+/// Ancillary chunk that was present in the datastream but was rejected while
+/// decoding.
+#[derive(Debug)]
+pub struct DecodeWarning {
+  /// The [type](ChunkType) of the rejected chunk
+  pub chunk: ChunkType,
+
+  /// Position of the rejected chunk in the datastream
+  pub offset: usize,
+
+  /// The reason for which the chunk was rejected
+  pub reason: RSMError,
+}
+
+impl Display for DecodeWarning {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    let name = String::from_utf8_lossy(&self.chunk.as_bytes()).into_owned();
+    write!(f, "{name} chunk at offset {}: {}", self.offset, self.reason)
+  }
+}
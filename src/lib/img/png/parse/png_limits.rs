@@ -0,0 +1,45 @@
+/// Resource limits enforced while decoding. Limits are checked before any
+/// allocation depending on them is made, in which case decoding fails with
+/// [`RSMError::LimitExceeded`](crate::lib::util::err::rsm_error::RSMError).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+  /// Maximum amount of pixels (width × height) declared by the image header
+  pub max_pixels: u64,
+
+  /// Maximum amount of bytes allocated to decode the image data
+  pub max_alloc: u64,
+
+  /// Maximum size in bytes of decompressed text or ICC profile data
+  pub max_metadata_size: usize,
+
+  /// Maximum amount of chunks read from the datastream
+  pub max_chunks: usize,
+
+  /// Maximum length of the data of a single chunk
+  pub max_chunk_length: u32,
+}
+
+impl Default for Limits {
+  fn default() -> Self {
+    Self {
+      max_pixels: 1 << 28,
+      max_alloc: 1 << 32,
+      max_metadata_size: 1 << 24,
+      max_chunks: 1 << 20,
+      max_chunk_length: i32::MAX as u32,
+    }
+  }
+}
+
+impl Limits {
+  /// Limits that only keep the bounds defined by the specification
+  pub fn unlimited() -> Self {
+    Self {
+      max_pixels: u64::MAX,
+      max_alloc: u64::MAX,
+      max_metadata_size: usize::MAX,
+      max_chunks: usize::MAX,
+      max_chunk_length: i32::MAX as u32,
+    }
+  }
+}
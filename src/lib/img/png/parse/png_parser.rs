@@ -1,7 +1,16 @@
 use crate::lib::{
   img::png::{
     chunk::{png_chunk::Chunk, png_chunk_type::ChunkType},
-    parse::states::png_state::{PNGState, ReadSignature},
+    parse::{
+      chunks::ihdr::png_header::PNGHeader,
+      png_decode_options::{CrcPolicy, DecodeOptions},
+      png_decode_warning::DecodeWarning,
+      png_limits::Limits,
+      states::{
+        data::png_metadata::PNGMetadata,
+        png_state::{PNGState, ReadSignature},
+      },
+    },
     reader::png_reader::PNGReader,
   },
   util::err::rsm_error::RSMError,
@@ -11,18 +20,32 @@ use std::marker::PhantomData;
 /// PNG image parser.
 pub struct PNGParser<'p, S: PNGState> {
   pub(crate) reader: PNGReader<'p>,
+  pub(crate) options: DecodeOptions,
+  pub(crate) warnings: Vec<DecodeWarning>,
+  pub(crate) chunks: usize,
   pub(crate) _state: PhantomData<S>,
 }
 
 impl<'p, S: PNGState> PNGParser<'p, S> {
   /// Read a chunk
   pub(crate) fn read_chunk(&mut self) -> Result<Chunk<'p>, RSMError> {
+    let offset: usize = self.reader.ptr;
+    let limits: &Limits = &self.options.limits;
+
+    self.chunks += 1;
+    if self.chunks > limits.max_chunks {
+      return Err(RSMError::LimitExceeded);
+    }
+
     let length_bytes: [u8; 4] = *self.reader.take_sized::<4>()?;
     let length: u32 = u32::from_be_bytes(length_bytes);
 
     if length > (i32::MAX as u32) {
       return Err(RSMError::OutOfBounds);
     }
+    if length > limits.max_chunk_length {
+      return Err(RSMError::LimitExceeded);
+    }
 
     let chunk_type_bytes: [u8; 4] = *self.reader.take_sized::<4>()?;
     let chunk_u32: u32 = u32::from_be_bytes(chunk_type_bytes);
@@ -31,20 +54,73 @@ impl<'p, S: PNGState> PNGParser<'p, S> {
     let data: &[u8] = self.reader.take(length as usize)?;
     let crc: [u8; 4] = *self.reader.take_sized::<4>()?;
 
-    Ok(Chunk {
+    let chunk: Chunk<'p> = Chunk {
+      offset,
       length,
       r#type,
       data,
       crc,
-    })
+    };
+
+    if self.options.crc == CrcPolicy::Verify && r#type.is_critical() && !chunk.is_crc_valid() {
+      return Err(RSMError::InvalidChecksum);
+    }
+    Ok(chunk)
+  }
+
+  /// Store the data of a chunk in the metadata. Errors from ancillary chunks
+  /// are collected as warnings, unless the parser is strict.
+  pub(crate) fn set_data(
+    &mut self,
+    chunk: Chunk<'_>,
+    meta: &mut PNGMetadata,
+    header: &PNGHeader,
+  ) -> Result<(), RSMError> {
+    let (r#type, offset) = (chunk.r#type, chunk.offset);
+    if !self.options.parses(r#type) {
+      return Ok(());
+    }
+
+    let result: Result<(), RSMError> =
+      if self.options.crc == CrcPolicy::Verify && !chunk.is_crc_valid() {
+        Err(RSMError::InvalidChecksum)
+      } else {
+        meta.set_data(chunk, header, &self.options, self.reader.bytes)
+      };
+
+    match result {
+      Err(reason) if !r#type.is_critical() && !self.options.strict => {
+        self.warnings.push(DecodeWarning {
+          chunk: r#type,
+          offset,
+          reason,
+        });
+        Ok(())
+      }
+      result => result,
+    }
+  }
+
+  /// Move the parser to the next state
+  pub(crate) fn transition<T: PNGState>(self) -> PNGParser<'p, T> {
+    PNGParser {
+      reader: self.reader,
+      options: self.options,
+      warnings: self.warnings,
+      chunks: self.chunks,
+      _state: PhantomData,
+    }
   }
 }
 
 impl<'p> PNGParser<'p, ReadSignature> {
   /// Create a new PNG image parser
-  pub fn new(bytes: &'p [u8]) -> Self {
+  pub fn new(bytes: &'p [u8], options: DecodeOptions) -> Self {
     Self {
       reader: PNGReader::new(bytes),
+      options,
+      warnings: Vec::new(),
+      chunks: 0,
       _state: PhantomData,
     }
   }
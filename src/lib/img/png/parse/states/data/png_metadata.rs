@@ -1,19 +1,24 @@
-use crate::lib::img::png::parse::chunks::{
-  actl::png_animation_control::AnimationControl,
-  cabx::png_attribution_manifest::AttributionManifest, chrm::png_chromaticities::Chromaticities,
-  cicp::png_code_points::CodePoints, clli::png_light_level::ContentLightLevel,
-  exif::png_exif::PNGExifData, fctl::png_fctl_frame::FrameControl,
-  iccp::png_icc_profile::ICCProfile, mdcv::png_color_volume::ColorVolume,
-  phys::png_physical_dimensions::PhysicalDimensions, srgb::png_rendering_intent::RenderingIntent,
-  text::png_text::Text, time::png_time::ModificationTime,
+use crate::lib::img::png::{
+  chunk::png_unknown_chunk::UnknownChunk,
+  parse::chunks::{
+    actl::png_animation_control::AnimationControl,
+    cabx::png_content_credentials::ContentCredentials, chrm::png_chromaticities::Chromaticities,
+    cicp::png_code_points::CodePoints, clli::png_light_level::ContentLightLevel,
+    exif::png_exif::PNGExifData, fctl::png_fctl_frame::FrameControl,
+    iccp::png_icc_profile::ICCProfile, idot::png_image_division::ImageDivision,
+    mdcv::png_color_volume::ColorVolume, phys::png_physical_dimensions::PhysicalDimensions,
+    plte::png_palette::Palette, srgb::png_rendering_intent::RenderingIntent, text::png_text::Text,
+    time::png_time::ModificationTime, trns::png_transparency::Transparency,
+    xmp::png_xmp::PNGXmpData,
+  },
 };
 
 #[derive(Default, Debug)]
 pub struct PNGMetadata {
   pub animation_control: Option<AnimationControl>,
-  pub attribution_manifests: Option<Vec<AttributionManifest>>,
   pub background_bytes: Option<Vec<u8>>,
   pub code_points: Option<CodePoints>,
+  pub content_credentials: Option<ContentCredentials>,
   pub exif: Option<PNGExifData>,
   pub frames: Option<Vec<FrameControl>>,
   pub gamma: Option<f32>,
@@ -21,12 +26,15 @@ pub struct PNGMetadata {
   pub color_volume: Option<ColorVolume>,
   pub histogram: Option<Vec<u16>>,
   pub icc_profile: Option<ICCProfile>,
+  pub image_divisions: Option<Vec<ImageDivision>>,
   pub light_level: Option<ContentLightLevel>,
   pub modification_time: Option<ModificationTime>,
-  pub palette: Option<Vec<[u8; 3]>>,
+  pub palette: Option<Palette>,
   pub physical_dimensions: Option<PhysicalDimensions>,
   pub rendering_intent: Option<RenderingIntent>,
   pub significant_bits: Option<Vec<u8>>,
   pub text_entries: Option<Vec<Text>>,
-  pub transparency_bytes: Option<Vec<u8>>,
+  pub transparency: Option<Transparency>,
+  pub unknown_chunks: Option<Vec<UnknownChunk>>,
+  pub xmp: Option<PNGXmpData>,
 }
@@ -0,0 +1,104 @@
+/// Known kinds of payloads that may be appended after the IEND chunk
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TrailingPayload {
+  /// ZIP archive, which is commonly used to build PNG/ZIP polyglots
+  Zip,
+
+  /// Another PNG image
+  Png,
+
+  /// JPEG image
+  Jpeg,
+
+  /// Data that could not be identified
+  Unknown,
+}
+
+/// Bytes found after the IEND chunk
+#[derive(Debug, PartialEq, Clone)]
+pub struct TrailingData {
+  /// Position of the first trailing byte in the datastream
+  pub offset: usize,
+
+  /// Amount of trailing bytes
+  pub length: usize,
+
+  /// The [kind of payload](TrailingPayload) that was detected
+  pub payload: TrailingPayload,
+}
+
+impl TrailingData {
+  /// PNG image signature
+  const PNG_SIGNATURE: [u8; 8] = [0x89, 0x50, 0x4e, 0x47, 0xd, 0xa, 0x1a, 0xa];
+
+  /// JPEG start of image marker, followed by the marker of the next segment
+  const JPEG_SIGNATURE: [u8; 3] = [0xff, 0xd8, 0xff];
+
+  /// ZIP local file header signature
+  const ZIP_LOCAL_FILE_SIGNATURE: [u8; 4] = *b"PK\x03\x04";
+
+  /// ZIP end of central directory signature. Archives are read from their end,
+  /// so this record identifies them even when the local files are not at the
+  /// start of the trailing bytes.
+  const ZIP_END_SIGNATURE: [u8; 4] = *b"PK\x05\x06";
+
+  /// Create trailing data from the bytes that follow the IEND chunk
+  pub(crate) fn new(offset: usize, bytes: &[u8]) -> Self {
+    Self {
+      offset,
+      length: bytes.len(),
+      payload: Self::detect(bytes),
+    }
+  }
+
+  /// Detect the kind of payload within trailing bytes
+  fn detect(bytes: &[u8]) -> TrailingPayload {
+    if bytes.starts_with(&Self::PNG_SIGNATURE) {
+      TrailingPayload::Png
+    } else if bytes.starts_with(&Self::JPEG_SIGNATURE) {
+      TrailingPayload::Jpeg
+    } else if bytes.starts_with(&Self::ZIP_LOCAL_FILE_SIGNATURE)
+      || bytes
+        .windows(4)
+        .any(|window| window == Self::ZIP_END_SIGNATURE)
+    {
+      TrailingPayload::Zip
+    } else {
+      TrailingPayload::Unknown
+    }
+  }
+
+  /// Strip the trailing bytes from the datastream they were found in
+  pub fn strip<'a>(&self, data: &'a [u8]) -> &'a [u8] {
+    &data[..self.offset.min(data.len())]
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_detect_payloads() {
+    let payloads: [(&[u8], TrailingPayload); 5] = [
+      (b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR", TrailingPayload::Png),
+      (b"\xff\xd8\xff\xe0\0\x10JFIF", TrailingPayload::Jpeg),
+      (b"PK\x03\x04\x14\0", TrailingPayload::Zip),
+      (b"\0\0\0PK\x05\x06\0\0", TrailingPayload::Zip),
+      (b"\0\0\0\0", TrailingPayload::Unknown),
+    ];
+
+    for (bytes, payload) in payloads {
+      assert_eq!(TrailingData::new(0, bytes).payload, payload);
+    }
+  }
+
+  #[test]
+  fn test_strip() {
+    let data: [u8; 6] = [1, 2, 3, 4, 5, 6];
+    let trailing: TrailingData = TrailingData::new(4, &data[4..]);
+
+    assert_eq!(trailing.length, 2);
+    assert_eq!(trailing.strip(&data), [1, 2, 3, 4]);
+  }
+}
@@ -1,18 +1,33 @@
 use crate::lib::{
   img::png::{
-    chunk::{png_chunk::Chunk, png_chunk_type::ChunkType},
+    chunk::{png_chunk::Chunk, png_chunk_type::ChunkType, png_unknown_chunk::UnknownChunk},
     parse::{
       chunks::{
-        actl::handle_actl::handle_actl, cabx::handle_cabx::handle_cabx,
-        chrm::handle_chrm::handle_chrm, cicp::handle_cicp::handle_cicp,
-        clli::handle_clli::handle_clli, exif::handle_exif::handle_exif,
-        fctl::handle_fctl::handle_fctl, handle_bkgd::handle_bkgd, handle_gama::handle_gama,
-        handle_hist::handle_hist, handle_plte::handle_plte, handle_sbit::handle_sbit,
-        handle_trns::handle_trns, handle_ztxt::handle_ztxt, iccp::handle_iccp::handle_iccp,
-        ihdr::png_header::PNGHeader, mdcv::handle_mdcv::handle_mdcv,
-        phys::handle_phys::handle_phys, srgb::handle_srgb::handle_srgb,
-        text::handle_text::handle_text, time::handle_time::handle_time,
+        actl::handle_actl::handle_actl,
+        cabx::handle_cabx::handle_cabx,
+        chrm::handle_chrm::handle_chrm,
+        cicp::handle_cicp::handle_cicp,
+        clli::handle_clli::handle_clli,
+        exif::handle_exif::handle_exif,
+        fctl::handle_fctl::handle_fctl,
+        handle_bkgd::handle_bkgd,
+        handle_gama::handle_gama,
+        handle_hist::handle_hist,
+        handle_sbit::handle_sbit,
+        handle_ztxt::handle_ztxt,
+        iccp::handle_iccp::handle_iccp,
+        idot::handle_idot::handle_idot,
+        ihdr::png_header::PNGHeader,
+        mdcv::handle_mdcv::handle_mdcv,
+        phys::handle_phys::handle_phys,
+        plte::handle_plte::handle_plte,
+        srgb::handle_srgb::handle_srgb,
+        text::{handle_itxt::handle_itxt, handle_text::handle_text},
+        time::handle_time::handle_time,
+        trns::handle_trns::handle_trns,
+        xmp::handle_xmp::handle_xmp,
       },
+      png_decode_options::DecodeOptions,
       states::data::png_metadata::PNGMetadata,
     },
   },
@@ -20,89 +35,91 @@ use crate::lib::{
 };
 
 impl PNGMetadata {
-  pub(crate) fn set_data(&mut self, chunk: Chunk<'_>, header: &PNGHeader) -> Result<(), RSMError> {
+  /// Store the data of a chunk, which belongs to the given image bytes. Errors
+  /// are returned for any chunk that fails to parse, which lets the parser
+  /// decide whether to escalate them.
+  pub(crate) fn set_data(
+    &mut self,
+    chunk: Chunk<'_>,
+    header: &PNGHeader,
+    options: &DecodeOptions,
+    image: &[u8],
+  ) -> Result<(), RSMError> {
+    let limits = &options.limits;
+
     match chunk.r#type {
       ChunkType::acTL => {
-        if let Ok(control) = chunk.parse_data_sized::<8, _, _>(|&data| handle_actl(data)) {
-          self.animation_control = control;
-        }
+        self.animation_control = chunk.parse_data_sized::<8, _, _>(|&data| handle_actl(data))?;
       }
 
       ChunkType::bKGD => {
-        if let Ok(background) = chunk.parse_data(|data| handle_bkgd(data, header.color_type)) {
-          self.background_bytes = Some(background.to_vec());
-        }
+        let background = chunk.parse_data(|data| handle_bkgd(data, header.color_type))?;
+        self.background_bytes = Some(background.to_vec());
       }
 
       ChunkType::caBX => {
-        if let Ok(manifest) = chunk.parse_data(handle_cabx) {
-          self
-            .attribution_manifests
-            .get_or_insert(Vec::new())
-            .extend(manifest.unwrap())
-        }
+        let credentials = chunk.parse_data(|data| handle_cabx(data, image))?;
+        self.content_credentials = Some(credentials);
       }
 
       ChunkType::cHRM => {
-        if let Ok(chromacities) = chunk.parse_data_sized::<32, _, _>(|&data| handle_chrm(data)) {
-          self.chromaticities = chromacities;
-        }
+        self.chromaticities = chunk.parse_data_sized::<32, _, _>(|&data| handle_chrm(data))?;
       }
 
       ChunkType::cICP => {
-        if let Ok(code_points) = chunk.parse_data_sized::<4, _, _>(|&data| handle_cicp(data)) {
-          self.code_points = Some(code_points);
-        }
+        let code_points = chunk.parse_data_sized::<4, _, _>(|&data| handle_cicp(data))?;
+        self.code_points = Some(code_points);
       }
 
       ChunkType::cLLI => {
-        if let Ok(light_level) = chunk.parse_data_sized::<8, _, _>(|&data| handle_clli(data)) {
-          self.light_level = light_level;
-        }
+        self.light_level = chunk.parse_data_sized::<8, _, _>(|&data| handle_clli(data))?;
       }
 
       ChunkType::eXIf => {
-        if let Ok(exif) = chunk.parse_data(handle_exif) {
-          self.exif = Some(exif);
-        }
+        let exif = chunk.parse_data(handle_exif)?;
+        self.exif = Some(exif);
       }
 
       ChunkType::fcTL => {
-        if let Ok(frame) = chunk.parse_data_sized::<26, _, _>(|&data| handle_fctl(data, header)) {
-          self.frames.get_or_insert(Vec::new()).push(frame.unwrap());
-        }
+        let frame = chunk.parse_data_sized::<26, _, _>(|&data| handle_fctl(data, header))?;
+        self.frames.get_or_insert(Vec::new()).extend(frame);
       }
 
       ChunkType::gAMA => {
-        if let Ok(gamma) = chunk.parse_data_sized::<4, _, _>(|&data| handle_gama(data)) {
-          self.gamma = Some(gamma);
-        }
+        let gamma = chunk.parse_data_sized::<4, _, _>(|&data| handle_gama(data))?;
+        self.gamma = Some(gamma);
       }
 
       ChunkType::hIST => {
-        if let Ok(histogram) = chunk.parse_data(handle_hist) {
-          self.histogram = histogram;
-        }
+        self.histogram = chunk.parse_data(handle_hist)?;
       }
 
       ChunkType::iCCP => {
-        if let Ok(profile) = chunk.parse_data(handle_iccp) {
-          self.icc_profile = Some(profile);
+        let profile = chunk.parse_data(|data| handle_iccp(data, limits))?;
+        self.icc_profile = Some(profile);
+      }
+
+      ChunkType::iDOT => {
+        let divisions = chunk.parse_data(|data| handle_idot(data, chunk.offset, *header.height))?;
+        self.image_divisions = Some(divisions);
+      }
+
+      ChunkType::iTXt => {
+        let text = chunk.parse_data(|data| handle_itxt(data, limits))?;
+        match handle_xmp(&text)? {
+          Some(xmp) => self.xmp = Some(xmp),
+          None => self.text_entries.get_or_insert(Vec::new()).push(text),
         }
       }
 
       ChunkType::mDCV => {
-        if let Ok(color_volume) = chunk.parse_data_sized::<24, _, _>(|&data| handle_mdcv(data)) {
-          self.color_volume = Some(color_volume);
-        }
+        let color_volume = chunk.parse_data_sized::<24, _, _>(|&data| handle_mdcv(data))?;
+        self.color_volume = Some(color_volume);
       }
 
       ChunkType::pHYs => {
-        if let Ok(physical_dimensions) =
-          chunk.parse_data_sized::<9, _, _>(|&data| handle_phys(data))
-        {
-          self.physical_dimensions = physical_dimensions
-        }
+        let dimensions = chunk.parse_data_sized::<9, _, _>(|&data| handle_phys(data))?;
+        self.physical_dimensions = Some(dimensions);
       }
 
       ChunkType::PLTE => {
@@ -111,43 +128,46 @@ impl PNGMetadata {
       }
 
       ChunkType::sBIT => {
-        if let Ok(bits) = chunk.parse_data(|data| handle_sbit(data, header.color_type)) {
-          self.significant_bits = Some(bits.to_vec());
-        }
+        let bits = chunk.parse_data(|data| handle_sbit(data, header.color_type))?;
+        self.significant_bits = Some(bits.to_vec());
       }
 
       ChunkType::sRGB => {
-        if let Ok(intent) = chunk.parse_data_sized::<1, _, _>(|&data| handle_srgb(data)) {
-          self.rendering_intent = Some(intent);
-        }
+        let intent = chunk.parse_data_sized::<1, _, _>(|&data| handle_srgb(data))?;
+        self.rendering_intent = Some(intent);
       }
 
       ChunkType::tEXt => {
-        if let Ok(text) = chunk.parse_data(handle_text) {
-          self.text_entries.get_or_insert(Vec::new()).push(text);
-        }
+        let text = chunk.parse_data(handle_text)?;
+        self.text_entries.get_or_insert(Vec::new()).push(text);
       }
 
       ChunkType::tIME => {
-        if let Ok(modification_time) = chunk.parse_data_sized::<7, _, _>(|&data| handle_time(data))
-        {
-          self.modification_time = modification_time;
-        }
+        let time = chunk.parse_data_sized::<7, _, _>(|&data| handle_time(data))?;
+        self.modification_time = Some(time);
       }
 
       ChunkType::tRNS => {
-        if let Ok(transparency) = chunk.parse_data(|data| handle_trns(data, header.color_type)) {
-          self.transparency_bytes = Some(transparency.to_vec());
-        }
+        let palette = self.palette.as_ref();
+        let transparency =
+          chunk.parse_data(|data| handle_trns(data, header.color_type, palette))?;
+        self.transparency = Some(transparency);
       }
 
       ChunkType::zTXt => {
-        if let Ok(text) = chunk.parse_data(handle_ztxt) {
-          self.text_entries.get_or_insert(Vec::new()).push(text);
-        }
+        let text = chunk.parse_data(|data| handle_ztxt(data, limits))?;
+        self.text_entries.get_or_insert(Vec::new()).push(text);
       }
 
-      _ => {}
+      _ => {
+        if options.keep_unknown_chunks {
+          let unknown: UnknownChunk = UnknownChunk {
+            r#type: chunk.r#type,
+            data: chunk.data.to_vec(),
+          };
+          self.unknown_chunks.get_or_insert(Vec::new()).push(unknown);
+        }
+      }
     }
     Ok(())
   }
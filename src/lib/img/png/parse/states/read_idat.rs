@@ -1,11 +1,11 @@
-use std::marker::PhantomData;
-
 use crate::lib::{
   img::png::{
     chunk::{png_chunk::Chunk, png_chunk_type::ChunkType},
     parse::{
       chunks::{
-        idat::{handle_idat::handle_idat, png_pixel_data::PixelData},
+        idat::{
+          handle_idat::handle_idat, png_image_stream::ImageStream, png_pixel_data::PixelData,
+        },
         ihdr::png_header::PNGHeader,
       },
       png_parser::PNGParser,
@@ -19,35 +19,30 @@ use crate::lib::{
 };
 
 impl<'p> PNGParser<'p, ReadIDAT> {
+  /// Read the IDAT (Image data) chunks, which also yields the first chunk that
+  /// follows them.
   pub(crate) fn read_idat(
     mut self,
     first: &Chunk<'_>,
     header: &PNGHeader,
-    meta: &mut PNGMetadata,
-  ) -> Result<(PNGParser<'p, ReadPostIDAT>, PixelData), RSMError> {
-    let mut idat_bytes: Vec<u8> = Vec::new();
-    idat_bytes.extend_from_slice(first.data);
+    meta: &PNGMetadata,
+  ) -> Result<(PNGParser<'p, ReadPostIDAT>, PixelData, Chunk<'p>), RSMError> {
+    // The data of each chunk is kept in place and inflated as one stream
+    let mut stream: ImageStream<'_> = ImageStream::default();
+    stream.push(first.data, first.offset);
 
     loop {
-      let chunk: Chunk<'_> = self.read_chunk()?;
+      let chunk: Chunk<'p> = self.read_chunk()?;
 
       match chunk.r#type {
-        ChunkType::IDAT => idat_bytes.extend_from_slice(chunk.data),
+        ChunkType::IDAT => stream.push(chunk.data, chunk.offset),
 
         ChunkType::IHDR => return Err(RSMError::InvalidContent),
         ChunkType::PLTE => return Err(RSMError::InvalidContent),
 
         _ => {
-          let pixel_data: PixelData = handle_idat(&idat_bytes, header, meta)?;
-          meta.set_data(chunk, header)?;
-
-          return Ok((
-            PNGParser {
-              reader: self.reader,
-              _state: PhantomData,
-            },
-            pixel_data,
-          ));
+          let pixel_data: PixelData = handle_idat(&stream, header, meta, &self.options)?;
+          return Ok((self.transition(), pixel_data, chunk));
         }
       }
     }
@@ -0,0 +1,38 @@
+use crate::lib::{
+  img::png::{
+    chunk::{png_chunk::Chunk, png_chunk_type::ChunkType},
+    parse::{
+      png_decode_warning::DecodeWarning,
+      png_parser::PNGParser,
+      states::png_state::{ReadIEND, ReadPostIEND},
+    },
+  },
+  util::err::rsm_error::RSMError,
+};
+
+impl<'p> PNGParser<'p, ReadIEND> {
+  /// Validate the IEND (Image trailer) chunk, which is absent when the
+  /// datastream was truncated.
+  pub(crate) fn read_iend(
+    mut self,
+    iend: Option<Chunk<'p>>,
+  ) -> Result<PNGParser<'p, ReadPostIEND>, RSMError> {
+    match iend {
+      Some(chunk) if !chunk.data.is_empty() => {
+        if self.options.strict {
+          return Err(RSMError::InvalidLength);
+        }
+        self.warnings.push(DecodeWarning {
+          chunk: ChunkType::IEND,
+          offset: chunk.offset,
+          reason: RSMError::InvalidLength,
+        });
+      }
+      Some(_) => {}
+
+      // Nothing can be read past a truncated datastream
+      None => self.reader.ptr = self.reader.bytes.len(),
+    }
+    Ok(self.transition())
+  }
+}
@@ -1,5 +1,3 @@
-use std::marker::PhantomData;
-
 use crate::lib::{
   img::png::{
     chunk::{png_chunk::Chunk, png_chunk_type::ChunkType},
@@ -21,12 +19,11 @@ impl<'p> PNGParser<'p, ReadIHDR> {
     }
     let header: PNGHeader = chunk.parse_data_sized::<13, _, _>(|&data| handle_ihdr(data))?;
 
-    Ok((
-      PNGParser {
-        reader: self.reader,
-        _state: PhantomData,
-      },
-      header,
-    ))
+    let pixels: u64 = *header.width as u64 * *header.height as u64;
+    if pixels > self.options.limits.max_pixels {
+      return Err(RSMError::LimitExceeded);
+    }
+
+    Ok((self.transition(), header))
   }
 }
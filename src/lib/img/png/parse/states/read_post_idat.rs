@@ -1,10 +1,9 @@
-use std::marker::PhantomData;
-
 use crate::lib::{
   img::png::{
     chunk::{png_chunk::Chunk, png_chunk_type::ChunkType},
     parse::{
       chunks::ihdr::png_header::PNGHeader,
+      png_decode_warning::DecodeWarning,
       png_parser::PNGParser,
       states::{
         data::png_metadata::PNGMetadata,
@@ -16,22 +15,37 @@ use crate::lib::{
 };
 
 impl<'p> PNGParser<'p, ReadPostIDAT> {
+  /// Read the chunks following the IDAT (Image data) chunks, starting with the
+  /// first one that was read after them, until IEND is reached. The IEND chunk
+  /// is absent when the datastream was truncated.
   pub(crate) fn read_post_idat(
     mut self,
+    first: Chunk<'p>,
     meta: &mut PNGMetadata,
     header: &PNGHeader,
-  ) -> Result<PNGParser<'p, ReadIEND>, RSMError> {
-    loop {
-      let next: Chunk<'_> = self.read_chunk()?;
+  ) -> Result<(PNGParser<'p, ReadIEND>, Option<Chunk<'p>>), RSMError> {
+    let mut next: Chunk<'p> = first;
 
+    loop {
       match next.r#type {
-        ChunkType::IEND => {
-          return Ok(PNGParser {
-            reader: self.reader,
-            _state: PhantomData,
+        ChunkType::IEND => return Ok((self.transition(), Some(next))),
+        _ => self.set_data(next, meta, header)?,
+      };
+
+      // The image data is already decoded at this point, so a truncated
+      // datastream is only reported as a missing IEND chunk.
+      let offset: usize = self.reader.ptr;
+      next = match self.read_chunk() {
+        Ok(chunk) => chunk,
+        Err(reason) if !self.options.strict => {
+          self.warnings.push(DecodeWarning {
+            chunk: ChunkType::IEND,
+            offset,
+            reason,
           });
+          return Ok((self.transition(), None));
         }
-        _ => meta.set_data(next, header)?,
+        Err(reason) => return Err(reason),
       };
     }
   }
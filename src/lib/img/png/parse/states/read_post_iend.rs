@@ -0,0 +1,18 @@
+use crate::lib::img::png::parse::{
+  png_parser::PNGParser,
+  states::{data::png_trailing_data::TrailingData, png_state::ReadPostIEND},
+};
+
+impl<'p> PNGParser<'p, ReadPostIEND> {
+  /// Read the bytes that follow the IEND (Image trailer) chunk, if any
+  pub(crate) fn read_post_iend(&self) -> Option<TrailingData> {
+    let offset: usize = self.reader.ptr;
+    let bytes: &[u8] = self.reader.bytes.get(offset..)?;
+
+    if bytes.is_empty() {
+      None
+    } else {
+      Some(TrailingData::new(offset, bytes))
+    }
+  }
+}
@@ -1,5 +1,3 @@
-use std::marker::PhantomData;
-
 use crate::lib::{
   img::png::{
     chunk::{png_chunk::Chunk, png_chunk_type::ChunkType},
@@ -25,19 +23,11 @@ impl<'p> PNGParser<'p, ReadPostIHDR> {
     loop {
       let chunk: Chunk<'p> = self.read_chunk()?;
       match chunk.r#type {
-        ChunkType::IDAT => {
-          let state: PNGParser<'p, ReadIDAT> = PNGParser {
-            reader: self.reader,
-            _state: PhantomData,
-          };
-          return Ok((state, meta, chunk));
-        }
+        ChunkType::IDAT => return Ok((self.transition(), meta, chunk)),
 
         ChunkType::IHDR => return Err(RSMError::InvalidContent),
         ChunkType::IEND => return Err(RSMError::InvalidContent),
-        _ => {
-          meta.set_data(chunk, header)?;
-        }
+        _ => self.set_data(chunk, &mut meta, header)?,
       }
     }
   }
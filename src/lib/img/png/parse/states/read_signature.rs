@@ -5,7 +5,6 @@ use crate::lib::{
   },
   util::err::rsm_error::RSMError,
 };
-use std::marker::PhantomData;
 
 impl<'p> PNGParser<'p, ReadSignature> {
   /// PNG image signature
@@ -16,10 +15,7 @@ impl<'p> PNGParser<'p, ReadSignature> {
     let bytes: &[u8; 8] = self.reader.take_sized::<8>()?;
 
     if *bytes == Self::SIGNATURE {
-      Ok(PNGParser {
-        reader: self.reader,
-        _state: PhantomData,
-      })
+      Ok(self.transition())
     } else {
       Err(RSMError::InvalidContent)
     }
@@ -0,0 +1,149 @@
+use crate::lib::img::png::quantize::png_histogram::{Color, nearest, premultiply};
+use std::collections::HashMap;
+
+/// Threshold map of ordered dithering, holding the order in which each pixel
+/// of an 8x8 tile rounds up
+const BAYER: [[u8; 8]; 8] = [
+  [0, 32, 8, 40, 2, 34, 10, 42],
+  [48, 16, 56, 24, 50, 18, 58, 26],
+  [12, 44, 4, 36, 14, 46, 6, 38],
+  [60, 28, 52, 20, 62, 30, 54, 22],
+  [3, 35, 11, 43, 1, 33, 9, 41],
+  [51, 19, 59, 27, 49, 17, 57, 25],
+  [15, 47, 7, 39, 13, 45, 5, 37],
+  [63, 31, 55, 23, 61, 29, 53, 21],
+];
+
+/// Dithering applied when replacing colors by palette entries
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Dithering {
+  /// Use the closest entry for each pixel
+  None,
+
+  /// Spread the error of each pixel to the pixels to its right and below it
+  #[default]
+  FloydSteinberg,
+
+  /// Offset each pixel by a fixed pattern before finding the closest entry,
+  /// which avoids error diffusion artifacts and compresses better
+  Bayer,
+}
+
+impl Dithering {
+  /// Replace each pixel of rows of the given width by the index of a palette
+  /// entry
+  pub(crate) fn remap(&self, pixels: &[[u8; 4]], width: usize, palette: &[Color]) -> Vec<u8> {
+    match self {
+      Self::None => {
+        let mut cache: HashMap<[u8; 4], u8> = HashMap::new();
+        pixels
+          .iter()
+          .map(|&rgba| {
+            *cache
+              .entry(rgba)
+              .or_insert_with(|| nearest(palette, &premultiply(rgba)) as u8)
+          })
+          .collect()
+      }
+      Self::FloydSteinberg => floyd_steinberg(pixels, width, palette),
+      Self::Bayer => bayer(pixels, width, palette),
+    }
+  }
+}
+
+/// Keep a color within the range of premultiplied samples
+fn clamp(color: Color) -> Color {
+  let alpha: f32 = color[3].clamp(0.0, 255.0);
+  [
+    color[0].clamp(0.0, alpha),
+    color[1].clamp(0.0, alpha),
+    color[2].clamp(0.0, alpha),
+    alpha,
+  ]
+}
+
+fn floyd_steinberg(pixels: &[[u8; 4]], width: usize, palette: &[Color]) -> Vec<u8> {
+  // Errors carried to the current and next rows, with a pixel of margin on
+  // each side
+  let mut current: Vec<Color> = vec![[0.0; 4]; width + 2];
+  let mut next: Vec<Color> = vec![[0.0; 4]; width + 2];
+  let mut indices: Vec<u8> = Vec::with_capacity(pixels.len());
+
+  for row in pixels.chunks_exact(width) {
+    for (x, &rgba) in row.iter().enumerate() {
+      let mut color: Color = premultiply(rgba);
+      for (sample, error) in color.iter_mut().zip(current[x + 1]) {
+        *sample += error;
+      }
+      let color: Color = clamp(color);
+      let index: usize = nearest(palette, &color);
+      indices.push(index as u8);
+
+      for channel in 0..4 {
+        let error: f32 = color[channel] - palette[index][channel];
+        current[x + 2][channel] += error * 7.0 / 16.0;
+        next[x][channel] += error * 3.0 / 16.0;
+        next[x + 1][channel] += error * 5.0 / 16.0;
+        next[x + 2][channel] += error / 16.0;
+      }
+    }
+    std::mem::swap(&mut current, &mut next);
+    next.fill([0.0; 4]);
+  }
+  indices
+}
+
+fn bayer(pixels: &[[u8; 4]], width: usize, palette: &[Color]) -> Vec<u8> {
+  // Offsets span the distance between entries of an evenly spaced palette
+  let spread: f32 = 255.0 / ((palette.len() as f32).cbrt() - 1.0).max(1.0);
+
+  pixels
+    .iter()
+    .enumerate()
+    .map(|(index, &rgba)| {
+      let (x, y) = (index % width, index / width);
+      let offset: f32 = ((BAYER[y % 8][x % 8] as f32 + 0.5) / 64.0 - 0.5) * spread;
+      let mut color: Color = premultiply(rgba);
+      let alpha: f32 = color[3] / 255.0;
+      for sample in &mut color[..3] {
+        *sample += offset * alpha;
+      }
+      nearest(palette, &clamp(color)) as u8
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_dithering() {
+    // Mid grey between black and white entries
+    let palette: [Color; 2] = [[0.0, 0.0, 0.0, 255.0], [255.0, 255.0, 255.0, 255.0]];
+    let pixels: Vec<[u8; 4]> = vec![[128, 128, 128, 255]; 64];
+
+    assert!(
+      Dithering::None
+        .remap(&pixels, 8, &palette)
+        .iter()
+        .all(|&i| i == 1)
+    );
+    for dithering in [Dithering::FloydSteinberg, Dithering::Bayer] {
+      let indices: Vec<u8> = dithering.remap(&pixels, 8, &palette);
+      let white: usize = indices.iter().filter(|&&index| index == 1).count();
+      assert!((28..=36).contains(&white), "{dithering:?}: {white}");
+    }
+
+    // Exact colors are kept
+    let pixels: Vec<[u8; 4]> = vec![[255, 255, 255, 255]; 64];
+    for dithering in [Dithering::None, Dithering::FloydSteinberg, Dithering::Bayer] {
+      assert!(
+        dithering
+          .remap(&pixels, 8, &palette)
+          .iter()
+          .all(|&i| i == 1)
+      );
+    }
+  }
+}
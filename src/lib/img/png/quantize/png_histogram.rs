@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+/// RGBA color whose red, green and blue samples are multiplied by its alpha,
+/// in the range of 8-bit samples. Colors are compared this way so that the
+/// color of transparent pixels matters less the more transparent they are.
+pub(crate) type Color = [f32; 4];
+
+/// Distinct color of an image, with the amount of pixels using it
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct HistogramEntry {
+  pub rgba: [u8; 4],
+  pub color: Color,
+  pub count: u32,
+}
+
+/// Count the distinct colors of pixels, sorted by their RGBA samples. Fully
+/// transparent pixels all count as the same color.
+pub(crate) fn histogram(pixels: &[[u8; 4]]) -> Vec<HistogramEntry> {
+  let mut counts: HashMap<[u8; 4], u32> = HashMap::new();
+  for &rgba in pixels {
+    let rgba: [u8; 4] = if rgba[3] == 0 { [0; 4] } else { rgba };
+    *counts.entry(rgba).or_insert(0) += 1;
+  }
+
+  let mut entries: Vec<HistogramEntry> = counts
+    .into_iter()
+    .map(|(rgba, count)| HistogramEntry {
+      rgba,
+      color: premultiply(rgba),
+      count,
+    })
+    .collect();
+  entries.sort_by_key(|entry| entry.rgba);
+  entries
+}
+
+pub(crate) fn premultiply([r, g, b, a]: [u8; 4]) -> Color {
+  let alpha: f32 = a as f32 / 255.0;
+  [
+    r as f32 * alpha,
+    g as f32 * alpha,
+    b as f32 * alpha,
+    a as f32,
+  ]
+}
+
+/// Convert a color back to 8-bit RGBA samples
+pub(crate) fn unpremultiply([r, g, b, a]: Color) -> [u8; 4] {
+  let alpha: u8 = a.round().clamp(0.0, 255.0) as u8;
+  if alpha == 0 {
+    return [0; 4];
+  }
+  let sample = |value: f32| (value * 255.0 / alpha as f32).round().clamp(0.0, 255.0) as u8;
+  [sample(r), sample(g), sample(b), alpha]
+}
+
+/// Squared Euclidean distance between two colors
+pub(crate) fn distance(first: &Color, second: &Color) -> f32 {
+  first
+    .iter()
+    .zip(second)
+    .map(|(first, second)| (first - second) * (first - second))
+    .sum()
+}
+
+/// Find the index of the palette entry closest to a color
+pub(crate) fn nearest(palette: &[Color], color: &Color) -> usize {
+  let mut best: (usize, f32) = (0, f32::MAX);
+  for (index, entry) in palette.iter().enumerate() {
+    let distance: f32 = distance(entry, color);
+    if distance < best.1 {
+      best = (index, distance);
+    }
+  }
+  best.0
+}
+
+/// Mean squared error of the samples of each pixel once replaced by the
+/// closest palette entry
+pub(crate) fn mean_error(histogram: &[HistogramEntry], palette: &[Color]) -> f32 {
+  let mut sum: f64 = 0.0;
+  let mut count: f64 = 0.0;
+  for entry in histogram {
+    let closest: &Color = &palette[nearest(palette, &entry.color)];
+    sum += distance(closest, &entry.color) as f64 * entry.count as f64;
+    count += entry.count as f64;
+  }
+  (sum / (count * 4.0).max(1.0)) as f32
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use proptest::{prelude::any, prop_assert_eq, proptest};
+
+  #[test]
+  fn test_histogram() {
+    let entries: Vec<HistogramEntry> =
+      histogram(&[[9, 9, 9, 255], [1, 2, 3, 0], [9, 9, 9, 255], [0, 0, 0, 0]]);
+    assert_eq!(entries.len(), 2);
+    assert_eq!((entries[0].rgba, entries[0].count), ([0; 4], 2));
+    assert_eq!((entries[1].rgba, entries[1].count), ([9, 9, 9, 255], 2));
+    assert_eq!(
+      nearest(&[[0.0; 4], [9.0, 9.0, 9.0, 255.0]], &entries[1].color),
+      1
+    );
+  }
+
+  proptest! {
+    #[test]
+    fn test_premultiply_round_trip(rgb in any::<[u8; 3]>()) {
+      let [r, g, b] = rgb;
+      prop_assert_eq!(unpremultiply(premultiply([r, g, b, 255])), [r, g, b, 255]);
+      prop_assert_eq!(unpremultiply(premultiply([r, g, b, 0])), [0; 4]);
+    }
+  }
+}
@@ -0,0 +1,59 @@
+use crate::lib::img::png::quantize::{
+  png_histogram::{Color, HistogramEntry, distance, nearest},
+  png_median_cut::median_cut,
+};
+
+/// Largest amount of refinements of the palette
+const ITERATIONS: usize = 16;
+
+/// Refinement stops once no entry moves further than this distance
+const CONVERGENCE: f32 = 0.25;
+
+/// Choose a palette by median cut, then refine it with k-means: each entry
+/// moves to the mean of the colors closest to it, until entries settle.
+pub(crate) fn kmeans(histogram: &[HistogramEntry], size: usize) -> Vec<Color> {
+  let mut palette: Vec<Color> = median_cut(histogram, size);
+
+  for _ in 0..ITERATIONS {
+    let mut sums: Vec<([f64; 4], f64)> = vec![([0.0; 4], 0.0); palette.len()];
+    for entry in histogram {
+      let (sum, count) = &mut sums[nearest(&palette, &entry.color)];
+      for (sum, sample) in sum.iter_mut().zip(entry.color) {
+        *sum += sample as f64 * entry.count as f64;
+      }
+      *count += entry.count as f64;
+    }
+
+    let mut moved: f32 = 0.0;
+    for (color, (sum, count)) in palette.iter_mut().zip(sums) {
+      // Entries closest to no color keep their place
+      if count > 0.0 {
+        let mean: Color = sum.map(|sum| (sum / count) as f32);
+        moved = moved.max(distance(color, &mean));
+        *color = mean;
+      }
+    }
+    if moved < CONVERGENCE * CONVERGENCE {
+      break;
+    }
+  }
+  palette
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::img::png::quantize::png_histogram::{histogram, mean_error};
+
+  #[test]
+  fn test_kmeans() {
+    let pixels: Vec<[u8; 4]> = (0..=255u8)
+      .flat_map(|value| [[value, 0, 0, 255], [0, value / 2, 0, 255], [9, 9, 9, 128]])
+      .collect();
+    let histogram: Vec<HistogramEntry> = histogram(&pixels);
+
+    let refined: Vec<Color> = kmeans(&histogram, 8);
+    assert_eq!(refined.len(), 8);
+    assert!(mean_error(&histogram, &refined) <= mean_error(&histogram, &median_cut(&histogram, 8)));
+  }
+}
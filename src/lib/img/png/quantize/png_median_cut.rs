@@ -0,0 +1,103 @@
+use crate::lib::img::png::quantize::png_histogram::{Color, HistogramEntry, distance};
+use std::ops::Range;
+
+/// Choose a palette by splitting the colors into boxes, then averaging the
+/// colors of each box. The box whose colors are the furthest from their mean
+/// is split next, at the median of the channel along which they vary most.
+pub(crate) fn median_cut(histogram: &[HistogramEntry], size: usize) -> Vec<Color> {
+  let mut entries: Vec<HistogramEntry> = histogram.to_vec();
+  let mut boxes: Vec<Range<usize>> = Vec::with_capacity(size);
+  boxes.push(0..entries.len());
+
+  while boxes.len() < size {
+    let Some((index, _)) = boxes
+      .iter()
+      .enumerate()
+      .filter(|(_, range)| range.len() > 1)
+      .map(|(index, range)| (index, squared_error(&entries[range.clone()])))
+      .max_by(|first, second| first.1.total_cmp(&second.1))
+    else {
+      break;
+    };
+
+    let range: Range<usize> = boxes.swap_remove(index);
+    let split: usize = range.start + split_box(&mut entries[range.clone()]);
+    boxes.push(range.start..split);
+    boxes.push(split..range.end);
+  }
+
+  boxes
+    .into_iter()
+    .filter(|range| !range.is_empty())
+    .map(|range| mean(&entries[range]))
+    .collect()
+}
+
+/// Weighted mean of colors
+pub(crate) fn mean(entries: &[HistogramEntry]) -> Color {
+  let mut sums: [f64; 4] = [0.0; 4];
+  let mut count: f64 = 0.0;
+  for entry in entries {
+    for (sum, sample) in sums.iter_mut().zip(entry.color) {
+      *sum += sample as f64 * entry.count as f64;
+    }
+    count += entry.count as f64;
+  }
+  sums.map(|sum| (sum / count.max(1.0)) as f32)
+}
+
+/// Sum of the squared distances between colors and their mean
+fn squared_error(entries: &[HistogramEntry]) -> f64 {
+  let mean: Color = mean(entries);
+  entries
+    .iter()
+    .map(|entry| distance(&entry.color, &mean) as f64 * entry.count as f64)
+    .sum()
+}
+
+/// Sort a box along the channel of widest range, returning the position of
+/// its weighted median. Both halves hold at least one color.
+fn split_box(entries: &mut [HistogramEntry]) -> usize {
+  let channel: usize = (0..4)
+    .max_by(|&first, &second| range(entries, first).total_cmp(&range(entries, second)))
+    .unwrap_or(0);
+  entries.sort_by(|first, second| first.color[channel].total_cmp(&second.color[channel]));
+
+  let total: u64 = entries.iter().map(|entry| entry.count as u64).sum();
+  let mut seen: u64 = 0;
+  for (index, entry) in entries.iter().enumerate() {
+    seen += entry.count as u64;
+    if seen * 2 >= total {
+      return (index + 1).clamp(1, entries.len() - 1);
+    }
+  }
+  entries.len() - 1
+}
+
+fn range(entries: &[HistogramEntry], channel: usize) -> f32 {
+  let samples = entries.iter().map(|entry| entry.color[channel]);
+  samples.clone().fold(f32::MIN, f32::max) - samples.fold(f32::MAX, f32::min)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::img::png::quantize::png_histogram::histogram;
+
+  #[test]
+  fn test_median_cut() {
+    // Two groups of dark and light greys
+    let pixels: Vec<[u8; 4]> = [10, 12, 14, 200, 202, 204]
+      .iter()
+      .map(|&grey| [grey, grey, grey, 255])
+      .collect();
+    let mut palette: Vec<Color> = median_cut(&histogram(&pixels), 2);
+    palette.sort_by(|first, second| first[0].total_cmp(&second[0]));
+    assert_eq!(
+      palette,
+      [[12.0, 12.0, 12.0, 255.0], [202.0, 202.0, 202.0, 255.0]]
+    );
+
+    assert_eq!(median_cut(&histogram(&pixels), 10).len(), 6);
+  }
+}
@@ -0,0 +1,116 @@
+use crate::lib::img::png::quantize::png_histogram::{Color, HistogramEntry};
+
+/// Depth of the leaves holding colors, each level telling apart one more bit
+/// of every sample
+const MAX_DEPTH: usize = 6;
+
+/// Node of a tree dividing the RGBA color space, where each node has up to 16
+/// children: one per combination of a bit of each sample
+#[derive(Debug, Default, Clone)]
+struct Node {
+  /// Indices of the children in the tree, where 0 marks a missing child
+  children: [usize; 16],
+
+  /// Weighted sums of the colors below the node
+  sums: [f64; 4],
+  count: f64,
+  leaf: bool,
+}
+
+impl Node {
+  /// Count the pixels of a color as being below the node
+  fn add(&mut self, entry: &HistogramEntry) {
+    let weight: f64 = entry.count as f64;
+    for (sum, sample) in self.sums.iter_mut().zip(entry.color) {
+      *sum += sample as f64 * weight;
+    }
+    self.count += weight;
+  }
+}
+
+/// Choose a palette by inserting the colors in a tree, then merging the
+/// leaves holding the fewest pixels, deepest first, until few enough remain.
+pub(crate) fn octree(histogram: &[HistogramEntry], size: usize) -> Vec<Color> {
+  let mut nodes: Vec<Node> = vec![Node::default()];
+  // Parents of leaves, by depth
+  let mut levels: Vec<Vec<usize>> = vec![Vec::new(); MAX_DEPTH];
+  let mut leaves: usize = 0;
+
+  for entry in histogram {
+    let mut node: usize = 0;
+    nodes[node].add(entry);
+    for (depth, parents) in levels.iter_mut().enumerate() {
+      let shift: usize = 7 - depth;
+      let child: usize = entry.rgba.iter().fold(0, |index, sample| {
+        (index << 1) | ((sample >> shift) & 1) as usize
+      });
+
+      if nodes[node].children[child] == 0 {
+        if nodes[node].children.iter().all(|&child| child == 0) {
+          parents.push(node);
+        }
+        nodes[node].children[child] = nodes.len();
+        nodes.push(Node {
+          leaf: depth + 1 == MAX_DEPTH,
+          ..Node::default()
+        });
+        leaves += (depth + 1 == MAX_DEPTH) as usize;
+      }
+      node = nodes[node].children[child];
+      nodes[node].add(entry);
+    }
+  }
+
+  // Parents at the deepest level only have leaves as children
+  while leaves > size.max(1) {
+    let Some(level) = levels.iter_mut().rev().find(|level| !level.is_empty()) else {
+      break;
+    };
+    let (position, &node) = level
+      .iter()
+      .enumerate()
+      .min_by(|first, second| nodes[*first.1].count.total_cmp(&nodes[*second.1].count))
+      .expect("levels are not empty");
+    level.swap_remove(position);
+
+    let children: usize = nodes[node]
+      .children
+      .iter()
+      .filter(|&&child| child != 0)
+      .count();
+    nodes[node].leaf = true;
+    leaves -= children - 1;
+  }
+
+  let mut palette: Vec<Color> = Vec::with_capacity(leaves);
+  let mut pending: Vec<usize> = vec![0];
+  while let Some(index) = pending.pop() {
+    let node: &Node = &nodes[index];
+    match node.leaf {
+      true => palette.push(node.sums.map(|sum| (sum / node.count) as f32)),
+      false => pending.extend(node.children.iter().filter(|&&child| child != 0)),
+    }
+  }
+  palette
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::img::png::quantize::png_histogram::{histogram, mean_error};
+
+  #[test]
+  fn test_octree() {
+    let pixels: Vec<[u8; 4]> = (0..64u8)
+      .flat_map(|value| [[value * 4, 0, 0, 255], [0, 0, value * 4, 255]])
+      .collect();
+    let histogram: Vec<HistogramEntry> = histogram(&pixels);
+    assert_eq!(octree(&histogram, 256).len(), histogram.len());
+
+    let palette: Vec<Color> = octree(&histogram, 16);
+    assert!(palette.len() <= 16 && palette.len() > 1);
+    let coarse: Vec<Color> = octree(&histogram, 2);
+    assert!(coarse.len() <= 2);
+    assert!(mean_error(&histogram, &palette) < mean_error(&histogram, &coarse));
+  }
+}
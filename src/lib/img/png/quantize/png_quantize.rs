@@ -0,0 +1,102 @@
+use crate::lib::{
+  img::png::{
+    encode::png_encoded_pixels::{EncodedPixels, index_bit_depth},
+    image::png_image::PNGImage,
+    parse::chunks::{
+      idat::png_pixel_data::PixelData, ihdr::png_color_type::ColorType, plte::png_palette::Palette,
+      trns::png_transparency::Transparency,
+    },
+    quantize::{
+      png_histogram::{Color, HistogramEntry, histogram, mean_error, premultiply, unpremultiply},
+      png_quantize_options::QuantizeOptions,
+    },
+  },
+  util::err::rsm_error::RSMError,
+};
+
+impl PNGImage {
+  /// Reduce the colors of the image to a palette using the given
+  /// [options](QuantizeOptions). The pixels become 8-bit RGBA colors of the
+  /// palette, which is set along with the alpha of its entries, so that the
+  /// image can be encoded with
+  /// [indexed](crate::lib::img::png::encode::png_encode_options::EncodeOptions::indexed)
+  /// colors.
+  pub fn quantize(mut self, options: QuantizeOptions) -> Result<PNGImage, RSMError> {
+    if options.quality > 100 || !(1..=Palette::MAX_ENTRIES).contains(&options.max_colors) {
+      return Err(RSMError::OutOfBounds);
+    }
+    EncodedPixels::new(&self.data)?;
+
+    let pixels: Vec<[u8; 4]> = self.data.to_rgba8();
+    let histogram: Vec<HistogramEntry> = histogram(&pixels);
+    let palette: Vec<Color> = choose_palette(&histogram, &options);
+
+    // Entries with alpha come first, so that their alpha values end early
+    let mut entries: Vec<[u8; 4]> = palette.into_iter().map(unpremultiply).collect();
+    entries.sort_by_key(|&entry| (entry[3] == 255, entry));
+    entries.dedup();
+
+    let targets: Vec<Color> = entries.iter().map(|&entry| premultiply(entry)).collect();
+    let indices: Vec<u8> = options
+      .dithering
+      .remap(&pixels, self.data.width as usize, &targets);
+    let colors: Vec<[u8; 4]> = indices
+      .iter()
+      .map(|&index| entries[index as usize])
+      .collect();
+
+    let alpha: Vec<u8> = entries
+      .iter()
+      .map(|entry| entry[3])
+      .take_while(|&alpha| alpha != 255)
+      .collect();
+    self.meta.palette = Some(Palette::new(
+      entries.iter().map(|&[r, g, b, _]| [r, g, b]).collect(),
+    )?);
+    self.meta.transparency = (!alpha.is_empty()).then_some(Transparency::PaletteAlpha(alpha));
+
+    // Chunks describing the previous samples no longer apply
+    self.meta.background_bytes = None;
+    self.meta.significant_bits = None;
+    self.meta.histogram = None;
+
+    self.header.color_type = ColorType::IndexedColor;
+    self.header.bit_depth = index_bit_depth(entries.len());
+    self.data = PixelData::from_rgba8(self.data.width, self.data.height, &colors);
+    Ok(self)
+  }
+}
+
+/// Choose the smallest palette meeting the quality, keeping the exact colors
+/// when there are few enough of them
+fn choose_palette(histogram: &[HistogramEntry], options: &QuantizeOptions) -> Vec<Color> {
+  let palette = |size: usize| -> Vec<Color> {
+    match size >= histogram.len() {
+      true => histogram.iter().map(|entry| entry.color).collect(),
+      false => options.quantizer.palette(histogram, size),
+    }
+  };
+
+  let limit: usize = options.max_colors.min(histogram.len());
+  let max_error: f32 = options.max_error();
+  if max_error == 0.0 {
+    return palette(limit);
+  }
+
+  // Larger palettes have a lower error, so search the smallest one meeting
+  // the quality
+  let (mut low, mut high) = (1, limit);
+  let mut best: Option<Vec<Color>> = None;
+  while low <= high {
+    let size: usize = (low + high) / 2;
+    let candidate: Vec<Color> = palette(size);
+    match mean_error(histogram, &candidate) <= max_error {
+      true => {
+        best = Some(candidate);
+        high = size - 1;
+      }
+      false => low = size + 1,
+    }
+  }
+  best.unwrap_or_else(|| palette(limit))
+}
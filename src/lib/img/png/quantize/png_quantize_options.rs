@@ -0,0 +1,69 @@
+use crate::lib::img::png::{
+  parse::chunks::plte::png_palette::Palette,
+  quantize::{png_dithering::Dithering, png_quantizer::Quantizer},
+};
+
+/// Options used when quantizing the colors of PNG images
+#[derive(Debug, Clone)]
+pub struct QuantizeOptions {
+  pub(crate) quantizer: Quantizer,
+  pub(crate) dithering: Dithering,
+
+  /// Quality from 0 to 100, where 100 asks for the exact colors
+  pub(crate) quality: u8,
+
+  /// Largest amount of palette entries, from 1 to 256
+  pub(crate) max_colors: usize,
+}
+
+impl Default for QuantizeOptions {
+  fn default() -> Self {
+    Self {
+      quantizer: Quantizer::default(),
+      dithering: Dithering::default(),
+      quality: 100,
+      max_colors: Palette::MAX_ENTRIES,
+    }
+  }
+}
+
+impl QuantizeOptions {
+  /// Create the default quantization options, using k-means and
+  /// Floyd–Steinberg dithering with up to 256 colors
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Set the algorithm choosing the palette
+  pub fn quantizer(mut self, quantizer: Quantizer) -> Self {
+    self.quantizer = quantizer;
+    self
+  }
+
+  /// Set the dithering applied when replacing colors by palette entries
+  pub fn dithering(mut self, dithering: Dithering) -> Self {
+    self.dithering = dithering;
+    self
+  }
+
+  /// Set the quality to reach, from 0 to 100, which picks the smallest
+  /// palette meeting it. The root mean square error of samples is allowed to
+  /// reach a fifth of the difference between 100 and the quality, in 8-bit
+  /// levels.
+  pub fn quality(mut self, quality: u8) -> Self {
+    self.quality = quality;
+    self
+  }
+
+  /// Set the largest amount of palette entries, from 1 to 256
+  pub fn max_colors(mut self, colors: usize) -> Self {
+    self.max_colors = colors;
+    self
+  }
+
+  /// Largest mean squared error of samples meeting the quality
+  pub(crate) fn max_error(&self) -> f32 {
+    let error: f32 = (100 - self.quality.min(100)) as f32 / 5.0;
+    error * error
+  }
+}
@@ -0,0 +1,33 @@
+use crate::lib::img::png::quantize::{
+  png_histogram::{Color, HistogramEntry},
+  png_kmeans::kmeans,
+  png_median_cut::median_cut,
+  png_octree::octree,
+};
+
+/// Algorithm choosing the entries of a palette
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Quantizer {
+  /// Split the colors into boxes along their widest channel, averaging each
+  /// box
+  MedianCut,
+
+  /// Refine a median cut palette by moving each entry to the mean of the
+  /// colors closest to it, which is slower but more accurate
+  #[default]
+  KMeans,
+
+  /// Merge the least used branches of a tree of colors, which is the fastest
+  Octree,
+}
+
+impl Quantizer {
+  /// Choose a palette of at most the given amount of entries for the colors
+  pub(crate) fn palette(&self, histogram: &[HistogramEntry], size: usize) -> Vec<Color> {
+    match self {
+      Self::MedianCut => median_cut(histogram, size),
+      Self::KMeans => kmeans(histogram, size),
+      Self::Octree => octree(histogram, size),
+    }
+  }
+}
@@ -1,5 +1,8 @@
 use crate::lib::{
-  img::png::{image::png_image::PNGImage, parse::png_parser::PNGParser},
+  img::png::{
+    image::png_image::PNGImage,
+    parse::{png_decode_options::DecodeOptions, png_parser::PNGParser},
+  },
   util::{data::file_data::FileData, err::rsm_error::RSMError},
 };
 
@@ -8,32 +11,57 @@ impl PNGImage {
   /// [FileData] using [TryInto].
   #[inline]
   pub fn read<'a, T>(data: T) -> Result<Self, RSMError>
+  where
+    T: TryInto<FileData<'a>>,
+    T::Error: Into<RSMError>,
+  {
+    Self::read_with(data, DecodeOptions::default())
+  }
+
+  /// Read a file as a PNG image using the given [options](DecodeOptions).
+  pub fn read_with<'a, T>(data: T, options: DecodeOptions) -> Result<Self, RSMError>
   where
     T: TryInto<FileData<'a>>,
     T::Error: Into<RSMError>,
   {
     let file_data: FileData<'_> = data.try_into().map_err(Into::into)?;
-    Self::read_bytes(file_data.as_bytes())
+    Self::read_bytes_with(file_data.as_bytes(), options)
   }
 
   /// Read a sequence of bytes as the data of a PNG image.
+  #[inline]
   pub fn read_bytes(data: &'_ [u8]) -> Result<Self, RSMError> {
-    Self::parse(data)
+    Self::read_bytes_with(data, DecodeOptions::default())
+  }
+
+  /// Read a sequence of bytes as the data of a PNG image using the given
+  /// [options](DecodeOptions).
+  pub fn read_bytes_with(data: &'_ [u8], options: DecodeOptions) -> Result<Self, RSMError> {
+    Self::parse(data, options)
   }
 
   /// Drive the parser's finite state machine to the end to read the data
-  fn parse(data: &'_ [u8]) -> Result<Self, RSMError> {
-    let parser = PNGParser::new(data);
+  fn parse(data: &'_ [u8], options: DecodeOptions) -> Result<Self, RSMError> {
+    let parser = PNGParser::new(data, options);
     let parser = parser.read_signature()?;
     let (parser, header) = parser.read_ihdr()?;
     let (parser, mut post_ihdr, first_idat) = parser.read_post_ihdr(&header)?;
-    let (parser, data) = parser.read_idat(&first_idat, &header, &mut post_ihdr)?;
-    let _ = parser.read_post_idat(&mut post_ihdr, &header);
+    let (parser, data, next) = parser.read_idat(&first_idat, &header, &post_ihdr)?;
+    let (parser, iend) = parser.read_post_idat(next, &mut post_ihdr, &header)?;
+    let parser = parser.read_iend(iend)?;
+    let trailing_data = parser.read_post_iend();
 
-    Ok(Self {
+    let mut image: PNGImage = Self {
       header,
       meta: post_ihdr,
       data,
-    })
+      warnings: parser.warnings,
+      trailing_data,
+    };
+
+    if parser.options.apply_orientation {
+      image.apply_orientation()?;
+    }
+    Ok(image)
   }
 }
@@ -0,0 +1,41 @@
+use crate::lib::{
+  img::png::{
+    encode::{png_encode_options::EncodeOptions, png_encoder::PNGEncoder},
+    image::png_image::PNGImage,
+  },
+  util::err::rsm_error::RSMError,
+};
+use std::{fs, path::Path};
+
+impl PNGImage {
+  /// Write the image as a PNG file
+  #[inline]
+  pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), RSMError> {
+    self.write_with(path, EncodeOptions::default())
+  }
+
+  /// Write the image as a PNG file using the given [options](EncodeOptions).
+  pub fn write_with<P: AsRef<Path>>(
+    &self,
+    path: P,
+    options: EncodeOptions,
+  ) -> Result<(), RSMError> {
+    let bytes: Vec<u8> = self.write_bytes_with(options)?;
+    fs::write(path, bytes)?;
+    Ok(())
+  }
+
+  /// Encode the image as the bytes of a PNG file. Pixels are written with
+  /// their own colors, even when they all belong to the palette of the image,
+  /// unless [indexed](EncodeOptions::indexed) output is requested.
+  #[inline]
+  pub fn write_bytes(&self) -> Result<Vec<u8>, RSMError> {
+    self.write_bytes_with(EncodeOptions::default())
+  }
+
+  /// Encode the image as the bytes of a PNG file using the given
+  /// [options](EncodeOptions).
+  pub fn write_bytes_with(&self, options: EncodeOptions) -> Result<Vec<u8>, RSMError> {
+    PNGEncoder::new(self, &options).encode()
+  }
+}
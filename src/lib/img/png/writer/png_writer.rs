@@ -0,0 +1,64 @@
+use crate::lib::img::png::chunk::png_chunk_type::ChunkType;
+
+/// Simple byte writer
+pub struct PNGWriter {
+  pub(crate) bytes: Vec<u8>,
+}
+
+impl PNGWriter {
+  /// PNG signature
+  pub const SIGNATURE: [u8; 8] = [0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a];
+
+  /// Create a new writer, starting with the PNG signature
+  pub fn new() -> Self {
+    Self {
+      bytes: Self::SIGNATURE.to_vec(),
+    }
+  }
+
+  /// Write a chunk with its length, type, data and CRC
+  pub fn write_chunk(&mut self, r#type: ChunkType, data: &[u8]) {
+    let type_bytes: [u8; 4] = r#type.as_bytes();
+    self.bytes.reserve(data.len() + 12);
+    self
+      .bytes
+      .extend_from_slice(&(data.len() as u32).to_be_bytes());
+    self.bytes.extend_from_slice(&type_bytes);
+    self.bytes.extend_from_slice(data);
+
+    let mut hasher: crc32fast::Hasher = crc32fast::Hasher::new();
+    hasher.update(&type_bytes);
+    hasher.update(data);
+    self
+      .bytes
+      .extend_from_slice(&hasher.finalize().to_be_bytes());
+  }
+
+  /// Write the `IEND` chunk and obtain the bytes of the image
+  pub fn finish(mut self) -> Vec<u8> {
+    self.write_chunk(ChunkType::IEND, &[]);
+    self.bytes
+  }
+}
+
+impl Default for PNGWriter {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_write_chunk() {
+    let bytes: Vec<u8> = PNGWriter::new().finish();
+
+    assert_eq!(&bytes[..8], &PNGWriter::SIGNATURE);
+    assert_eq!(
+      &bytes[8..],
+      &[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]
+    );
+  }
+}
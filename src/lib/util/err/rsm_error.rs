@@ -10,9 +10,11 @@ use std::{
 #[cfg_attr(test, derive(strum_macros::EnumIter))]
 pub enum RSMError {
   DecompressionError,
+  InvalidChecksum,
   InvalidContent,
   InvalidFile,
   InvalidLength,
+  LimitExceeded,
   NotEnoughContent,
   OutOfBounds,
   Other(String),
@@ -22,9 +24,11 @@ impl Display for RSMError {
   fn fmt(&self, f: &mut Formatter<'_>) -> Result {
     let message: &str = match self {
       Self::DecompressionError => "Failed to decompress content",
+      Self::InvalidChecksum => "Checksum does not match the content",
       Self::InvalidContent => "File contents are invalid",
       Self::InvalidFile => "Invalid file data or path",
       Self::InvalidLength => "Invalid lenght for data provided",
+      Self::LimitExceeded => "Resource limit exceeded",
       Self::NotEnoughContent => "Not enough content to read",
       Self::OutOfBounds => "Value is out of bounds",
       Self::Other(msg) => &msg.to_string(),
@@ -45,7 +49,7 @@ impl From<io::Error> for RSMError {
 pub mod tests {
   use super::*;
   use proptest::{prop_assert, prop_assert_eq, proptest};
-  use std::{io::ErrorKind, mem::discriminant};
+  use std::mem::discriminant;
   use strum::IntoEnumIterator;
 
   /// Test the messages defined within [RSMError] are not empty for a way to
@@ -67,7 +71,7 @@ pub mod tests {
     /// [`io::Error`].
     #[test]
     fn test_errors_other_mapping(message in ".+") {
-      let error: io::Error = io::Error::new(ErrorKind::Other, message.clone());
+      let error: io::Error = io::Error::other(message.clone());
       let mapped_error = RSMError::from(error);
 
       if let RSMError::Other(ref inner) = mapped_error {
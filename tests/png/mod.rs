@@ -1 +1,20 @@
+mod png_composite;
+mod png_content_credentials;
+mod png_filter;
+mod png_idat;
+mod png_limits;
+mod png_optimize;
+mod png_options;
+mod png_orientation;
+#[cfg(feature = "parallel")]
+mod png_parallel;
+mod png_quantize;
+mod png_resample;
 mod png_suite;
+mod png_text;
+mod png_trailing_data;
+mod png_transform;
+mod png_transparency;
+mod png_warnings;
+mod png_write;
+mod utils;
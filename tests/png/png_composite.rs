@@ -0,0 +1,57 @@
+use crate::png::utils::build_png;
+use rsm::lib::img::png::{
+  image::{
+    composite::{
+      png_blend_mode::BlendMode, png_composite_options::CompositeOptions,
+      png_porter_duff::PorterDuff,
+    },
+    png_image::PNGImage,
+  },
+  parse::chunks::idat::{png_pixel_data::PixelData, png_pixel_format::ColorTarget},
+};
+
+/// Opaque grey RGB image
+fn photo(width: u32, height: u32) -> PNGImage {
+  let row: Vec<u8> = vec![128; width as usize * 3];
+  let rows: Vec<&[u8]> = vec![row.as_slice(); height as usize];
+  PNGImage::read_bytes(&build_png((width, 8, 2), &rows, &[], &[])).unwrap()
+}
+
+#[test]
+fn test_watermark() {
+  // A translucent white mark in the bottom right corner, partly outside
+  let mut image: PNGImage = photo(8, 6);
+  let mark: PixelData = PixelData::from_rgba8(4, 4, &[[255, 255, 255, 255]; 16]);
+  let options: CompositeOptions = CompositeOptions::new()
+    .opacity(0.5)
+    .blend(BlendMode::Screen);
+  image.data.draw(&mark, 6, 4, options).unwrap();
+
+  let colors: Vec<[u8; 4]> = image.data.to_rgba8();
+  for (index, color) in colors.iter().enumerate() {
+    let (x, y) = (index % 8, index / 8);
+    match x >= 6 && y >= 4 {
+      true => assert!(color[0] > 128 && color[3] == 255, "{color:?}"),
+      false => assert_eq!(*color, [128, 128, 128, 255]),
+    }
+  }
+
+  let written: PNGImage = PNGImage::read_bytes(&image.write_bytes().unwrap()).unwrap();
+  assert_eq!(written.data.data, image.data.data);
+}
+
+#[test]
+fn test_sprite_atlas() {
+  // Sprites are copied side by side into a transparent atlas
+  let mut atlas: PixelData = PixelData::from_rgba8(4, 2, &[[0; 4]; 8]);
+  let sprites: [[u8; 4]; 2] = [[255, 0, 0, 255], [0, 255, 0, 64]];
+  for (index, color) in sprites.into_iter().enumerate() {
+    let sprite: PixelData = PixelData::from_rgba8(2, 2, &[color; 4]);
+    let options: CompositeOptions = CompositeOptions::new().operator(PorterDuff::Source);
+    atlas.draw(&sprite, index as i64 * 2, 0, options).unwrap();
+  }
+  assert_eq!(atlas.color, ColorTarget::Rgba);
+  let colors: Vec<[u8; 4]> = atlas.to_rgba8();
+  assert_eq!(colors[..2], [sprites[0]; 2]);
+  assert_eq!(colors[6..], [sprites[1]; 2]);
+}
@@ -0,0 +1,157 @@
+use crate::png::utils::{chunk, grey_png};
+use c2pa::{Builder, EphemeralSigner};
+use rsm::lib::{
+  img::png::{
+    image::png_image::PNGImage,
+    parse::{
+      chunks::cabx::{
+        png_attribution_assertion::{AttributionAssertion, MiningPermission},
+        png_attribution_manifest::{AttributionManifest, IngredientRelationship},
+        png_content_credentials::{ContentCredentials, CredentialState},
+      },
+      png_decode_options::{CrcPolicy, DecodeOptions},
+    },
+  },
+  util::err::rsm_error::RSMError,
+};
+use std::io::Cursor;
+
+const MANIFEST: &str = r#"{
+  "title": "edited.png",
+  "assertions": [
+    {
+      "label": "c2pa.actions",
+      "data": {
+        "actions": [
+          {
+            "action": "c2pa.opened",
+            "parameters": { "ingredientIds": ["original"] }
+          },
+          {
+            "action": "c2pa.edited",
+            "softwareAgent": { "name": "rsm" }
+          }
+        ]
+      }
+    },
+    {
+      "label": "c2pa.training-mining",
+      "data": {
+        "entries": {
+          "c2pa.ai_training": { "use": "notAllowed" }
+        }
+      }
+    }
+  ]
+}"#;
+
+const ORIGINAL: &str = r#"{
+  "title": "original.png",
+  "assertions": [
+    {
+      "label": "c2pa.actions",
+      "data": {
+        "actions": [
+          {
+            "action": "c2pa.created",
+            "digitalSourceType": "http://cv.iptc.org/newscodes/digitalsourcetype/digitalCapture"
+          }
+        ]
+      }
+    }
+  ]
+}"#;
+
+/// Sign a PNG with an ephemeral certificate, which is not trusted
+fn sign(png: &[u8], definition: &str, parent: Option<&[u8]>) -> Vec<u8> {
+  let signer: EphemeralSigner = EphemeralSigner::new("rsm.local").unwrap();
+  let mut builder: Builder = Builder::from_json(definition).unwrap();
+
+  if let Some(parent) = parent {
+    let ingredient: &str =
+      r#"{ "title": "original.png", "relationship": "parentOf", "label": "original" }"#;
+    builder
+      .add_ingredient_from_stream(ingredient, "image/png", &mut Cursor::new(parent))
+      .unwrap();
+  }
+
+  let mut signed: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+  builder
+    .sign(&signer, "image/png", &mut Cursor::new(png), &mut signed)
+    .unwrap();
+  signed.into_inner()
+}
+
+fn signed_png() -> Vec<u8> {
+  let text: Vec<u8> = chunk(b"tEXt", b"Comment\0rsm");
+  let original: Vec<u8> = sign(
+    &grey_png(2, &[&[0, 255]], std::slice::from_ref(&text), &[]),
+    ORIGINAL,
+    None,
+  );
+  sign(
+    &grey_png(2, &[&[0, 128]], &[text], &[]),
+    MANIFEST,
+    Some(&original),
+  )
+}
+
+#[test]
+fn test_content_credentials() {
+  let image: PNGImage = PNGImage::read_bytes(&signed_png()).unwrap();
+  let credentials: ContentCredentials = image.meta.content_credentials.unwrap();
+
+  assert!(image.warnings.is_empty());
+  assert_eq!(credentials.state, CredentialState::Valid);
+  assert!(credentials.is_signature_valid());
+  assert!(!credentials.has_hash_mismatch());
+
+  let active: &AttributionManifest = credentials.active().unwrap();
+  assert_eq!(active.title.as_deref(), Some("edited.png"));
+  assert_eq!(active.name.as_deref(), Some("rsm.local"));
+
+  let AttributionAssertion::Actions(actions) = &active.assertions[0] else {
+    panic!("expected actions, found {:?}", active.assertions[0]);
+  };
+  assert_eq!(actions[0].action, "c2pa.opened");
+  assert_eq!(actions[1].action, "c2pa.edited");
+  assert_eq!(actions[1].software_agent.as_deref(), Some("rsm"));
+
+  let AttributionAssertion::TrainingMining(entries) = &active.assertions[1] else {
+    panic!(
+      "expected training and mining, found {:?}",
+      active.assertions[1]
+    );
+  };
+  assert_eq!(entries[0].usage, "c2pa.ai_training");
+  assert_eq!(entries[0].permission, MiningPermission::NotAllowed);
+
+  let ingredient = &active.ingredients[0];
+  assert_eq!(ingredient.relationship, IngredientRelationship::ParentOf);
+  let parent: &AttributionManifest = credentials.ingredient_manifest(ingredient).unwrap();
+  assert_eq!(parent.title.as_deref(), Some("original.png"));
+}
+
+#[test]
+fn test_content_credentials_hash_mismatch() {
+  let mut png: Vec<u8> = signed_png();
+  let text: usize = png.windows(11).position(|w| w == b"Comment\0rsm").unwrap();
+  png[text + 8] = b'R';
+
+  let options: DecodeOptions = DecodeOptions::new().crc(CrcPolicy::Ignore);
+  let image: PNGImage = PNGImage::read_bytes_with(&png, options).unwrap();
+  let credentials: ContentCredentials = image.meta.content_credentials.unwrap();
+
+  assert_eq!(credentials.state, CredentialState::Invalid);
+  assert!(credentials.has_hash_mismatch());
+  assert!(credentials.is_signature_valid());
+}
+
+#[test]
+fn test_content_credentials_malformed() {
+  let png: Vec<u8> = grey_png(1, &[&[0]], &[chunk(b"caBX", b"rsm")], &[]);
+  let image: PNGImage = PNGImage::read_bytes(&png).unwrap();
+
+  assert!(image.meta.content_credentials.is_none());
+  assert!(matches!(image.warnings[0].reason, RSMError::Other(_)));
+}
@@ -0,0 +1,75 @@
+use crate::png::utils::build_png;
+use rsm::lib::img::png::{
+  image::{
+    filter::{png_edge_mode::EdgeMode, png_kernel::Kernel, png_luma::Luma},
+    png_image::PNGImage,
+  },
+  parse::chunks::{idat::png_pixel_format::ColorTarget, ihdr::png_color_type::ColorType},
+};
+
+/// 16x16 RGB checkerboard of 4x4 squares
+fn checkerboard() -> PNGImage {
+  let rows: Vec<Vec<u8>> = (0..16u32)
+    .map(|y| {
+      (0..16u32)
+        .flat_map(|x| match (x / 4 + y / 4) % 2 {
+          0 => [250, 20, 20],
+          _ => [20, 20, 250],
+        })
+        .collect()
+    })
+    .collect();
+  let rows: Vec<&[u8]> = rows.iter().map(Vec::as_slice).collect();
+  PNGImage::read_bytes(&build_png((16, 8, 2), &rows, &[], &[])).unwrap()
+}
+
+/// Sum of the differences between the red samples of neighbouring pixels
+/// along rows
+fn variation(image: &PNGImage) -> u32 {
+  let colors: Vec<[u8; 4]> = image.data.to_rgba8();
+  colors
+    .chunks_exact(image.data.width as usize)
+    .flat_map(|row| row.windows(2))
+    .map(|pair| pair[0][0].abs_diff(pair[1][0]) as u32)
+    .sum()
+}
+
+#[test]
+fn test_blur_preview() {
+  let mut image: PNGImage = checkerboard();
+  image.data.gaussian_blur(2.0).unwrap();
+  assert!(variation(&image) < variation(&checkerboard()) / 2);
+
+  let written: PNGImage = PNGImage::read_bytes(&image.write_bytes().unwrap()).unwrap();
+  assert_eq!(written.data.data, image.data.data);
+
+  let mut boxed: PNGImage = checkerboard();
+  boxed.data.box_blur(3).unwrap();
+  assert!(variation(&boxed) < variation(&checkerboard()) / 2);
+}
+
+#[test]
+fn test_filters_keep_dimensions() {
+  let mut image: PNGImage = checkerboard();
+  image.data.unsharp_mask(1.0, 0.5, 0.0).unwrap();
+  image
+    .data
+    .convolve(&Kernel::sharpen(), EdgeMode::Mirror)
+    .unwrap();
+  image.data.contrast(1.2).unwrap();
+  image.data.hue(90.0).unwrap();
+  assert_eq!((image.data.width, image.data.height), (16, 16));
+  assert_eq!(image.data.data.len(), 16 * 16 * 4);
+}
+
+#[test]
+fn test_grayscale() {
+  let mut image: PNGImage = checkerboard();
+  image.data.grayscale(Luma::Rec601).unwrap();
+  assert_eq!(image.data.color, ColorTarget::GreyAlpha);
+
+  // Grey pixels are written as grey
+  let written: PNGImage = PNGImage::read_bytes(&image.write_bytes().unwrap()).unwrap();
+  assert_eq!(written.header.color_type, ColorType::GreyscaleAlpha);
+  assert_eq!(written.data.to_rgba8(), image.data.to_rgba8());
+}
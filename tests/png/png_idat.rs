@@ -0,0 +1,69 @@
+use crate::png::utils::build_png_split;
+use rsm::lib::{
+  img::png::{
+    image::png_image::PNGImage,
+    parse::{
+      chunks::idat::png_pixel_format::ColorTarget, png_decode_options::DecodeOptions,
+      png_limits::Limits,
+    },
+  },
+  util::err::rsm_error::RSMError,
+};
+
+/// Build an 8-bit greyscale PNG whose image data is split into chunks of the
+/// given size
+fn split_png(width: u32, height: u32, size: usize) -> (Vec<u8>, Vec<u8>) {
+  let pixels: Vec<u8> = (0..width * height).map(|n| (n * 7 % 256) as u8).collect();
+  let raw: Vec<u8> = pixels
+    .chunks(width as usize)
+    .flat_map(|row| [&[0u8][..], row].concat())
+    .collect();
+
+  let png: Vec<u8> = build_png_split((width, height, 8, 0, 0), &raw, size, &[], &[]);
+  (png, pixels)
+}
+
+#[test]
+fn test_split_idat() {
+  for size in [1, 3, 64, usize::MAX] {
+    let (png, pixels) = split_png(64, 48, size);
+    let options: DecodeOptions = DecodeOptions::new().color(ColorTarget::Grey);
+    let image: PNGImage = PNGImage::read_bytes_with(&png, options).unwrap();
+    assert_eq!(image.data.data, pixels);
+  }
+}
+
+#[test]
+fn test_split_idat_is_not_copied() {
+  // The scanlines (48 × 65 bytes) and the canvas (64 × 48 bytes) are the only
+  // allocations, as split data is streamed in place
+  let (png, pixels) = split_png(64, 48, 64);
+  let decode = |max_alloc: u64| {
+    let limits: Limits = Limits {
+      max_alloc,
+      ..Limits::default()
+    };
+    let options: DecodeOptions = DecodeOptions::new().color(ColorTarget::Grey).limits(limits);
+    PNGImage::read_bytes_with(&png, options)
+  };
+
+  assert_eq!(decode(6192).unwrap().data.data, pixels);
+  assert!(matches!(decode(6191), Err(RSMError::LimitExceeded)));
+}
+
+#[test]
+fn test_truncated_split_idat() {
+  let (png, _) = split_png(64, 48, 16);
+
+  // Drop the last IDAT chunk, holding the end of the stream
+  let iend: usize = png.len() - 12;
+  let last: usize = png
+    .windows(4)
+    .rposition(|window| window == b"IDAT")
+    .unwrap()
+    - 4;
+  let truncated: Vec<u8> = [&png[..last], &png[iend..]].concat();
+
+  let result = PNGImage::read_bytes(&truncated);
+  assert!(matches!(result, Err(RSMError::DecompressionError)));
+}
@@ -0,0 +1,74 @@
+use crate::png::utils::{chunk, grey_png, zlib};
+use rsm::lib::{
+  img::png::{
+    chunk::png_chunk_type::ChunkType,
+    image::png_image::PNGImage,
+    parse::{png_decode_options::DecodeOptions, png_limits::Limits},
+  },
+  util::err::rsm_error::RSMError,
+};
+
+#[test]
+fn test_pixel_limit() {
+  let mut ihdr: Vec<u8> = Vec::new();
+  ihdr.extend_from_slice(&(i32::MAX as u32).to_be_bytes());
+  ihdr.extend_from_slice(&(i32::MAX as u32).to_be_bytes());
+  ihdr.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+  let mut png: Vec<u8> = vec![0x89, 0x50, 0x4e, 0x47, 0xd, 0xa, 0x1a, 0xa];
+  png.extend(chunk(b"IHDR", &ihdr));
+  png.extend(chunk(b"IDAT", &zlib(&[0; 64])));
+  png.extend(chunk(b"IEND", &[]));
+
+  let result = PNGImage::read_bytes(&png);
+  assert!(matches!(result, Err(RSMError::LimitExceeded)));
+}
+
+#[test]
+fn test_allocation_limit() {
+  let png: Vec<u8> = grey_png(4, &[&[0u8; 4] as &[u8]; 4], &[], &[]);
+  let limits: Limits = Limits {
+    max_alloc: 32,
+    ..Limits::default()
+  };
+
+  assert!(PNGImage::read_bytes(&png).is_ok());
+  let result = PNGImage::read_bytes_with(&png, DecodeOptions::new().limits(limits));
+  assert!(matches!(result, Err(RSMError::LimitExceeded)));
+}
+
+#[test]
+fn test_chunk_limits() {
+  let texts: Vec<Vec<u8>> = (0..8).map(|_| chunk(b"tEXt", b"Comment\0rsm")).collect();
+  let png: Vec<u8> = grey_png(1, &[&[0]], &texts, &[]);
+
+  let count: Limits = Limits {
+    max_chunks: 8,
+    ..Limits::default()
+  };
+  let result = PNGImage::read_bytes_with(&png, DecodeOptions::new().limits(count));
+  assert!(matches!(result, Err(RSMError::LimitExceeded)));
+
+  let length: Limits = Limits {
+    max_chunk_length: 8,
+    ..Limits::default()
+  };
+  let result = PNGImage::read_bytes_with(&png, DecodeOptions::new().limits(length));
+  assert!(matches!(result, Err(RSMError::LimitExceeded)));
+}
+
+#[test]
+fn test_metadata_limit() {
+  let mut ztxt: Vec<u8> = b"Comment\0\0".to_vec();
+  ztxt.extend(zlib(&[b'a'; 4096]));
+  let png: Vec<u8> = grey_png(1, &[&[0]], &[chunk(b"zTXt", &ztxt)], &[]);
+
+  let limits: Limits = Limits {
+    max_metadata_size: 16,
+    ..Limits::default()
+  };
+  let image = PNGImage::read_bytes_with(&png, DecodeOptions::new().limits(limits)).unwrap();
+
+  assert_eq!(image.warnings[0].chunk, ChunkType::zTXt);
+  assert!(matches!(image.warnings[0].reason, RSMError::LimitExceeded));
+}
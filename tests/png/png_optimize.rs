@@ -0,0 +1,153 @@
+use crate::png::utils::{build_png, chunk};
+use rsm::lib::img::png::{
+  encode::png_filter::FilterStrategy,
+  image::png_image::PNGImage,
+  optimize::png_optimize_options::OptimizeOptions,
+  parse::{
+    chunks::{
+      idat::png_pixel_format::{BitDepthHandling, ColorTarget},
+      ihdr::{png_bit_depth::BitDepth, png_color_type::ColorType},
+    },
+    png_decode_options::DecodeOptions,
+  },
+};
+
+fn lossless() -> DecodeOptions {
+  DecodeOptions::new()
+    .color(ColorTarget::Rgba)
+    .bit_depth(BitDepthHandling::Keep)
+}
+
+/// RGBA sprite sheet drawn with a few colors over a transparent background
+fn sprites() -> Vec<u8> {
+  let colors: [[u8; 4]; 4] = [
+    [0, 0, 0, 0],
+    [230, 40, 40, 255],
+    [40, 40, 230, 255],
+    [250, 250, 250, 128],
+  ];
+  let rows: Vec<Vec<u8>> = (0..64)
+    .map(|y| {
+      (0..64)
+        .flat_map(|x| match (x / 16 + y / 16) % 3 {
+          _ if (x % 16 < 2) || (y % 16 < 2) => colors[0],
+          0 => colors[1],
+          1 => colors[2],
+          _ => colors[3],
+        })
+        .collect()
+    })
+    .collect();
+  let rows: Vec<&[u8]> = rows.iter().map(Vec::as_slice).collect();
+  build_png((64, 8, 6), &rows, &[], &[])
+}
+
+#[test]
+fn test_optimize_palette() {
+  let png: Vec<u8> = sprites();
+  let optimized: Vec<u8> = PNGImage::optimize_bytes(&png).unwrap();
+  assert!(optimized.len() < png.len());
+
+  let original: PNGImage = PNGImage::read_bytes_with(&png, lossless()).unwrap();
+  let image: PNGImage = PNGImage::read_bytes_with(&optimized, lossless()).unwrap();
+  assert_eq!(image.header.color_type, ColorType::IndexedColor);
+  assert_eq!(image.header.bit_depth, BitDepth::D2);
+  assert_eq!(image.data.data, original.data.data);
+}
+
+#[test]
+fn test_optimize_bit_depth() {
+  // 16-bit greyscale whose least significant bytes repeat the most
+  // significant ones, using more than 256 combinations with alpha
+  let rows: Vec<Vec<u8>> = (0..32u16)
+    .map(|y| {
+      (0..32u16)
+        .flat_map(|x| [(x * 8) as u8, (x * 8) as u8, (y * 8) as u8, (y * 8) as u8])
+        .collect()
+    })
+    .collect();
+  let rows: Vec<&[u8]> = rows.iter().map(Vec::as_slice).collect();
+  let png: Vec<u8> = build_png((32, 16, 4), &rows, &[], &[]);
+
+  let options: OptimizeOptions = OptimizeOptions::new()
+    .filters(vec![FilterStrategy::Adaptive])
+    .levels(vec![6, 12]);
+  let optimized: Vec<u8> = PNGImage::optimize_bytes_with(&png, options).unwrap();
+  let image: PNGImage = PNGImage::read_bytes(&optimized).unwrap();
+  assert_eq!(image.header.color_type, ColorType::GreyscaleAlpha);
+  assert_eq!(image.header.bit_depth, BitDepth::D8);
+  assert_eq!(
+    image.data.data,
+    PNGImage::read_bytes(&png).unwrap().data.data
+  );
+
+  // Samples using both bytes keep them
+  let rows: Vec<Vec<u8>> = (0..16u8).map(|y| vec![y, 7 * y, y, 3]).collect();
+  let rows: Vec<&[u8]> = rows.iter().map(Vec::as_slice).collect();
+  let png: Vec<u8> = build_png((2, 16, 0), &rows, &[], &[]);
+
+  let image: PNGImage = PNGImage::read_bytes_with(&png, lossless()).unwrap();
+  let optimized: PNGImage =
+    PNGImage::read_bytes_with(&image.optimize().unwrap(), lossless()).unwrap();
+  assert_eq!(optimized.header.bit_depth, BitDepth::D16);
+  assert_eq!(optimized.data.data, image.data.data);
+}
+
+#[test]
+fn test_optimize_metadata() {
+  let gama: Vec<u8> = chunk(b"gAMA", &45_455u32.to_be_bytes());
+  let text: Vec<u8> = chunk(b"tEXt", b"Comment\0sprites");
+  let png: Vec<u8> = build_png((2, 8, 2), &[&[9; 6], &[9; 6]], &[gama], &[text]);
+
+  let kept: PNGImage = PNGImage::read_bytes(&PNGImage::optimize_bytes(&png).unwrap()).unwrap();
+  assert_eq!(kept.header.color_type, ColorType::Greyscale);
+  assert!(kept.meta.text_entries.is_some());
+
+  let options: OptimizeOptions = OptimizeOptions::new().strip(true);
+  let stripped: Vec<u8> = PNGImage::optimize_bytes_with(&png, options).unwrap();
+  let stripped: PNGImage = PNGImage::read_bytes(&stripped).unwrap();
+  assert_eq!(stripped.meta.gamma, kept.meta.gamma);
+  assert!(stripped.meta.text_entries.is_none());
+  assert_eq!(stripped.data.data, kept.data.data);
+}
+
+#[test]
+fn test_optimize_sample_chunks() {
+  // Black and white pixels become 1-bit greyscale or indices
+  let rows: Vec<Vec<u8>> = (0..16).map(|y| vec![255 * (y % 2); 48]).collect();
+  let rows: Vec<&[u8]> = rows.iter().map(Vec::as_slice).collect();
+  let sbit: Vec<u8> = chunk(b"sBIT", &[5, 5, 5]);
+  let white: Vec<u8> = chunk(b"bKGD", &[0, 255, 0, 255, 0, 255]);
+  let png: Vec<u8> = build_png((16, 8, 2), &rows, &[sbit.clone(), white], &[]);
+
+  let image: PNGImage = PNGImage::read_bytes(&PNGImage::optimize_bytes(&png).unwrap()).unwrap();
+  assert!(image.warnings.is_empty());
+  match image.header.color_type {
+    ColorType::Greyscale => {
+      assert_eq!(image.meta.significant_bits, Some(vec![1]));
+      assert_eq!(image.meta.background_bytes, Some(vec![0, 1]));
+    }
+    color_type => {
+      assert_eq!(color_type, ColorType::IndexedColor);
+      assert_eq!(image.meta.significant_bits, Some(vec![5, 5, 5]));
+      assert_eq!(image.meta.background_bytes, Some(vec![1]));
+    }
+  }
+
+  // A background that is neither grey nor among the pixels is left out
+  let red: Vec<u8> = chunk(b"bKGD", &[0, 255, 0, 0, 0, 0]);
+  let png: Vec<u8> = build_png((16, 8, 2), &rows, &[sbit, red], &[]);
+  let image: PNGImage = PNGImage::read_bytes(&PNGImage::optimize_bytes(&png).unwrap()).unwrap();
+  assert!(image.meta.significant_bits.is_some());
+  assert_eq!(image.meta.background_bytes, None);
+
+  let options: OptimizeOptions = OptimizeOptions::new().strip(true);
+  let stripped: Vec<u8> = PNGImage::optimize_bytes_with(&png, options).unwrap();
+  assert_eq!(
+    PNGImage::read_bytes(&stripped)
+      .unwrap()
+      .meta
+      .significant_bits,
+    None
+  );
+}
@@ -0,0 +1,115 @@
+use crate::png::utils::{build_png, chunk, grey_png};
+use rsm::lib::{
+  img::png::{
+    chunk::png_chunk_type::ChunkType,
+    image::png_image::PNGImage,
+    parse::{
+      chunks::idat::png_pixel_format::{BitDepthHandling, ColorTarget, PixelLayout},
+      png_decode_options::{CrcPolicy, DecodeOptions},
+    },
+  },
+  util::err::rsm_error::RSMError,
+};
+
+/// RGBA pixels with distinct channels: (1, 2, 3, 4) and (5, 6, 7, 8)
+fn rgba_png() -> Vec<u8> {
+  build_png((2, 8, 6), &[&[1, 2, 3, 4, 5, 6, 7, 8]], &[], &[])
+}
+
+#[test]
+fn test_crc_policy() {
+  let mut png: Vec<u8> = grey_png(
+    1,
+    &[&[0]],
+    &[chunk(b"gAMA", &100_000u32.to_be_bytes())],
+    &[],
+  );
+  let gama_crc: usize = 33 + 12;
+  png[gama_crc] ^= 0xff;
+
+  let image: PNGImage = PNGImage::read_bytes(&png).unwrap();
+  assert!(matches!(
+    image.warnings[0].reason,
+    RSMError::InvalidChecksum
+  ));
+  assert!(image.meta.gamma.is_none());
+
+  let ignore: DecodeOptions = DecodeOptions::new().crc(CrcPolicy::Ignore);
+  let image: PNGImage = PNGImage::read_bytes_with(&png, ignore.clone()).unwrap();
+  assert!(image.meta.gamma.is_some());
+
+  // Corrupt the CRC of IHDR
+  png[29] ^= 0xff;
+  let result = PNGImage::read_bytes(&png);
+  assert!(matches!(result, Err(RSMError::InvalidChecksum)));
+  assert!(PNGImage::read_bytes_with(&png, ignore).is_ok());
+}
+
+#[test]
+fn test_ancillary_chunks() {
+  let gama: Vec<u8> = chunk(b"gAMA", &100_000u32.to_be_bytes());
+  let text: Vec<u8> = chunk(b"tEXt", b"Title\0rsm");
+  let png: Vec<u8> = grey_png(1, &[&[0]], &[gama, text], &[]);
+
+  let options: DecodeOptions = DecodeOptions::new().ancillary_chunks([ChunkType::tEXt]);
+  let image: PNGImage = PNGImage::read_bytes_with(&png, options).unwrap();
+
+  assert!(image.meta.gamma.is_none());
+  assert!(image.meta.text_entries.is_some());
+}
+
+#[test]
+fn test_keep_unknown_chunks() {
+  let private: Vec<u8> = chunk(b"prIv", &[1, 2, 3]);
+  let png: Vec<u8> = grey_png(1, &[&[0]], &[private], &[]);
+
+  let image: PNGImage = PNGImage::read_bytes(&png).unwrap();
+  assert!(image.meta.unknown_chunks.is_none());
+
+  let options: DecodeOptions = DecodeOptions::new().keep_unknown_chunks(true);
+  let image: PNGImage = PNGImage::read_bytes_with(&png, options).unwrap();
+  let unknown = &image.meta.unknown_chunks.unwrap()[0];
+
+  assert_eq!(unknown.r#type.as_bytes(), *b"prIv");
+  assert_eq!(unknown.data, [1, 2, 3]);
+}
+
+#[test]
+fn test_color_targets() {
+  let png: Vec<u8> = rgba_png();
+  let decode = |target: ColorTarget| {
+    let options: DecodeOptions = DecodeOptions::new().color(target);
+    PNGImage::read_bytes_with(&png, options).unwrap().data.data
+  };
+
+  assert_eq!(decode(ColorTarget::Rgba), [1, 2, 3, 4, 5, 6, 7, 8]);
+  assert_eq!(decode(ColorTarget::Rgb), [1, 2, 3, 5, 6, 7]);
+  assert_eq!(decode(ColorTarget::GreyAlpha), [2, 4, 6, 8]);
+  assert_eq!(decode(ColorTarget::Grey), [2, 6]);
+}
+
+#[test]
+fn test_planar_layout() {
+  let options: DecodeOptions = DecodeOptions::new().layout(PixelLayout::Planar);
+  let image: PNGImage = PNGImage::read_bytes_with(&rgba_png(), options).unwrap();
+
+  assert_eq!(image.data.layout, PixelLayout::Planar);
+  assert_eq!(image.data.data, [1, 5, 2, 6, 3, 7, 4, 8]);
+}
+
+#[test]
+fn test_bit_depth_handling() {
+  let png: Vec<u8> = build_png((1, 16, 0), &[&[0x12, 0x34]], &[], &[]);
+
+  let image: PNGImage = PNGImage::read_bytes(&png).unwrap();
+  assert_eq!(image.data.bit_depth, 8);
+  assert_eq!(image.data.data, [0x12, 0x12, 0x12, 0xff]);
+
+  let options: DecodeOptions = DecodeOptions::new()
+    .bit_depth(BitDepthHandling::Keep)
+    .color(ColorTarget::GreyAlpha);
+  let image: PNGImage = PNGImage::read_bytes_with(&png, options).unwrap();
+
+  assert_eq!(image.data.bit_depth, 16);
+  assert_eq!(image.data.data, [0x12, 0x34, 0xff, 0xff]);
+}
@@ -0,0 +1,53 @@
+use crate::png::utils::{chunk, grey_png};
+use rsm::lib::img::png::{
+  image::png_image::PNGImage,
+  parse::{chunks::exif::png_exif::PNGExifData, png_decode_options::DecodeOptions},
+};
+
+/// 3x2 greyscale PNG whose EXIF data holds the given orientation
+fn oriented_png(orientation: u16) -> Vec<u8> {
+  let mut exif: PNGExifData = PNGExifData {
+    fields: Vec::new(),
+    thumbnail: None,
+    little_endian: false,
+  };
+  exif.set_orientation(orientation).unwrap();
+
+  let exif: Vec<u8> = chunk(b"eXIf", &exif.to_bytes().unwrap());
+  grey_png(3, &[&[1, 2, 3], &[4, 5, 6]], &[], &[exif])
+}
+
+#[test]
+fn test_apply_orientation() {
+  let options: DecodeOptions = DecodeOptions::new().apply_orientation(true);
+  let expected: [(u16, u32, u32, [u8; 6]); 8] = [
+    (1, 3, 2, [1, 2, 3, 4, 5, 6]),
+    (2, 3, 2, [3, 2, 1, 6, 5, 4]),
+    (3, 3, 2, [6, 5, 4, 3, 2, 1]),
+    (4, 3, 2, [4, 5, 6, 1, 2, 3]),
+    (5, 2, 3, [1, 4, 2, 5, 3, 6]),
+    (6, 2, 3, [4, 1, 5, 2, 6, 3]),
+    (7, 2, 3, [6, 3, 5, 2, 4, 1]),
+    (8, 2, 3, [3, 6, 2, 5, 1, 4]),
+  ];
+
+  for (orientation, width, height, grey) in expected {
+    let png: Vec<u8> = oriented_png(orientation);
+    let image: PNGImage = PNGImage::read_bytes_with(&png, options.clone()).unwrap();
+
+    assert_eq!((image.data.width, image.data.height), (width, height));
+    assert_eq!((*image.header.width, *image.header.height), (width, height));
+    let red: Vec<u8> = image.data.data.chunks_exact(4).map(|p| p[0]).collect();
+    assert_eq!(red, grey);
+    assert_eq!(image.meta.exif.unwrap().orientation(), Some(1));
+  }
+}
+
+#[test]
+fn test_orientation_is_opt_in() {
+  let image: PNGImage = PNGImage::read_bytes(&oriented_png(6)).unwrap();
+
+  assert_eq!((image.data.width, image.data.height), (3, 2));
+  assert_eq!((*image.header.width, *image.header.height), (3, 2));
+  assert_eq!(image.meta.exif.unwrap().orientation(), Some(6));
+}
@@ -0,0 +1,212 @@
+use crate::png::utils::{build_png_split, chunk};
+use miniz_oxide::{
+  DataFormat, MZFlush,
+  deflate::{core::CompressorOxide, stream::deflate},
+};
+use rsm::lib::{
+  img::png::{
+    encode::png_encode_options::EncodeOptions,
+    image::png_image::PNGImage,
+    parse::{
+      chunks::{idat::png_pixel_format::ColorTarget, idot::png_image_division::ImageDivision},
+      png_decode_options::DecodeOptions,
+      png_limits::Limits,
+    },
+  },
+  util::err::rsm_error::RSMError,
+};
+
+const SIGNATURE: [u8; 8] = [0x89, 0x50, 0x4e, 0x47, 0xd, 0xa, 0x1a, 0xa];
+
+/// Filtered scanlines of an 8-bit RGB image, using every filter type
+fn scanlines(width: u32, height: u32, interlaced: bool) -> Vec<u8> {
+  // Width and height of the Adam7 passes, or of the whole image
+  let passes: Vec<(u32, u32)> = match interlaced {
+    true => [(0, 0, 8, 8), (4, 0, 8, 8), (0, 4, 4, 8), (2, 0, 4, 4)]
+      .into_iter()
+      .chain([(0, 2, 2, 4), (1, 0, 2, 2), (0, 1, 1, 2)])
+      .map(|(x, y, dx, dy)| ((width + dx - 1 - x) / dx, (height + dy - 1 - y) / dy))
+      .collect(),
+    false => vec![(width, height)],
+  };
+
+  let mut state: u32 = 0x9e37_79b9;
+  let mut data: Vec<u8> = Vec::new();
+  for (pass_width, pass_height) in passes.into_iter().filter(|&(w, h)| w > 0 && h > 0) {
+    for row in 0..pass_height {
+      data.push((row % 5) as u8);
+      for _ in 0..pass_width * 3 {
+        state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        data.push((state >> 28) as u8);
+      }
+    }
+  }
+  data
+}
+
+/// Build a PNG whose compressed data is split into `IDAT` chunks of the given
+/// size
+fn png(width: u32, height: u32, interlaced: bool, size: usize) -> Vec<u8> {
+  let header: (u32, u32, u8, u8, u8) = (width, height, 8, 2, interlaced as u8);
+  build_png_split(
+    header,
+    &scanlines(width, height, interlaced),
+    size,
+    &[],
+    &[],
+  )
+}
+
+fn decode(png: &[u8], threads: usize) -> Result<PNGImage, RSMError> {
+  let options: DecodeOptions = DecodeOptions::new()
+    .color(ColorTarget::Rgb)
+    .threads(threads);
+  PNGImage::read_bytes_with(png, options)
+}
+
+#[test]
+fn test_parallel_matches_serial() {
+  for interlaced in [false, true] {
+    for size in [8192, usize::MAX] {
+      let png: Vec<u8> = png(600, 401, interlaced, size);
+      let serial: PNGImage = decode(&png, 1).unwrap();
+
+      for threads in [0, 2, 5] {
+        let parallel: PNGImage = decode(&png, threads).unwrap();
+        assert_eq!(parallel.data.data, serial.data.data);
+      }
+    }
+  }
+}
+
+#[test]
+fn test_parallel_errors() {
+  // Unknown filter type in the last scanline
+  let mut data: Vec<u8> = scanlines(600, 401, false);
+  let last: usize = data.len() - (600 * 3 + 1);
+  data[last] = 5;
+
+  let png: Vec<u8> = build_png_split((600, 401, 8, 2, 0), &data, 8192, &[], &[]);
+  assert!(decode(&png, 4).is_err());
+
+  // Missing end of the compressed data
+  let png: Vec<u8> = self::png(600, 401, false, 8192);
+  let iend: usize = png.len() - 12;
+  let last: usize = png
+    .windows(4)
+    .rposition(|window| window == b"IDAT")
+    .unwrap()
+    - 4;
+  let truncated: Vec<u8> = [&png[..last], &png[iend..]].concat();
+  assert!(matches!(
+    decode(&truncated, 4),
+    Err(RSMError::DecompressionError)
+  ));
+}
+
+/// Build a PNG whose halves are compressed independently and located by an
+/// `iDOT` chunk, as done by Apple. Returns it with the size of the first half.
+fn divided_png(width: u32, height: u32) -> (Vec<u8>, usize) {
+  let data: Vec<u8> = scanlines(width, height, false);
+  let split: usize = 200 * (width as usize * 3 + 1);
+
+  let mut compressor: Box<CompressorOxide> = Box::default();
+  compressor.set_format_and_level(DataFormat::Zlib, 6);
+  let mut compressed: Vec<u8> = vec![0u8; data.len() * 2];
+  let first = deflate(
+    &mut compressor,
+    &data[..split],
+    &mut compressed,
+    MZFlush::Full,
+  );
+  let second = deflate(
+    &mut compressor,
+    &data[split..],
+    &mut compressed[first.bytes_written..],
+    MZFlush::Finish,
+  );
+  compressed.truncate(first.bytes_written + second.bytes_written);
+  let (top, bottom) = compressed.split_at(first.bytes_written);
+
+  // The iDOT chunk follows the IHDR chunk, and is followed by the IDAT chunks
+  let mut idot: Vec<u8> = 2u32.to_be_bytes().to_vec();
+  for value in [0, 200, 40, 200, height - 200, 40 + 12 + top.len() as u32] {
+    idot.extend_from_slice(&value.to_be_bytes());
+  }
+  let mut ihdr: Vec<u8> = width.to_be_bytes().to_vec();
+  ihdr.extend_from_slice(&height.to_be_bytes());
+  ihdr.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+  let mut png: Vec<u8> = SIGNATURE.to_vec();
+  png.extend(chunk(b"IHDR", &ihdr));
+  png.extend(chunk(b"iDOT", &idot));
+  png.extend(chunk(b"IDAT", top));
+  png.extend(chunk(b"IDAT", bottom));
+  png.extend(chunk(b"IEND", &[]));
+  (png, top.len())
+}
+
+#[test]
+fn test_image_divisions() {
+  let (png, top) = divided_png(600, 401);
+
+  let image: PNGImage = decode(&png, 2).unwrap();
+  assert_eq!(
+    image.meta.image_divisions.unwrap()[1],
+    ImageDivision {
+      first_row: 200,
+      rows: 201,
+      offset: 73 + 12 + top,
+    }
+  );
+  assert!(image.warnings.is_empty());
+  assert_eq!(image.data.data, decode(&png, 1).unwrap().data.data);
+}
+
+#[test]
+fn test_parallel_limits() {
+  // The scanlines (401 × 1801 bytes) and the canvas (600 × 401 × 3 bytes) are
+  // needed in any case, and the segments take as much as the scanlines
+  let (png, _) = divided_png(600, 401);
+  let decode = |max_alloc: u64| {
+    let limits: Limits = Limits {
+      max_alloc,
+      ..Limits::default()
+    };
+    let options: DecodeOptions = DecodeOptions::new()
+      .color(ColorTarget::Rgb)
+      .threads(2)
+      .limits(limits);
+    PNGImage::read_bytes_with(&png, options)
+  };
+
+  // Below the room for the segments, the data is inflated on a single thread
+  let serial: PNGImage = self::decode(&png, 1).unwrap();
+  for max_alloc in [2_166_202, 2_166_201, 1_444_001] {
+    assert_eq!(decode(max_alloc).unwrap().data.data, serial.data.data);
+  }
+  assert!(matches!(decode(1_444_000), Err(RSMError::LimitExceeded)));
+}
+
+#[test]
+fn test_parallel_encoding() {
+  // Large enough to be compressed in several groups
+  let image: PNGImage = decode(&png(800, 500, true, usize::MAX), 1).unwrap();
+
+  let serial: Vec<u8> = image.write_bytes().unwrap();
+  let parallel: Vec<u8> = image
+    .write_bytes_with(EncodeOptions::new().threads(3))
+    .unwrap();
+  assert_ne!(parallel, serial);
+  assert_eq!(
+    parallel,
+    image
+      .write_bytes_with(EncodeOptions::new().threads(8))
+      .unwrap()
+  );
+
+  for threads in [1, 4] {
+    let decoded: PNGImage = decode(&parallel, threads).unwrap();
+    assert_eq!(decoded.data.data, image.data.data);
+  }
+}
@@ -0,0 +1,128 @@
+use crate::png::utils::{build_png, chunk};
+use rsm::lib::{
+  img::png::{
+    encode::png_encode_options::EncodeOptions,
+    image::png_image::PNGImage,
+    parse::chunks::{
+      ihdr::{png_bit_depth::BitDepth, png_color_type::ColorType},
+      trns::png_transparency::Transparency,
+    },
+    quantize::{
+      png_dithering::Dithering, png_quantize_options::QuantizeOptions, png_quantizer::Quantizer,
+    },
+  },
+  util::err::rsm_error::RSMError,
+};
+
+/// 48x48 RGBA gradient fading out towards the bottom
+fn gradient() -> PNGImage {
+  let rows: Vec<Vec<u8>> = (0..48u8)
+    .map(|y| {
+      (0..48u8)
+        .flat_map(|x| [x * 5, y * 5, 255 - x * 5, 255 - y * 2])
+        .collect()
+    })
+    .collect();
+  let rows: Vec<&[u8]> = rows.iter().map(Vec::as_slice).collect();
+  PNGImage::read_bytes(&build_png((48, 8, 6), &rows, &[], &[])).unwrap()
+}
+
+#[test]
+fn test_quantize() {
+  for quantizer in [Quantizer::MedianCut, Quantizer::KMeans, Quantizer::Octree] {
+    for dithering in [Dithering::None, Dithering::FloydSteinberg, Dithering::Bayer] {
+      let options: QuantizeOptions = QuantizeOptions::new()
+        .quantizer(quantizer)
+        .dithering(dithering)
+        .max_colors(64);
+      let image: PNGImage = gradient().quantize(options).unwrap();
+
+      let palette_size: usize = image.meta.palette.as_ref().unwrap().len();
+      assert!(palette_size <= 64 && palette_size > 16);
+      assert_eq!(image.header.color_type, ColorType::IndexedColor);
+      assert!(matches!(
+        image.meta.transparency,
+        Some(Transparency::PaletteAlpha(_))
+      ));
+
+      // The quantized colors are written as indices when requested
+      let bytes: Vec<u8> = image
+        .write_bytes_with(EncodeOptions::new().indexed(true))
+        .unwrap();
+      let written: PNGImage = PNGImage::read_bytes(&bytes).unwrap();
+      assert_eq!(written.header.color_type, ColorType::IndexedColor);
+      assert_eq!(written.header.bit_depth, BitDepth::D8);
+      assert_eq!(written.meta.palette, image.meta.palette);
+      assert_eq!(written.data.data, image.data.data);
+    }
+  }
+}
+
+#[test]
+fn test_quality() {
+  // Few colors are kept exactly
+  let rows: [&[u8]; 2] = [&[1, 2, 3, 255, 9, 9, 9, 0], &[200, 0, 0, 255, 1, 2, 3, 255]];
+  let png: Vec<u8> = build_png((2, 8, 6), &rows, &[], &[]);
+  let image: PNGImage = PNGImage::read_bytes(&png).unwrap();
+  let quantized: PNGImage = PNGImage::read_bytes(&png)
+    .unwrap()
+    .quantize(QuantizeOptions::new())
+    .unwrap();
+  assert_eq!(quantized.meta.palette.as_ref().unwrap().len(), 3);
+  assert_eq!(quantized.header.bit_depth, BitDepth::D2);
+  assert_eq!(
+    quantized.data.data,
+    [1, 2, 3, 255, 0, 0, 0, 0, 200, 0, 0, 255, 1, 2, 3, 255]
+  );
+  assert_eq!(
+    quantized.meta.transparency,
+    Some(Transparency::PaletteAlpha(vec![0]))
+  );
+  assert_eq!(image.data.data.len(), quantized.data.data.len());
+
+  // A lower quality needs fewer colors
+  let sizes: Vec<usize> = [100, 80, 50]
+    .into_iter()
+    .map(|quality| {
+      let options: QuantizeOptions = QuantizeOptions::new()
+        .quality(quality)
+        .quantizer(Quantizer::MedianCut)
+        .dithering(Dithering::None);
+      let image: PNGImage = gradient().quantize(options).unwrap();
+      image.meta.palette.unwrap().len()
+    })
+    .collect();
+  assert_eq!(sizes[0], 256);
+  assert!(sizes[1] < sizes[0] && sizes[2] < sizes[1], "{sizes:?}");
+
+  for options in [
+    QuantizeOptions::new().quality(101),
+    QuantizeOptions::new().max_colors(0),
+    QuantizeOptions::new().max_colors(257),
+  ] {
+    assert!(matches!(
+      gradient().quantize(options),
+      Err(RSMError::OutOfBounds)
+    ));
+  }
+}
+
+#[test]
+fn test_write_palette() {
+  // Indexed images are written with their palette again when requested
+  let plte: Vec<u8> = chunk(b"PLTE", &[0, 0, 0, 255, 0, 0, 0, 0, 255]);
+  let png: Vec<u8> = build_png((4, 2, 3), &[&[0b0001_1000], &[0b1001_0000]], &[plte], &[]);
+  let image: PNGImage = PNGImage::read_bytes(&png).unwrap();
+
+  let written: PNGImage = PNGImage::read_bytes(&image.write_bytes().unwrap()).unwrap();
+  assert_ne!(written.header.color_type, ColorType::IndexedColor);
+  assert_eq!(written.data.data, image.data.data);
+
+  let bytes: Vec<u8> = image
+    .write_bytes_with(EncodeOptions::new().indexed(true))
+    .unwrap();
+  let written: PNGImage = PNGImage::read_bytes(&bytes).unwrap();
+  assert_eq!(written.header.color_type, ColorType::IndexedColor);
+  assert_eq!(written.header.bit_depth, BitDepth::D2);
+  assert_eq!(written.data.data, image.data.data);
+}
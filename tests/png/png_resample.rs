@@ -0,0 +1,42 @@
+use crate::png::utils::build_png;
+use rsm::lib::img::png::{
+  image::{png_image::PNGImage, resample::png_resample_filter::ResampleFilter},
+  parse::{
+    chunks::idat::png_pixel_format::{BitDepthHandling, ColorTarget},
+    png_decode_options::DecodeOptions,
+  },
+};
+
+#[test]
+fn test_thumbnail() {
+  let rows: Vec<Vec<u8>> = (0..120u32)
+    .map(|y| (0..300u32).flat_map(|x| [x as u8, y as u8, 90]).collect())
+    .collect();
+  let rows: Vec<&[u8]> = rows.iter().map(Vec::as_slice).collect();
+  let mut image: PNGImage = PNGImage::read_bytes(&build_png((300, 8, 2), &rows, &[], &[])).unwrap();
+
+  image.data.thumbnail(64, 64).unwrap();
+  assert_eq!((image.data.width, image.data.height), (64, 26));
+
+  let written: PNGImage = PNGImage::read_bytes(&image.write_bytes().unwrap()).unwrap();
+  assert_eq!((*written.header.width, *written.header.height), (64, 26));
+  assert_eq!(written.data.data, image.data.data);
+}
+
+#[test]
+fn test_resize_keeps_format() {
+  let rows: Vec<Vec<u8>> = (0..4u8)
+    .map(|y| vec![y * 60, 0, 255 - y * 60, 255])
+    .collect();
+  let rows: Vec<&[u8]> = rows.iter().map(Vec::as_slice).collect();
+  let options: DecodeOptions = DecodeOptions::new()
+    .color(ColorTarget::Grey)
+    .bit_depth(BitDepthHandling::Keep);
+  let mut image: PNGImage =
+    PNGImage::read_bytes_with(&build_png((2, 16, 0), &rows, &[], &[]), options).unwrap();
+
+  image.data.resize(3, 8, ResampleFilter::CatmullRom).unwrap();
+  assert_eq!(image.data.color, ColorTarget::Grey);
+  assert_eq!(image.data.bit_depth, 16);
+  assert_eq!(image.data.data.len(), 3 * 8 * 2);
+}
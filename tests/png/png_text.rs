@@ -0,0 +1,88 @@
+use crate::png::utils::{chunk, grey_png, zlib};
+use rsm::lib::img::png::{
+  image::png_image::PNGImage,
+  parse::chunks::{text::png_text::Text, xmp::png_xmp::PNGXmpData},
+};
+
+#[test]
+fn test_compressed_text() {
+  let content: String = "rsm ".repeat(4096);
+
+  let mut ztxt: Vec<u8> = b"Comment\0\0".to_vec();
+  ztxt.extend(zlib(content.as_bytes()));
+
+  let mut itxt: Vec<u8> = b"Title\0\x01\0fr\0Titre\0".to_vec();
+  itxt.extend(zlib("Été".as_bytes()));
+
+  let chunks: [Vec<u8>; 2] = [chunk(b"zTXt", &ztxt), chunk(b"iTXt", &itxt)];
+  let image: PNGImage = PNGImage::read_bytes(&grey_png(1, &[&[0]], &chunks, &[])).unwrap();
+  let entries: Vec<Text> = image.meta.text_entries.unwrap();
+
+  assert!(image.warnings.is_empty());
+  assert_eq!(entries[0], Text::compressed("Comment", &content).unwrap());
+  assert_eq!(
+    entries[1],
+    Text::international("Title", "fr", "Titre", "Été", true).unwrap()
+  );
+}
+
+#[test]
+fn test_xmp() {
+  let mut xmp: PNGXmpData = PNGXmpData::new();
+  xmp.set_title("Sunset");
+  xmp.set_creators(&["Jane Doe"]);
+
+  let chunks: [Vec<u8>; 2] = [
+    chunk(b"iTXt", &xmp.to_itxt()),
+    chunk(b"tEXt", b"Comment\0rsm"),
+  ];
+  let image: PNGImage = PNGImage::read_bytes(&grey_png(1, &[&[0]], &chunks, &[])).unwrap();
+
+  assert!(image.warnings.is_empty());
+  assert_eq!(image.meta.xmp.unwrap().properties, xmp.properties);
+  assert_eq!(image.meta.text_entries.unwrap().len(), 1);
+}
+
+#[test]
+fn test_invalid_text() {
+  let chunks: [Vec<u8>; 3] = [
+    chunk(b"zTXt", &[&b"\0\0"[..], &zlib(b"rsm")].concat()),
+    chunk(b"tEXt", b" Title\0rsm"),
+    chunk(b"tEXt", b"Title\0r\x07sm"),
+  ];
+  let image: PNGImage = PNGImage::read_bytes(&grey_png(1, &[&[0]], &chunks, &[])).unwrap();
+
+  assert_eq!(image.warnings.len(), 3);
+  assert!(image.meta.text_entries.is_none());
+}
+
+#[test]
+fn test_text_round_trip() {
+  let text: Vec<u8> = chunk(b"tEXt", b"Copyright\0\xa9 rsm\ncaf\xe9");
+  let image: PNGImage =
+    PNGImage::read_bytes(&grey_png(1, &[&[0]], std::slice::from_ref(&text), &[])).unwrap();
+
+  let written: Vec<u8> = image.write_bytes().unwrap();
+  assert!(written.windows(text.len()).any(|window| window == text));
+  assert_eq!(
+    image.meta.text_entries.unwrap(),
+    [Text::new("Copyright", "© rsm\ncafé").unwrap()]
+  );
+}
+
+#[test]
+fn test_registered_keywords() {
+  let mut image: PNGImage = PNGImage::read_bytes(&grey_png(1, &[&[0]], &[], &[])).unwrap();
+  image.meta.set_title("Ωmega").unwrap();
+  image.meta.set_description(&"rsm ".repeat(512)).unwrap();
+  image.meta.set_software("rsm").unwrap();
+
+  let written: PNGImage = PNGImage::read_bytes(&image.write_bytes().unwrap()).unwrap();
+  let entries: Vec<Text> = written.meta.text_entries.clone().unwrap();
+
+  assert!(matches!(entries[0], Text::InternationalText { .. }));
+  assert!(matches!(entries[1], Text::CompressedText(..)));
+  assert!(matches!(entries[2], Text::Text(..)));
+  assert_eq!(written.meta.title(), Some("Ωmega"));
+  assert_eq!(written.meta.software(), Some("rsm"));
+}
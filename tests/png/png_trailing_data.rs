@@ -0,0 +1,34 @@
+use crate::png::utils::grey_png;
+use rsm::lib::img::png::{
+  image::png_image::PNGImage, parse::states::data::png_trailing_data::TrailingPayload,
+};
+
+#[test]
+fn test_no_trailing_data() {
+  let png: Vec<u8> = grey_png(1, &[&[0]], &[], &[]);
+  assert!(PNGImage::read_bytes(&png).unwrap().trailing_data.is_none());
+}
+
+#[test]
+fn test_appended_zip() {
+  let png: Vec<u8> = grey_png(1, &[&[0]], &[], &[]);
+  let mut polyglot: Vec<u8> = png.clone();
+  polyglot.extend_from_slice(b"PK\x03\x04\x14\0\0\0\0\0");
+
+  let image: PNGImage = PNGImage::read_bytes(&polyglot).unwrap();
+  let trailing = image.trailing_data.unwrap();
+
+  assert_eq!(trailing.offset, png.len());
+  assert_eq!(trailing.length, 10);
+  assert_eq!(trailing.payload, TrailingPayload::Zip);
+  assert_eq!(trailing.strip(&polyglot), png);
+}
+
+#[test]
+fn test_appended_png() {
+  let png: Vec<u8> = grey_png(1, &[&[0]], &[], &[]);
+  let polyglot: Vec<u8> = [png.as_slice(), png.as_slice()].concat();
+
+  let image: PNGImage = PNGImage::read_bytes(&polyglot).unwrap();
+  assert_eq!(image.trailing_data.unwrap().payload, TrailingPayload::Png);
+}
@@ -0,0 +1,74 @@
+use crate::png::utils::build_png;
+use rsm::lib::{
+  img::png::{
+    image::{
+      png_image::PNGImage, resample::png_resample_filter::ResampleFilter,
+      transform::png_affine::Affine,
+    },
+    parse::chunks::idat::png_pixel_format::ColorTarget,
+  },
+  util::err::rsm_error::RSMError,
+};
+
+/// 8x8 RGB image where each pixel holds its coordinates
+fn coordinates() -> PNGImage {
+  let rows: Vec<Vec<u8>> = (0..8u8)
+    .map(|y| (0..8u8).flat_map(|x| [x, y, 0]).collect())
+    .collect();
+  let rows: Vec<&[u8]> = rows.iter().map(Vec::as_slice).collect();
+  PNGImage::read_bytes(&build_png((8, 8, 2), &rows, &[], &[])).unwrap()
+}
+
+#[test]
+fn test_crop_and_pad() {
+  // A sprite is cut out, then given a border
+  let mut image: PNGImage = coordinates();
+  image.data.crop(2, 3, 2, 1).unwrap();
+  assert_eq!(image.data.to_rgba8(), [[2, 3, 0, 255], [3, 3, 0, 255]]);
+  image.data.pad(1, 1, 0, 0, [255, 0, 0, 128]).unwrap();
+  assert_eq!(
+    image.data.to_rgba8(),
+    [
+      [255, 0, 0, 128],
+      [255, 0, 0, 128],
+      [255, 0, 0, 128],
+      [2, 3, 0, 255],
+      [3, 3, 0, 255],
+      [255, 0, 0, 128]
+    ]
+  );
+
+  let written: PNGImage = PNGImage::read_bytes(&image.write_bytes().unwrap()).unwrap();
+  assert_eq!((*written.header.width, *written.header.height), (3, 2));
+  assert_eq!(written.data.data, image.data.data);
+
+  assert!(matches!(
+    image.data.crop(0, 0, 4, 1),
+    Err(RSMError::OutOfBounds)
+  ));
+}
+
+#[test]
+fn test_warp() {
+  let mut image: PNGImage = coordinates();
+  let transform: Affine = Affine::scale(0.5, 0.5).then(Affine::shear(0.25, 0.0));
+  image
+    .data
+    .warp(&transform, 6, 4, ResampleFilter::Mitchell, [0; 4])
+    .unwrap();
+  assert_eq!(image.data.color, ColorTarget::Rgba);
+  assert_eq!(image.data.data.len(), 6 * 4 * 4);
+
+  // Rotating by any angle enlarges the image to hold every pixel
+  let mut image: PNGImage = coordinates();
+  image
+    .data
+    .rotate(45.0, ResampleFilter::Lanczos3, [0; 4])
+    .unwrap();
+  assert_eq!((image.data.width, image.data.height), (12, 12));
+  assert_eq!(image.data.to_rgba8()[0], [0; 4]);
+
+  let written: PNGImage = PNGImage::read_bytes(&image.write_bytes().unwrap()).unwrap();
+  assert_eq!((*written.header.width, *written.header.height), (12, 12));
+  assert_eq!(written.data.data, image.data.data);
+}
@@ -0,0 +1,86 @@
+use crate::png::utils::{build_png, chunk};
+use rsm::lib::img::png::{
+  image::png_image::PNGImage,
+  parse::{
+    chunks::{
+      idat::png_pixel_format::{BitDepthHandling, ColorTarget},
+      trns::png_transparency::Transparency,
+    },
+    png_decode_options::DecodeOptions,
+  },
+};
+
+fn rgba16() -> DecodeOptions {
+  DecodeOptions::new()
+    .color(ColorTarget::Rgba)
+    .bit_depth(BitDepthHandling::Keep)
+}
+
+#[test]
+fn test_16_bit_rgb_key() {
+  // Both pixels share the low bytes of the key, but only the first matches it
+  let trns: Vec<u8> = chunk(b"tRNS", &[0x01, 0x10, 0x02, 0x20, 0x03, 0x30]);
+  let row: [u8; 12] = [
+    0x01, 0x10, 0x02, 0x20, 0x03, 0x30, //
+    0xff, 0x10, 0x02, 0x20, 0x03, 0x30,
+  ];
+  let png: Vec<u8> = build_png((2, 16, 2), &[&row], &[trns], &[]);
+  let image: PNGImage = PNGImage::read_bytes_with(&png, rgba16()).unwrap();
+
+  assert_eq!(
+    image.meta.transparency,
+    Some(Transparency::RgbKey(0x0110, 0x0220, 0x0330))
+  );
+  assert_eq!(&image.data.data[6..8], [0, 0]);
+  assert_eq!(&image.data.data[14..16], [0xff, 0xff]);
+}
+
+#[test]
+fn test_16_bit_grey_key() {
+  let trns: Vec<u8> = chunk(b"tRNS", &[0x12, 0x34]);
+  let png: Vec<u8> = build_png((2, 16, 0), &[&[0x12, 0x34, 0x00, 0x34]], &[trns], &[]);
+  let image: PNGImage = PNGImage::read_bytes_with(&png, rgba16()).unwrap();
+
+  assert_eq!(&image.data.data[6..8], [0, 0]);
+  assert_eq!(&image.data.data[14..16], [0xff, 0xff]);
+}
+
+#[test]
+fn test_low_bit_depth_grey_key() {
+  let trns: Vec<u8> = chunk(b"tRNS", &[0, 2]);
+  let png: Vec<u8> = build_png((4, 2, 0), &[&[0b10_01_10_11]], &[trns], &[]);
+  let image: PNGImage =
+    PNGImage::read_bytes_with(&png, DecodeOptions::new().color(ColorTarget::Rgba)).unwrap();
+  let alpha: Vec<u8> = image
+    .data
+    .data
+    .chunks_exact(4)
+    .map(|pixel| pixel[3])
+    .collect();
+
+  assert_eq!(alpha, [0, 255, 0, 255]);
+}
+
+#[test]
+fn test_palette_alpha() {
+  let plte: Vec<u8> = chunk(b"PLTE", &[255, 0, 0, 0, 255, 0]);
+  let trns: Vec<u8> = chunk(b"tRNS", &[64]);
+  let png: Vec<u8> = build_png((2, 8, 3), &[&[0, 1]], &[plte.clone(), trns], &[]);
+  let image: PNGImage =
+    PNGImage::read_bytes_with(&png, DecodeOptions::new().color(ColorTarget::Rgba)).unwrap();
+
+  assert_eq!(image.data.data, [255, 0, 0, 64, 0, 255, 0, 255]);
+  let palette = image.meta.palette.unwrap();
+  assert_eq!(
+    palette.to_rgba(image.meta.transparency.as_ref()),
+    [[255, 0, 0, 64], [0, 255, 0, 255]]
+  );
+
+  // More alpha values than palette entries
+  let trns: Vec<u8> = chunk(b"tRNS", &[64, 64, 64]);
+  let png: Vec<u8> = build_png((2, 8, 3), &[&[0, 1]], &[plte, trns], &[]);
+  let image: PNGImage = PNGImage::read_bytes(&png).unwrap();
+
+  assert_eq!(image.warnings.len(), 1);
+  assert!(image.meta.transparency.is_none());
+}
@@ -0,0 +1,58 @@
+use crate::png::utils::{chunk, grey_png};
+use rsm::lib::img::png::{
+  chunk::png_chunk_type::ChunkType, image::png_image::PNGImage,
+  parse::png_decode_options::DecodeOptions,
+};
+
+#[test]
+fn test_ancillary_errors_as_warnings() {
+  let chrm: Vec<u8> = chunk(b"cHRM", &[0; 12]);
+  let text: Vec<u8> = chunk(b"tEXt", b"Title\0rsm");
+  let png: Vec<u8> = grey_png(1, &[&[127]], &[chrm], &[text]);
+
+  let image: PNGImage = PNGImage::read_bytes(&png).unwrap();
+  assert_eq!(image.warnings.len(), 1);
+
+  let warning = &image.warnings[0];
+  assert_eq!(warning.chunk, ChunkType::cHRM);
+  assert_eq!(warning.offset, 33);
+  assert!(image.meta.chromaticities.is_none());
+  assert!(image.meta.text_entries.is_some());
+}
+
+#[test]
+fn test_strict_ancillary_errors() {
+  let time: Vec<u8> = chunk(b"tIME", &[0; 3]);
+  let png: Vec<u8> = grey_png(1, &[&[127]], &[], &[time]);
+
+  assert!(PNGImage::read_bytes(&png).is_ok());
+  assert!(PNGImage::read_bytes_with(&png, DecodeOptions::new().strict(true)).is_err());
+}
+
+#[test]
+fn test_missing_iend() {
+  let mut png: Vec<u8> = grey_png(1, &[&[127]], &[], &[chunk(b"tEXt", b"A\0b")]);
+  png.truncate(png.len() - 12);
+
+  let image: PNGImage = PNGImage::read_bytes(&png).unwrap();
+  assert_eq!(image.warnings[0].chunk, ChunkType::IEND);
+  assert!(PNGImage::read_bytes_with(&png, DecodeOptions::new().strict(true)).is_err());
+}
+
+#[test]
+fn test_unknown_physical_unit() {
+  let phys: Vec<u8> = chunk(b"pHYs", &[0, 0, 0, 1, 0, 0, 0, 1, 2]);
+  let image: PNGImage = PNGImage::read_bytes(&grey_png(1, &[&[127]], &[phys], &[])).unwrap();
+
+  assert_eq!(image.warnings[0].chunk, ChunkType::pHYs);
+  assert!(image.meta.physical_dimensions.is_none());
+}
+
+#[test]
+fn test_invalid_modification_time() {
+  let time: Vec<u8> = chunk(b"tIME", &[0x07, 0xea, 2, 30, 0, 0, 0]);
+  let image: PNGImage = PNGImage::read_bytes(&grey_png(1, &[&[127]], &[time], &[])).unwrap();
+
+  assert_eq!(image.warnings[0].chunk, ChunkType::tIME);
+  assert!(image.meta.modification_time.is_none());
+}
@@ -0,0 +1,198 @@
+use crate::png::utils::{build_png, chunk, grey_png};
+use c2pa::SigningAlg;
+use rsm::lib::img::png::{
+  encode::{
+    png_credentials_signer::CredentialsSigner, png_encode_options::EncodeOptions,
+    png_filter::FilterStrategy,
+  },
+  image::png_image::PNGImage,
+  parse::{
+    chunks::{
+      cabx::png_content_credentials::{ContentCredentials, CredentialState},
+      idat::{
+        png_filters::FilterType,
+        png_pixel_format::{BitDepthHandling, ColorTarget, PixelLayout},
+      },
+      phys::png_physical_dimensions::PhysicalDimensions,
+      text::png_text::Text,
+      time::png_time::ModificationTime,
+    },
+    png_decode_options::DecodeOptions,
+  },
+};
+
+const MANIFEST: &str = r#"{
+  "title": "generated.png",
+  "assertions": [
+    {
+      "label": "c2pa.actions",
+      "data": {
+        "actions": [
+          {
+            "action": "c2pa.created",
+            "digitalSourceType": "http://cv.iptc.org/newscodes/digitalsourcetype/trainedAlgorithmicMedia"
+          }
+        ]
+      }
+    }
+  ]
+}"#;
+
+fn signer() -> CredentialsSigner {
+  CredentialsSigner::from_keys(
+    MANIFEST,
+    include_bytes!("credentials/es256_certs.pem"),
+    include_bytes!("credentials/es256_private.key"),
+    SigningAlg::Es256,
+  )
+  .unwrap()
+}
+
+#[test]
+fn test_write_round_trip() {
+  let text: Vec<u8> = chunk(b"tEXt", b"Comment\0rsm");
+  let gama: Vec<u8> = chunk(b"gAMA", &45_455u32.to_be_bytes());
+  let png: Vec<u8> = build_png(
+    (3, 8, 6),
+    &[&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12], &[12; 12]],
+    &[gama],
+    &[text],
+  );
+  let image: PNGImage = PNGImage::read_bytes(&png).unwrap();
+
+  for filter in [
+    FilterStrategy::Adaptive,
+    FilterStrategy::Fixed(FilterType::Paeth),
+  ] {
+    let options: EncodeOptions = EncodeOptions::new().filter(filter).compression(9);
+    let written: PNGImage =
+      PNGImage::read_bytes(&image.write_bytes_with(options).unwrap()).unwrap();
+
+    assert!(written.warnings.is_empty());
+    assert_eq!(written.data.data, image.data.data);
+    assert_eq!(written.meta.gamma, image.meta.gamma);
+    assert_eq!(
+      written.meta.text_entries.unwrap(),
+      [Text::new("Comment", "rsm").unwrap()]
+    );
+  }
+}
+
+#[test]
+fn test_write_pixel_formats() {
+  let png: Vec<u8> = build_png((2, 16, 0), &[&[0x12, 0x34, 0xab, 0xcd]], &[], &[]);
+  let options: DecodeOptions = DecodeOptions::new()
+    .color(ColorTarget::GreyAlpha)
+    .bit_depth(BitDepthHandling::Keep)
+    .layout(PixelLayout::Planar);
+  let image: PNGImage = PNGImage::read_bytes_with(&png, options.clone()).unwrap();
+
+  let written: Vec<u8> = image.write_bytes().unwrap();
+  let decoded: PNGImage = PNGImage::read_bytes_with(&written, options).unwrap();
+  assert_eq!(decoded.data.data, image.data.data);
+  assert_eq!(decoded.header.bit_depth as u8, 16);
+}
+
+#[test]
+fn test_write_without_metadata() {
+  let png: Vec<u8> = grey_png(1, &[&[7]], &[chunk(b"tEXt", b"Comment\0rsm")], &[]);
+  let image: PNGImage = PNGImage::read_bytes(&png).unwrap();
+
+  let options: EncodeOptions = EncodeOptions::new().metadata(false);
+  let written: PNGImage = PNGImage::read_bytes(&image.write_bytes_with(options).unwrap()).unwrap();
+  assert!(written.meta.text_entries.is_none());
+}
+
+#[test]
+fn test_write_sample_chunks() {
+  // Indexed pixels keep the chunks referring to their palette
+  let before: Vec<Vec<u8>> = vec![
+    chunk(b"sBIT", &[5, 6, 5]),
+    chunk(b"PLTE", &[0, 0, 0, 255, 255, 255]),
+    chunk(b"bKGD", &[1]),
+    chunk(b"hIST", &[0, 10, 0, 20]),
+  ];
+  let png: Vec<u8> = build_png((2, 8, 3), &[&[0, 1], &[1, 1]], &before, &[]);
+  let image: PNGImage = PNGImage::read_bytes(&png).unwrap();
+
+  let options: EncodeOptions = EncodeOptions::new().indexed(true);
+  let indexed: PNGImage = PNGImage::read_bytes(&image.write_bytes_with(options).unwrap()).unwrap();
+  assert!(indexed.warnings.is_empty());
+  assert_eq!(indexed.meta.significant_bits, Some(vec![5, 6, 5]));
+  assert_eq!(indexed.meta.background_bytes, Some(vec![1]));
+  assert_eq!(indexed.meta.histogram, Some(vec![10, 20]));
+
+  // Other pixels get the color of the background, and no histogram
+  let options: DecodeOptions = DecodeOptions::new().color(ColorTarget::Rgba);
+  let image: PNGImage = PNGImage::read_bytes_with(&png, options).unwrap();
+  let rgba: PNGImage = PNGImage::read_bytes(&image.write_bytes().unwrap()).unwrap();
+  assert_eq!(rgba.meta.significant_bits, Some(vec![5, 6, 5, 8]));
+  assert_eq!(
+    rgba.meta.background_bytes,
+    Some(vec![0, 255, 0, 255, 0, 255])
+  );
+  assert_eq!(rgba.meta.histogram, None);
+}
+
+#[test]
+fn test_write_content_credentials() {
+  let image: PNGImage = PNGImage::read_bytes(&grey_png(2, &[&[0, 255]], &[], &[])).unwrap();
+
+  let options: EncodeOptions = EncodeOptions::new().credentials(signer());
+  let signed: Vec<u8> = image.write_bytes_with(options).unwrap();
+  let signed: PNGImage = PNGImage::read_bytes(&signed).unwrap();
+  let credentials: ContentCredentials = signed.meta.content_credentials.unwrap();
+
+  assert!(signed.warnings.is_empty());
+  assert_eq!(credentials.state, CredentialState::Valid);
+  assert!(!credentials.has_hash_mismatch());
+  assert_eq!(
+    credentials.active().unwrap().name.as_deref(),
+    Some("rsm test signer")
+  );
+  assert_eq!(signed.data.data, image.data.data);
+}
+
+#[test]
+fn test_invalid_credentials() {
+  let certificates: &[u8] = include_bytes!("credentials/es256_certs.pem");
+  let key: &[u8] = include_bytes!("credentials/es256_private.key");
+
+  assert!(CredentialsSigner::from_keys("{", certificates, key, SigningAlg::Es256).is_err());
+  assert!(CredentialsSigner::from_keys(MANIFEST, key, certificates, SigningAlg::Es256).is_err());
+}
+
+#[test]
+fn test_write_dpi() {
+  let mut image: PNGImage = PNGImage::read_bytes(&grey_png(1, &[&[0]], &[], &[])).unwrap();
+  image.meta.physical_dimensions = Some(PhysicalDimensions::from_dpi(300.0, 300.0).unwrap());
+
+  let written: PNGImage = PNGImage::read_bytes(&image.write_bytes().unwrap()).unwrap();
+  let dimensions: PhysicalDimensions = written.meta.physical_dimensions.unwrap();
+  let (x, y) = dimensions.dpi().unwrap();
+
+  assert_eq!((dimensions.pp_x, dimensions.pp_y), (11811, 11811));
+  assert_eq!((x.round(), y.round()), (300.0, 300.0));
+}
+
+#[test]
+fn test_write_modification_time() {
+  let time: ModificationTime = ModificationTime::new(2026, 10, 19, 14, 30, 5).unwrap();
+  let image: PNGImage = PNGImage::read_bytes(&grey_png(1, &[&[0]], &[], &[])).unwrap();
+  let fixed = || EncodeOptions::new().modification_time(time);
+
+  let written: Vec<u8> = image.write_bytes_with(fixed()).unwrap();
+  assert_eq!(written, image.write_bytes_with(fixed()).unwrap());
+  let decoded: PNGImage = PNGImage::read_bytes(&written).unwrap();
+  assert_eq!(decoded.meta.modification_time, Some(time));
+  assert_eq!(time.to_string(), "2026-10-19T14:30:05Z");
+
+  let before: ModificationTime = ModificationTime::now();
+  let touched: PNGImage = PNGImage::read_bytes(
+    &decoded
+      .write_bytes_with(EncodeOptions::new().touch())
+      .unwrap(),
+  )
+  .unwrap();
+  assert!(touched.meta.modification_time.unwrap() >= before);
+}
@@ -0,0 +1,75 @@
+use libdeflater::{CompressionLvl, Compressor};
+
+/// Encode a chunk with its length, type and CRC
+pub fn chunk(r#type: &[u8; 4], data: &[u8]) -> Vec<u8> {
+  let mut bytes: Vec<u8> = Vec::with_capacity(data.len() + 12);
+  bytes.extend_from_slice(&(data.len() as u32).to_be_bytes());
+  bytes.extend_from_slice(r#type);
+  bytes.extend_from_slice(data);
+
+  let mut hasher = crc32fast::Hasher::new();
+  hasher.update(r#type);
+  hasher.update(data);
+  bytes.extend_from_slice(&hasher.finalize().to_be_bytes());
+  bytes
+}
+
+/// Compress data using zlib
+pub fn zlib(data: &[u8]) -> Vec<u8> {
+  let mut compressor = Compressor::new(CompressionLvl::default());
+  let mut buffer = vec![0u8; compressor.zlib_compress_bound(data.len())];
+  let size = compressor.zlib_compress(data, &mut buffer).unwrap();
+  buffer.truncate(size);
+  buffer
+}
+
+/// Build a PNG from its header values and unfiltered rows, placing the given
+/// chunks before and after the image data.
+pub fn build_png(
+  (width, bit_depth, color_type): (u32, u8, u8),
+  rows: &[&[u8]],
+  before: &[Vec<u8>],
+  after: &[Vec<u8>],
+) -> Vec<u8> {
+  let mut raw: Vec<u8> = Vec::new();
+  for row in rows {
+    raw.push(0);
+    raw.extend_from_slice(row);
+  }
+
+  let header: (u32, u32, u8, u8, u8) = (width, rows.len() as u32, bit_depth, color_type, 0);
+  build_png_split(header, &raw, usize::MAX, before, after)
+}
+
+/// Build a PNG from its header values (width, height, bit depth, color type
+/// and interlace method) and filtered scanlines, whose compressed data is
+/// split into `IDAT` chunks of the given size, placing the given chunks before
+/// and after the image data.
+pub fn build_png_split(
+  (width, height, bit_depth, color_type, interlace): (u32, u32, u8, u8, u8),
+  scanlines: &[u8],
+  split: usize,
+  before: &[Vec<u8>],
+  after: &[Vec<u8>],
+) -> Vec<u8> {
+  let mut ihdr: Vec<u8> = Vec::new();
+  ihdr.extend_from_slice(&width.to_be_bytes());
+  ihdr.extend_from_slice(&height.to_be_bytes());
+  ihdr.extend_from_slice(&[bit_depth, color_type, 0, 0, interlace]);
+
+  let mut png: Vec<u8> = vec![0x89, 0x50, 0x4e, 0x47, 0xd, 0xa, 0x1a, 0xa];
+  png.extend(chunk(b"IHDR", &ihdr));
+  before.iter().for_each(|c| png.extend(c));
+  for data in zlib(scanlines).chunks(split) {
+    png.extend(chunk(b"IDAT", data));
+  }
+  after.iter().for_each(|c| png.extend(c));
+  png.extend(chunk(b"IEND", &[]));
+  png
+}
+
+/// Build an 8-bit greyscale PNG from unfiltered rows, placing the given
+/// chunks before and after the image data.
+pub fn grey_png(width: u32, rows: &[&[u8]], before: &[Vec<u8>], after: &[Vec<u8>]) -> Vec<u8> {
+  build_png((width, 8, 0), rows, before, after)
+}